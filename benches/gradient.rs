@@ -0,0 +1,81 @@
+//! Gradients with thousands of stops, like one frame per LED per step of an animation.
+//!
+//! `cargo bench --bench gradient`. The `baseline` numbers do the same work the way it used to be
+//! done, growing the `Vec` one push at a time and formatting every color into its own `String`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+use serde::de::DeserializeSeed;
+
+use led_json::{Color, Fill, ParserCache};
+
+const STOPS: usize = 5000;
+
+fn gradient() -> Fill {
+    Fill::Gradient((0..STOPS).map(|i| Color { red: i as u8, green: (i / 7) as u8, blue: (i / 13) as u8 }).collect())
+}
+
+fn deserialize(c: &mut Criterion) {
+    let json = serde_json::to_string(&gradient()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let mut group = c.benchmark_group("deserialize");
+
+    group.bench_function("fill from str", |b| b.iter(|| serde_json::from_str::<Fill>(black_box(&json)).unwrap()));
+
+    // room for every stop, so after the first run everything is a hit
+    let cache = ParserCache::new(STOPS);
+
+    group.bench_function("cached fill from str", |b| {
+        b.iter(|| cache.fill().deserialize(&mut serde_json::Deserializer::from_str(black_box(&json))).unwrap())
+    });
+
+    // `Value` knows its length up front, so this is where the size hint pays off
+    group.bench_function("fill from value", |b| b.iter(|| Fill::deserialize(black_box(&value)).unwrap()));
+
+    group.bench_function("baseline from value", |b| {
+        b.iter(|| {
+            let mut colors = Vec::new();
+
+            for entry in black_box(&value).as_array().unwrap() {
+                colors.push(entry.as_str().unwrap().parse::<Color>().unwrap());
+            }
+
+            Fill::Gradient(colors)
+        })
+    });
+
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let fill = gradient();
+    let colors = match &fill {
+        Fill::Gradient(colors) => colors,
+        _ => unreachable!(),
+    };
+
+    let mut group = c.benchmark_group("serialize");
+    let mut out = Vec::with_capacity(STOPS * 10);
+
+    group.bench_function("fill", |b| {
+        b.iter(|| {
+            out.clear();
+            serde_json::to_writer(&mut out, black_box(&fill)).unwrap();
+        })
+    });
+
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            out.clear();
+
+            let strings: Vec<String> = black_box(colors).iter().map(|c| format!("#{:02x}{:02x}{:02x}", c.red, c.green, c.blue)).collect();
+            serde_json::to_writer(&mut out, &strings).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, deserialize, serialize);
+criterion_main!(benches);
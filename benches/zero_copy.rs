@@ -0,0 +1,83 @@
+//! Parsing a large array of hex colors straight out of an in-memory JSON string.
+//!
+//! `cargo bench --bench zero_copy`. Before timing anything, this counts the allocations one parse
+//! makes and fails if there's more than the growing `Vec` of stops needs, i.e. if any stop went
+//! through an intermediate `String`. The `baseline` does exactly that, through a `Vec<String>`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use led_json::{Color, Fill};
+
+const STOPS: usize = 100_000;
+
+/// Counts every allocation and reallocation on its way to the system allocator.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let value = f();
+
+    (value, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+fn json() -> String {
+    let stops: Vec<Color> = (0..STOPS).map(|i| Color::new(i as u8, (i / 7) as u8, (i / 13) as u8)).collect();
+
+    serde_json::to_string(&Fill::Gradient(stops)).unwrap()
+}
+
+fn zero_copy(c: &mut Criterion) {
+    let json = json();
+
+    let (fill, count) = allocations(|| serde_json::from_str::<Fill>(&json).unwrap());
+
+    assert_eq!(fill.stops().map(<[Color]>::len), Some(STOPS));
+    // JSON arrays don't say how long they are, so the `Vec` doubles its way up to `STOPS`
+    assert!(count <= 2 * (STOPS as f64).log2().ceil() as usize, "{} allocations for {} stops", count, STOPS);
+
+    let (_, baseline) = allocations(|| serde_json::from_str::<Vec<String>>(&json).unwrap());
+
+    assert!(baseline >= STOPS);
+    println!("{} stops: {} allocations, {} through a Vec<String>", STOPS, count, baseline);
+
+    let mut group = c.benchmark_group("zero copy");
+
+    group.bench_function("fill from str", |b| b.iter(|| serde_json::from_str::<Fill>(black_box(&json)).unwrap()));
+
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            let strings: Vec<String> = serde_json::from_str(black_box(&json)).unwrap();
+
+            Fill::Gradient(strings.iter().map(|s| s.parse::<Color>().unwrap()).collect())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, zero_copy);
+criterion_main!(benches);
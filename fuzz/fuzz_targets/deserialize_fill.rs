@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use led_json::Fill;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = serde_json::from_slice::<Fill>(data);
+});
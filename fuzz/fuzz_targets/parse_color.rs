@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use std::str::FromStr;
+
+use led_json::{Color, Fill};
+
+fuzz_target!(|data: &str| {
+    if let Ok(color) = Color::from_str(data) {
+        // anything that parses has to survive a round trip
+        assert_eq!(Color::from_str(&color.to_string()).unwrap(), color);
+    }
+
+    let _ = Fill::from_str(data);
+});
@@ -0,0 +1,353 @@
+//! Sass-style color adjustments, for deriving hover, pressed and disabled variants from a base
+//! color. Each takes `&self` and returns a new `Color` so they can be chained:
+//! `base.darken(0.1).desaturate(0.2)`.
+//!
+//! Amounts are fractions of the full HSL range rather than percentages, and every result is
+//! clamped to a valid color.
+//!
+//! `ColorAdjustments` batches photo-style exposure, contrast, saturation and hue changes so a
+//! config can declare them, and applies to whole fills.
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::lab::{linear_to_srgb, srgb_to_linear};
+
+impl Color {
+    /// Adds `amount` to the HSL lightness. A negative amount darkens.
+    pub fn lighten(&self, amount: f32) -> Color {
+        let hsl = self.to_hsl();
+
+        Color::from_hsl(hsl.hue, hsl.saturation, hsl.lightness + amount)
+    }
+
+    /// Subtracts `amount` from the HSL lightness. A negative amount lightens.
+    pub fn darken(&self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Adds `amount` to the HSL saturation. A negative amount desaturates.
+    pub fn saturate(&self, amount: f32) -> Color {
+        let hsl = self.to_hsl();
+
+        if hsl.saturation + amount <= 0.0 {
+            return self.grayscale()
+        }
+
+        Color::from_hsl(hsl.hue, hsl.saturation + amount, hsl.lightness)
+    }
+
+    /// Subtracts `amount` from the HSL saturation. A negative amount saturates.
+    pub fn desaturate(&self, amount: f32) -> Color {
+        self.saturate(-amount)
+    }
+
+    /// Mixes in `weight` of `other`, channel by channel. The same as `lerp`: `0.0` is `self`,
+    /// `1.0` is `other`, and the weight is clamped.
+    pub fn mix(&self, other: &Color, weight: f32) -> Color {
+        self.lerp(other, weight)
+    }
+
+    /// The negative of the color, `255 - c` for each channel.
+    pub fn invert(&self) -> Color {
+        Color {
+            red: 255 - self.red,
+            green: 255 - self.green,
+            blue: 255 - self.blue,
+        }
+    }
+
+    /// Fully desaturated, keeping the HSL lightness.
+    pub fn grayscale(&self) -> Color {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+
+        // the lightness is the midpoint of the largest and smallest channels, rounded up
+        let gray = (u16::from(max) + u16::from(min)).div_ceil(2) as u8;
+
+        Color { red: gray, green: gray, blue: gray }
+    }
+}
+
+/// Post-processing for a whole fill, declared next to it in a config, like
+/// `{ "exposure": 0.5, "hue_shift": 30.0 }`. Every field is optional and defaults to leaving the
+/// color alone.
+///
+/// The steps run in the order of the fields: exposure in linear light, contrast on the
+/// gamma-encoded channels, then saturation and hue in HSL.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorAdjustments {
+    /// In stops: each `1.0` doubles the light and each `-1.0` halves it.
+    pub exposure: f32,
+
+    /// Scales each channel's distance from the middle, so `0.0` is flat gray and `2.0` doubles
+    /// the contrast.
+    pub contrast: f32,
+
+    /// Scales the HSL saturation, so `0.0` is grayscale.
+    pub saturation: f32,
+
+    /// Degrees added to the hue.
+    pub hue_shift: f32,
+}
+
+impl ColorAdjustments {
+    /// Changes nothing.
+    pub const NONE: ColorAdjustments = ColorAdjustments { exposure: 0.0, contrast: 1.0, saturation: 1.0, hue_shift: 0.0 };
+}
+
+impl Default for ColorAdjustments {
+    fn default() -> Self {
+        ColorAdjustments::NONE
+    }
+}
+
+impl Color {
+    /// Applies every step of `adjustments`, clamping the result to a valid color. Steps left at
+    /// their defaults are skipped, so they can't shift the color through rounding.
+    ///
+    /// ```
+    /// use led_json::{Color, ColorAdjustments};
+    ///
+    /// let brighter = ColorAdjustments { exposure: 1.0, ..ColorAdjustments::NONE };
+    ///
+    /// assert_eq!(Color::new(0, 0, 0).adjust(&brighter), Color::new(0, 0, 0));
+    /// assert_eq!(Color::new(128, 128, 128).adjust(&brighter), Color::new(176, 176, 176));
+    /// ```
+    pub fn adjust(&self, adjustments: &ColorAdjustments) -> Color {
+        let mut color = *self;
+
+        if adjustments.exposure != 0.0 {
+            let gain = 2f64.powf(f64::from(adjustments.exposure));
+            let expose = |c: u8| linear_to_srgb(srgb_to_linear(c) * gain);
+
+            color = Color { red: expose(color.red), green: expose(color.green), blue: expose(color.blue) };
+        }
+
+        if adjustments.contrast != 1.0 {
+            let stretch = |c: u8| {
+                let c = (f32::from(c) / 255.0 - 0.5) * adjustments.contrast + 0.5;
+
+                (c.clamp(0.0, 1.0) * 255.0).round() as u8
+            };
+
+            color = Color { red: stretch(color.red), green: stretch(color.green), blue: stretch(color.blue) };
+        }
+
+        if adjustments.saturation != 1.0 || adjustments.hue_shift != 0.0 {
+            let hsl = color.to_hsl();
+
+            color = Color::from_hsl(hsl.hue + adjustments.hue_shift, hsl.saturation * adjustments.saturation, hsl.lightness);
+        }
+
+        color
+    }
+}
+
+impl Fill {
+    /// `Color::adjust` on a solid color or every stop of a gradient.
+    ///
+    /// A `Rainbow` has no colors to adjust, but its hue cycle takes the saturation and hue shift.
+    /// The other keyword fills are returned as they are, and so is a `Raw` string, which this crate
+    /// can't read colors out of.
+    pub fn adjust(&self, adjustments: &ColorAdjustments) -> Fill {
+        let apply = |stops: &[Color]| stops.iter().map(|c| c.adjust(adjustments)).collect();
+
+        match self {
+            Fill::Rainbow(params) => {
+                let mut params = *params;
+                params.saturation = (params.saturation * adjustments.saturation).clamp(0.0, 1.0);
+                params.phase += adjustments.hue_shift / 360.0;
+
+                Fill::Rainbow(params)
+            },
+            Fill::None => Fill::None,
+            Fill::Transparent => Fill::Transparent,
+            Fill::Raw(raw) => Fill::Raw(raw.clone()),
+            Fill::Color(color) => Fill::Color(color.adjust(adjustments)),
+            Fill::Gradient(stops) => Fill::Gradient(apply(stops)),
+            Fill::Radial { center, stops } => Fill::Radial { center: *center, stops: apply(stops) },
+            Fill::Conic { angle, stops } => Fill::Conic { angle: *angle, stops: apply(stops) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    /// A spread of colors across the whole cube.
+    fn samples() -> impl Iterator<Item = Color> {
+        (0..=255).step_by(51).flat_map(|r| {
+            (0..=255).step_by(51).flat_map(move |g| {
+                (0..=255).step_by(51).map(move |b| Color { red: r, green: g, blue: b })
+            })
+        })
+    }
+
+    #[test]
+    fn lighten_and_darken() {
+        assert_eq!(BLUE.lighten(0.25), Color { red: 128, green: 128, blue: 255 });
+        assert_eq!(BLUE.darken(0.25), Color { red: 0, green: 0, blue: 128 });
+        assert_eq!(BLUE.darken(-0.25), BLUE.lighten(0.25));
+    }
+
+    #[test]
+    fn saturate_and_desaturate() {
+        let muted = Color { red: 96, green: 96, blue: 160 };
+
+        assert_eq!(muted.saturate(1.0), Color { red: 1, green: 1, blue: 255 });
+        assert_eq!(muted.desaturate(1.0), Color { red: 128, green: 128, blue: 128 });
+    }
+
+    #[test]
+    fn mix() {
+        assert_eq!(BLACK.mix(&WHITE, 0.5), Color { red: 128, green: 128, blue: 128 });
+        assert_eq!(BLACK.mix(&WHITE, 0.0), BLACK);
+    }
+
+    #[test]
+    fn invert() {
+        assert_eq!(BLACK.invert(), WHITE);
+        assert_eq!(Color { red: 255, green: 0, blue: 100 }.invert(), Color { red: 0, green: 255, blue: 155 });
+
+        for color in samples() {
+            assert_eq!(color.invert().invert(), color);
+        }
+    }
+
+    #[test]
+    fn grayscale() {
+        assert_eq!(BLUE.grayscale(), Color { red: 128, green: 128, blue: 128 });
+
+        for color in samples() {
+            let gray = color.grayscale();
+
+            assert!(gray.red == gray.green && gray.green == gray.blue, "{:?} -> {:?}", color, gray);
+        }
+    }
+
+    #[test]
+    fn chains() {
+        assert_eq!(BLUE.darken(0.25).lighten(0.25), BLUE);
+        assert_eq!(BLUE.desaturate(0.5).grayscale().invert(), Color { red: 127, green: 127, blue: 127 });
+    }
+
+    #[test]
+    fn zero_amounts_are_identity() {
+        for color in samples() {
+            assert_eq!(color.lighten(0.0), color);
+            assert_eq!(color.saturate(0.0), color);
+        }
+    }
+
+    #[test]
+    fn large_amounts_clamp() {
+        for color in samples() {
+            assert_eq!(color.lighten(10.0), WHITE);
+            assert_eq!(color.darken(10.0), BLACK);
+            assert_eq!(color.saturate(-10.0), color.grayscale());
+            assert_eq!(color.saturate(10.0), color.saturate(1.0));
+            assert_eq!(color.mix(&WHITE, 10.0), WHITE);
+            assert_eq!(color.mix(&WHITE, -10.0), color);
+        }
+    }
+
+    mod adjustments {
+        use super::*;
+
+        use crate::rainbow::RainbowParams;
+
+        const RED: Color = Color { red: 255, green: 0, blue: 0 };
+
+        #[test]
+        fn none_is_identity() {
+            for color in samples() {
+                assert_eq!(color.adjust(&ColorAdjustments::NONE), color);
+            }
+        }
+
+        #[test]
+        fn exposure() {
+            let adjust = |exposure| Color { red: 64, green: 128, blue: 0 }.adjust(&ColorAdjustments { exposure, ..ColorAdjustments::NONE });
+
+            assert_eq!(adjust(-1.0), Color { red: 44, green: 92, blue: 0 });
+            assert_eq!(adjust(10.0), Color { red: 255, green: 255, blue: 0 });
+            assert_eq!(adjust(-100.0), BLACK);
+        }
+
+        #[test]
+        fn contrast() {
+            let adjust = |color: Color, contrast| color.adjust(&ColorAdjustments { contrast, ..ColorAdjustments::NONE });
+
+            assert_eq!(adjust(Color { red: 64, green: 128, blue: 192 }, 0.0), Color { red: 128, green: 128, blue: 128 });
+            assert_eq!(adjust(Color { red: 64, green: 128, blue: 192 }, 2.0), Color { red: 1, green: 129, blue: 255 });
+            assert_eq!(adjust(WHITE, 0.5), Color { red: 191, green: 191, blue: 191 });
+        }
+
+        #[test]
+        fn saturation_and_hue() {
+            let adjust = |saturation, hue_shift| RED.adjust(&ColorAdjustments { saturation, hue_shift, ..ColorAdjustments::NONE });
+
+            assert_eq!(adjust(0.0, 0.0), RED.grayscale());
+            assert_eq!(adjust(1.0, 120.0), Color { red: 0, green: 255, blue: 0 });
+            assert_eq!(adjust(1.0, -120.0), BLUE);
+            assert_eq!(adjust(0.5, 0.0), Color::from_hsl(0.0, 0.5, 0.5));
+        }
+
+        #[test]
+        fn fills() {
+            let adjustments = ColorAdjustments { exposure: 0.5, hue_shift: 90.0, ..ColorAdjustments::NONE };
+            let gradient = Fill::Conic { angle: 45.0, stops: vec![RED, BLUE] };
+
+            assert_eq!(
+                gradient.adjust(&adjustments),
+                Fill::Conic { angle: 45.0, stops: vec![RED.adjust(&adjustments), BLUE.adjust(&adjustments)] }
+            );
+            assert_eq!(Fill::Color(BLUE).adjust(&adjustments), Fill::Color(BLUE.adjust(&adjustments)));
+            assert_eq!(Fill::None.adjust(&adjustments), Fill::None);
+        }
+
+        #[test]
+        fn rainbow_takes_saturation_and_hue() {
+            let adjustments = ColorAdjustments { saturation: 0.5, hue_shift: 180.0, exposure: 3.0, ..ColorAdjustments::NONE };
+
+            assert_eq!(
+                Fill::rainbow().adjust(&adjustments),
+                Fill::Rainbow(RainbowParams { saturation: 0.5, phase: 0.5, ..RainbowParams::DEFAULT })
+            );
+        }
+
+        #[test]
+        fn serde() {
+            let adjustments: ColorAdjustments = serde_json::from_str(r#"{ "exposure": -0.5, "hue_shift": 30.0 }"#).unwrap();
+
+            assert_eq!(adjustments, ColorAdjustments { exposure: -0.5, hue_shift: 30.0, ..ColorAdjustments::NONE });
+            assert_eq!(serde_json::from_str::<ColorAdjustments>("{}").unwrap(), ColorAdjustments::NONE);
+            assert!(serde_json::from_str::<ColorAdjustments>(r#"{ "brightness": 1.0 }"#).is_err());
+
+            let json = serde_json::to_string(&adjustments).unwrap();
+
+            assert_eq!(json, r#"{"exposure":-0.5,"contrast":1.0,"saturation":1.0,"hue_shift":30.0}"#);
+            assert_eq!(serde_json::from_str::<ColorAdjustments>(&json).unwrap(), adjustments);
+        }
+    }
+
+    #[test]
+    fn lighten_never_darkens() {
+        for color in samples() {
+            for step in 0..=10 {
+                let amount = step as f32 / 10.0;
+
+                assert!(color.lighten(amount).to_hsl().lightness >= color.to_hsl().lightness - 0.01);
+                assert!(color.darken(amount).to_hsl().lightness <= color.to_hsl().lightness + 0.01);
+            }
+        }
+    }
+}
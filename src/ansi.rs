@@ -0,0 +1,227 @@
+//! Terminal colors: the 16 ANSI colors, xterm's 256-color palette and the escape sequences that
+//! select them.
+//!
+//! Terminals let users change the first 16 colors, so these use xterm's defaults. The other 240
+//! are the same everywhere.
+
+use core::fmt;
+
+use crate::color::Color;
+use crate::error::ParseColorError;
+
+/// xterm's default colors 0 to 15.
+const ANSI16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// The channel values of the 6x6x6 color cube at 16 to 231.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// How many colors a terminal can show, which decides the escape sequence `AnsiEscape` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiDepth {
+    /// `ESC[31m`: the 16 ANSI colors, which every terminal has.
+    Ansi16,
+
+    /// `ESC[38;5;196m`: xterm's 256 colors.
+    Ansi256,
+
+    /// `ESC[38;2;255;0;0m`: the exact color.
+    TrueColor,
+}
+
+/// Turns off every color and style set before it.
+pub const RESET: &str = "\x1b[0m";
+
+impl Color {
+    /// Color `index` of xterm's 256-color palette.
+    pub const fn from_ansi256(index: u8) -> Color {
+        match index {
+            0..=15 => {
+                let (red, green, blue) = ANSI16[index as usize];
+
+                Color::new(red, green, blue)
+            },
+            16..=231 => {
+                let i = index - 16;
+
+                Color::new(CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize])
+            },
+            _ => {
+                let level = 8 + 10 * (index - 232);
+
+                Color::new(level, level, level)
+            },
+        }
+    }
+
+    /// The closest of the 256-color palette's fixed colors, 16 to 255. The first 16 are left
+    /// out since terminals let users change them.
+    pub fn to_ansi256(&self) -> u8 {
+        nearest(self, 16..=255)
+    }
+
+    /// The closest of the 16 ANSI colors, going by xterm's defaults.
+    pub fn to_ansi16(&self) -> u8 {
+        nearest(self, 0..=15)
+    }
+
+    /// Writes the escape sequence that sets the foreground to this color, or the closest one
+    /// `depth` can show.
+    ///
+    /// ```
+    /// use led_json::{AnsiDepth, Color};
+    ///
+    /// let red = Color::new(255, 0, 0);
+    ///
+    /// assert_eq!(red.ansi_fg(AnsiDepth::Ansi16).to_string(), "\x1b[91m");
+    /// assert_eq!(red.ansi_fg(AnsiDepth::Ansi256).to_string(), "\x1b[38;5;196m");
+    /// assert_eq!(red.ansi_bg(AnsiDepth::TrueColor).to_string(), "\x1b[48;2;255;0;0m");
+    /// ```
+    pub fn ansi_fg(&self, depth: AnsiDepth) -> AnsiEscape<'_> {
+        AnsiEscape { color: self, depth, background: false }
+    }
+
+    /// Like `ansi_fg`, for the background.
+    pub fn ansi_bg(&self, depth: AnsiDepth) -> AnsiEscape<'_> {
+        AnsiEscape { color: self, depth, background: true }
+    }
+}
+
+fn distance(a: &Color, b: &Color) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+
+    d(a.red, b.red) + d(a.green, b.green) + d(a.blue, b.blue)
+}
+
+/// The palette index in `range` closest to `color`, the lowest one on ties.
+fn nearest(color: &Color, range: core::ops::RangeInclusive<u8>) -> u8 {
+    let start = *range.start();
+
+    range.min_by_key(|&i| distance(color, &Color::from_ansi256(i))).unwrap_or(start)
+}
+
+/// `ansi(196)` or one of the terminal color names, like `red` or `bright-red`, if `s` is either.
+pub(crate) fn parse(s: &str) -> Option<core::result::Result<Color, ParseColorError>> {
+    if let Some(index) = s.strip_prefix("ansi(").and_then(|rest| rest.strip_suffix(')')) {
+        return Some(index.trim().parse::<u8>()
+            .map(Color::from_ansi256)
+            .map_err(|_| ParseColorError::InvalidAnsi(s.into())))
+    }
+
+    let (name, bright) = match s.strip_prefix("bright-").or_else(|| s.strip_prefix("bright_")) {
+        Some(name) => (name, true),
+        None => (s, false),
+    };
+
+    let index = NAMES.iter().position(|n| *n == name)?;
+
+    Some(Ok(Color::from_ansi256(index as u8 + if bright { 8 } else { 0 })))
+}
+
+/// The escape sequence selecting a color; see `Color::ansi_fg`.
+#[derive(Debug, Clone, Copy)]
+pub struct AnsiEscape<'a> {
+    color: &'a Color,
+    depth: AnsiDepth,
+    background: bool,
+}
+
+impl fmt::Display for AnsiEscape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = self.color;
+
+        match self.depth {
+            AnsiDepth::Ansi16 => {
+                let index = c.to_ansi16();
+                let base = match (self.background, index < 8) {
+                    (false, true) => 30,
+                    (false, false) => 90 - 8,
+                    (true, true) => 40,
+                    (true, false) => 100 - 8,
+                };
+
+                write!(f, "\x1b[{}m", base + index)
+            },
+            AnsiDepth::Ansi256 => write!(f, "\x1b[{};5;{}m", if self.background { 48 } else { 38 }, c.to_ansi256()),
+            AnsiDepth::TrueColor => {
+                write!(f, "\x1b[{};2;{};{};{}m", if self.background { 48 } else { 38 }, c.red, c.green, c.blue)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn palette() {
+        assert_eq!(Color::from_ansi256(1), Color::new(205, 0, 0));
+        assert_eq!(Color::from_ansi256(16), Color::new(0, 0, 0));
+        assert_eq!(Color::from_ansi256(196), Color::new(255, 0, 0));
+        assert_eq!(Color::from_ansi256(231), Color::new(255, 255, 255));
+        assert_eq!(Color::from_ansi256(232), Color::new(8, 8, 8));
+        assert_eq!(Color::from_ansi256(255), Color::new(238, 238, 238));
+    }
+
+    #[test]
+    fn palette_colors_map_back_to_themselves() {
+        for i in 16..=255 {
+            assert_eq!(Color::from_ansi256(i).to_ansi256(), i);
+        }
+
+        for i in 0..16 {
+            assert_eq!(Color::from_ansi256(i).to_ansi16(), i);
+        }
+    }
+
+    #[test]
+    fn nearest() {
+        assert_eq!(Color::new(250, 10, 5).to_ansi256(), 196);
+        assert_eq!(Color::new(128, 128, 128).to_ansi256(), 244);
+        assert_eq!(Color::new(250, 10, 5).to_ansi16(), 9);
+        assert_eq!(Color::new(30, 30, 30).to_ansi16(), 0);
+    }
+
+    #[test]
+    fn escapes() {
+        let blue = Color::new(0, 0, 200);
+
+        assert_eq!(blue.ansi_fg(AnsiDepth::Ansi16).to_string(), "\x1b[34m");
+        assert_eq!(blue.ansi_bg(AnsiDepth::Ansi16).to_string(), "\x1b[44m");
+        assert_eq!(Color::new(255, 255, 255).ansi_bg(AnsiDepth::Ansi16).to_string(), "\x1b[107m");
+        assert_eq!(blue.ansi_bg(AnsiDepth::Ansi256).to_string(), "\x1b[48;5;20m");
+        assert_eq!(blue.ansi_fg(AnsiDepth::TrueColor).to_string(), "\x1b[38;2;0;0;200m");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(parse("red"), Some(Ok(Color::new(205, 0, 0))));
+        assert_eq!(parse("bright-red"), Some(Ok(Color::new(255, 0, 0))));
+        assert_eq!(parse("bright_white"), Some(Ok(Color::new(255, 255, 255))));
+        assert_eq!(parse("ansi( 21 )"), Some(Ok(Color::new(0, 0, 255))));
+        assert_eq!(parse("ansi(256)"), Some(Err(ParseColorError::InvalidAnsi("ansi(256)".into()))));
+        assert_eq!(parse("orange"), None);
+        assert_eq!(parse("#fff"), None);
+    }
+}
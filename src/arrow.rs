@@ -0,0 +1,116 @@
+//! Columnar encoding of colors as Arrow `FixedSizeBinary(3)` arrays, one `[red, green, blue]`
+//! value per row.
+
+use arrow_array::{Array, FixedSizeBinaryArray};
+use arrow_array::builder::FixedSizeBinaryBuilder;
+use thiserror::Error;
+
+use crate::color::Color;
+use crate::palette::Palette;
+
+const WIDTH: i32 = 3;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ArrowError {
+    #[error("Expected FixedSizeBinary(3), got FixedSizeBinary({0})")]
+    InvalidWidth(i32),
+
+    #[error("Null color at row {0}")]
+    NullValue(usize),
+}
+
+pub fn to_arrow<'a, I>(colors: I) -> FixedSizeBinaryArray
+where
+    I: IntoIterator<Item = &'a Color>,
+{
+    let colors = colors.into_iter();
+    let mut builder = FixedSizeBinaryBuilder::with_capacity(colors.size_hint().0, WIDTH);
+
+    for c in colors {
+        builder.append_value([c.red, c.green, c.blue])
+            .expect("colors are always 3 bytes wide");
+    }
+
+    builder.finish()
+}
+
+pub fn from_arrow(array: &FixedSizeBinaryArray) -> std::result::Result<Vec<Color>, ArrowError> {
+    if array.value_length() != WIDTH {
+        return Err(ArrowError::InvalidWidth(array.value_length()))
+    }
+
+    (0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                return Err(ArrowError::NullValue(i))
+            }
+
+            let v = array.value(i);
+
+            Ok(Color { red: v[0], green: v[1], blue: v[2] })
+        })
+        .collect()
+}
+
+impl Palette {
+    /// Only the colors are encoded; swatch names are dropped.
+    pub fn to_arrow(&self) -> FixedSizeBinaryArray {
+        to_arrow(self.colors())
+    }
+
+    pub fn from_arrow(array: &FixedSizeBinaryArray) -> std::result::Result<Self, ArrowError> {
+        Ok(from_arrow(array)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let colors = vec![
+            Color { red: 255, green: 0, blue: 255 },
+            Color { red: 1, green: 2, blue: 3 },
+        ];
+
+        let array = to_arrow(&colors);
+
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.value(1), &[1, 2, 3]);
+        assert_eq!(from_arrow(&array), Ok(colors));
+    }
+
+    #[test]
+    fn empty() {
+        let array = to_arrow(&[]);
+
+        assert_eq!(array.len(), 0);
+        assert_eq!(from_arrow(&array), Ok(vec![]));
+    }
+
+    #[test]
+    fn palette_round_trip() {
+        let mut palette = Palette::new();
+        palette.push(Color { red: 10, green: 20, blue: 30 });
+
+        assert_eq!(Palette::from_arrow(&palette.to_arrow()), Ok(palette));
+    }
+
+    #[test]
+    fn wrong_width() {
+        let array = FixedSizeBinaryArray::try_from_iter(vec![[1u8, 2, 3, 4]].into_iter()).unwrap();
+
+        assert_eq!(from_arrow(&array), Err(ArrowError::InvalidWidth(4)));
+    }
+
+    #[test]
+    fn nulls() {
+        let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+            vec![Some([1u8, 2, 3]), None].into_iter(),
+            3,
+        ).unwrap();
+
+        assert_eq!(from_arrow(&array), Err(ArrowError::NullValue(1)));
+    }
+}
@@ -0,0 +1,140 @@
+//! Checks for gradients that will visibly band once rasterized to 8-bit colors.
+
+use alloc::vec::Vec;
+
+use crate::fill::Fill;
+
+/// Adjacent pixels further apart than this (CIEDE2000) always show as a visible step.
+const VISIBLE_STEP: f32 = 1.0;
+
+/// The edge between two flat areas at least this many pixels wide is easier to see, so smaller
+/// steps (down to `PLATEAU_STEP`) count there. This is what makes stretched 8-bit gradients band
+/// even though a single level is well under `VISIBLE_STEP`.
+const PLATEAU_WIDTH: u32 = 16;
+const PLATEAU_STEP: f32 = 0.3;
+
+/// A visible step between pixel `position - 1` and pixel `position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub position: u32,
+    pub delta_e: f32,
+}
+
+impl Fill {
+    /// The largest CIEDE2000 difference between neighbouring samples when the fill is sampled at
+    /// `n` evenly spaced points from `0.0` to `1.0`. Fewer than two samples gives `0.0`.
+    ///
+    /// Samples are 8-bit colors, so once `n` is large enough this stops shrinking and shows the
+    /// smallest step the gradient can be drawn with.
+    pub fn max_delta_e_between_samples(&self, n: usize) -> f32 {
+        if n < 2 {
+            return 0.0
+        }
+
+        let samples: Vec<_> = (0..n).map(|i| self.sample(i as f32 / (n - 1) as f32)).collect();
+
+        samples.windows(2)
+            .map(|pair| pair[0].delta_e(&pair[1]))
+            .fold(0.0, f32::max)
+    }
+
+    /// Every visible step when the fill is drawn across `width` pixels, in order. An empty result
+    /// means the fill will look smooth at that width.
+    ///
+    /// A step is visible if it's over 1.0, or over 0.3 with at least 16 identical pixels before
+    /// it.
+    pub fn banding(&self, width: u32) -> Vec<Band> {
+        let mut bands = Vec::new();
+        let mut previous = self.sample_xy(0, 0, width, 1);
+
+        // how many pixels in a row have been the same as `previous`
+        let mut run = 1;
+
+        for x in 1..width {
+            let color = self.sample_xy(x, 0, width, 1);
+
+            if color == previous {
+                run += 1;
+                continue
+            }
+
+            let delta_e = previous.delta_e(&color);
+
+            if delta_e > VISIBLE_STEP || (run >= PLATEAU_WIDTH && delta_e > PLATEAU_STEP) {
+                bands.push(Band { position: x, delta_e });
+            }
+
+            previous = color;
+            run = 1;
+        }
+
+        bands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn gradient(from: Color, to: Color) -> Fill {
+        Fill::Gradient(vec![from, to])
+    }
+
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+    #[test]
+    fn solid_fills_have_no_steps() {
+        assert_eq!(Fill::Color(WHITE).max_delta_e_between_samples(100), 0.0);
+        assert_eq!(Fill::None.max_delta_e_between_samples(100), 0.0);
+        assert!(Fill::Color(WHITE).banding(100).is_empty());
+    }
+
+    #[test]
+    fn too_few_samples() {
+        assert_eq!(gradient(BLACK, WHITE).max_delta_e_between_samples(0), 0.0);
+        assert_eq!(gradient(BLACK, WHITE).max_delta_e_between_samples(1), 0.0);
+    }
+
+    #[test]
+    fn two_samples_are_the_endpoints() {
+        let fill = gradient(BLACK, WHITE);
+
+        assert_eq!(fill.max_delta_e_between_samples(2), BLACK.delta_e(&WHITE));
+    }
+
+    #[test]
+    fn more_samples_means_smaller_steps() {
+        let fill = gradient(BLACK, WHITE);
+
+        assert!(fill.max_delta_e_between_samples(10) > fill.max_delta_e_between_samples(100));
+        assert!(fill.max_delta_e_between_samples(100) > fill.max_delta_e_between_samples(1000));
+    }
+
+    #[test]
+    fn wide_gray_gradient_is_smooth() {
+        assert!(gradient(BLACK, WHITE).banding(1024).is_empty());
+    }
+
+    #[test]
+    fn narrow_gradient_bands() {
+        let bands = gradient(BLACK, WHITE).banding(16);
+
+        assert_eq!(bands.len(), 15);
+        assert_eq!(bands[0].position, 1);
+    }
+
+    #[test]
+    fn stretched_gradient_bands_at_each_level() {
+        // only ten 8-bit levels between the two, so each one becomes a visible stripe
+        let fill = gradient(Color { red: 100, green: 100, blue: 100 }, Color { red: 110, green: 110, blue: 110 });
+        let bands = fill.banding(1000);
+
+        assert_eq!(bands.len(), 10, "{:?}", bands);
+        assert!(bands.iter().all(|b| b.delta_e > PLATEAU_STEP && b.delta_e < VISIBLE_STEP));
+
+        // the same gradient over a short strip has no room for wide stripes
+        assert!(fill.banding(20).is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+//! Conversions of whole slices of colors, like the palettes extracted from images, which are
+//! much faster than converting one `Color` at a time: decoding the sRGB transfer function is a
+//! lookup in a table of the 256 channel values rather than a `powf` per channel.
+//!
+//! Each function writes the conversion of `input[i]` to `output[i]`, and panics if the slices
+//! aren't the same length. With the `rayon` feature, long slices are converted in parallel.
+//!
+//! ```
+//! use led_json::batch::convert_srgb_to_oklab;
+//! use led_json::{Color, Oklab};
+//!
+//! let colors = vec![Color::new(255, 0, 0); 1000];
+//! let mut oklab = vec![Oklab { lightness: 0.0, a: 0.0, b: 0.0 }; colors.len()];
+//!
+//! convert_srgb_to_oklab(&colors, &mut oklab);
+//!
+//! assert_eq!(oklab[999], Color::new(255, 0, 0).to_oklab());
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::color::Color;
+use crate::consts::{SRGB_TO_XYZ, WHITE_D65};
+use crate::lab::{multiply, srgb_to_linear, xyz_to_lab, Lab};
+use crate::oklab::{self, clamp_linear, Oklab, Oklch};
+
+/// Slices at least this long are split between threads.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// How many colors each thread converts at a time.
+#[cfg(feature = "rayon")]
+const CHUNK: usize = 1024;
+
+/// `srgb_to_linear` of every channel value.
+fn linear(color: &Color) -> [f64; 3] {
+    static TABLE: OnceLock<[f64; 256]> = OnceLock::new();
+
+    let table = TABLE.get_or_init(|| core::array::from_fn(|c| srgb_to_linear(c as u8)));
+
+    [table[usize::from(color.red)], table[usize::from(color.green)], table[usize::from(color.blue)]]
+}
+
+fn convert<A: Sync, B: Send>(input: &[A], output: &mut [B], f: impl Fn(&A) -> B + Sync) {
+    assert_eq!(input.len(), output.len(), "the output slice has to be as long as the input");
+
+    #[cfg(feature = "rayon")]
+    if input.len() >= PARALLEL_THRESHOLD {
+        use rayon::prelude::*;
+
+        output.par_chunks_mut(CHUNK).zip(input.par_chunks(CHUNK)).for_each(|(output, input)| {
+            for (out, value) in output.iter_mut().zip(input) {
+                *out = f(value);
+            }
+        });
+
+        return
+    }
+
+    for (out, value) in output.iter_mut().zip(input) {
+        *out = f(value);
+    }
+}
+
+/// `Color::to_oklab` of every color.
+pub fn convert_srgb_to_oklab(input: &[Color], output: &mut [Oklab]) {
+    convert(input, output, |color| oklab::from_linear(linear(color)));
+}
+
+/// `Color::from_oklab` of every color.
+pub fn convert_oklab_to_srgb(input: &[Oklab], output: &mut [Color]) {
+    convert(input, output, |oklab| clamp_linear(oklab::to_linear(oklab)));
+}
+
+/// `Color::to_oklch` of every color.
+pub fn convert_srgb_to_oklch(input: &[Color], output: &mut [Oklch]) {
+    convert(input, output, |color| oklab::from_linear(linear(color)).into());
+}
+
+/// `Color::from_oklch` of every color.
+pub fn convert_oklch_to_srgb(input: &[Oklch], output: &mut [Color]) {
+    convert(input, output, |oklch| clamp_linear(oklab::to_linear(&(*oklch).into())));
+}
+
+/// `Color::to_lab` of every color.
+pub fn convert_srgb_to_lab(input: &[Color], output: &mut [Lab]) {
+    convert(input, output, |color| xyz_to_lab(multiply(&SRGB_TO_XYZ, linear(color)), WHITE_D65));
+}
+
+/// `Color::from_lab` of every color.
+pub fn convert_lab_to_srgb(input: &[Lab], output: &mut [Color]) {
+    convert(input, output, |lab| Color::from_lab(*lab));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every color with channels that are multiples of 15: 5832 of them, past the parallel
+    /// threshold.
+    fn colors() -> Vec<Color> {
+        (0..=255).step_by(15)
+            .flat_map(|r| (0..=255).step_by(15).flat_map(move |g| (0..=255).step_by(15).map(move |b| Color::new(r, g, b))))
+            .collect()
+    }
+
+    #[test]
+    fn matches_one_at_a_time() {
+        let colors = colors();
+
+        let mut oklab = vec![Oklab { lightness: 0.0, a: 0.0, b: 0.0 }; colors.len()];
+        let mut oklch = vec![Oklch { lightness: 0.0, chroma: 0.0, hue: 0.0 }; colors.len()];
+        let mut lab = vec![Lab { lightness: 0.0, a: 0.0, b: 0.0 }; colors.len()];
+
+        convert_srgb_to_oklab(&colors, &mut oklab);
+        convert_srgb_to_oklch(&colors, &mut oklch);
+        convert_srgb_to_lab(&colors, &mut lab);
+
+        for (i, color) in colors.iter().enumerate() {
+            assert_eq!(oklab[i], color.to_oklab());
+            assert_eq!(oklch[i], color.to_oklch());
+            assert_eq!(lab[i], color.to_lab());
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let colors = colors();
+        let mut back = vec![Color::default(); colors.len()];
+
+        let mut oklab = vec![Oklab { lightness: 0.0, a: 0.0, b: 0.0 }; colors.len()];
+        convert_srgb_to_oklab(&colors, &mut oklab);
+        convert_oklab_to_srgb(&oklab, &mut back);
+        assert_eq!(back, colors);
+
+        let mut oklch = vec![Oklch { lightness: 0.0, chroma: 0.0, hue: 0.0 }; colors.len()];
+        convert_srgb_to_oklch(&colors, &mut oklch);
+        convert_oklch_to_srgb(&oklch, &mut back);
+        assert_eq!(back, colors);
+
+        let mut lab = vec![Lab { lightness: 0.0, a: 0.0, b: 0.0 }; colors.len()];
+        convert_srgb_to_lab(&colors, &mut lab);
+        convert_lab_to_srgb(&lab, &mut back);
+        assert_eq!(back, colors);
+    }
+
+    #[test]
+    fn empty() {
+        convert_srgb_to_oklab(&[], &mut []);
+    }
+
+    #[test]
+    #[should_panic(expected = "as long as the input")]
+    fn lengths_have_to_match() {
+        convert_srgb_to_lab(&[Color::default(); 3], &mut [Lab { lightness: 0.0, a: 0.0, b: 0.0 }; 2]);
+    }
+}
@@ -0,0 +1,135 @@
+//! The compact form `Color` and `Fill` take in formats that aren't human readable, like bincode
+//! and postcard. Those can't tell a string from an array on their own, so the forms
+//! `deserialize_any` picks between in JSON don't work there.
+//!
+//! A color is its three channel bytes. A fill is a tag byte followed by what that kind of fill
+//! holds:
+//!
+//! | tag | fill          | followed by                                        |
+//! |-----|---------------|----------------------------------------------------|
+//! | 0   | `Rainbow`     | saturation, lightness, period and phase as `f32`s   |
+//! | 1   | `None`        | nothing                                            |
+//! | 2   | `Transparent` | nothing                                            |
+//! | 3   | `Color`       | a color                                            |
+//! | 4   | `Gradient`    | a sequence of colors                               |
+//! | 5   | `Radial`      | the center's `x` and `y` as `f32`s, then the stops |
+//! | 6   | `Conic`       | the angle as an `f32`, then the stops              |
+//! | 7   | `Raw`         | a string                                           |
+//!
+//! How sequences and floats are written is up to the format. A `CustomFill` takes the same form,
+//! with its keywords written as `Raw` strings.
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::rainbow::RainbowParams;
+
+const RAINBOW: u8 = 0;
+const NONE: u8 = 1;
+const TRANSPARENT: u8 = 2;
+pub(crate) const COLOR: u8 = 3;
+pub(crate) const GRADIENT: u8 = 4;
+const RADIAL: u8 = 5;
+const CONIC: u8 = 6;
+pub(crate) const RAW: u8 = 7;
+
+pub(crate) fn serialize_color<S>(color: &Color, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    (color.red, color.green, color.blue).serialize(serializer)
+}
+
+pub(crate) fn deserialize_color<'de, D>(deserializer: D) -> core::result::Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let (red, green, blue) = <(u8, u8, u8)>::deserialize(deserializer)?;
+
+    Ok(Color { red, green, blue })
+}
+
+pub(crate) fn serialize_fill<S>(fill: &Fill, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match fill {
+        Fill::Rainbow(params) => {
+            serialize_tagged(RAINBOW, &(params.saturation, params.lightness, params.period, params.phase), serializer)
+        },
+        Fill::None => serialize_tagged(NONE, &(), serializer),
+        Fill::Transparent => serialize_tagged(TRANSPARENT, &(), serializer),
+        Fill::Color(color) => serialize_tagged(COLOR, color, serializer),
+        Fill::Gradient(stops) => serialize_tagged(GRADIENT, stops, serializer),
+        Fill::Radial { center: (x, y), stops } => serialize_tagged(RADIAL, &(x, y, stops), serializer),
+        Fill::Conic { angle, stops } => serialize_tagged(CONIC, &(angle, stops), serializer),
+        Fill::Raw(raw) => serialize_tagged(RAW, raw, serializer),
+    }
+}
+
+/// A fill's tag byte and what follows it.
+pub(crate) fn serialize_tagged<S, T>(tag: u8, payload: &T, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize + ?Sized,
+{
+    // always two elements, so formats that need a fixed length up front can read it back; the
+    // keywords have `()` for a payload, which takes no space
+    let mut tuple = serializer.serialize_tuple(2)?;
+
+    tuple.serialize_element(&tag)?;
+    tuple.serialize_element(payload)?;
+
+    tuple.end()
+}
+
+/// A fill as it comes out of the compact form, before `fill::validated` checks it.
+pub(crate) fn deserialize_fill<'de, D>(deserializer: D) -> core::result::Result<Fill, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct TagAndPayload;
+
+    impl<'de> Visitor<'de> for TagAndPayload {
+        type Value = Fill;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a fill tag followed by its payload")
+        }
+
+        fn visit_seq<S>(self, mut seq: S) -> core::result::Result<Fill, S::Error>
+        where
+            S: SeqAccess<'de>,
+        {
+            let tag: u8 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+            let fill = match tag {
+                RAINBOW => seq.next_element::<(f32, f32, f32, f32)>()?.map(|(saturation, lightness, period, phase)| {
+                    Fill::Rainbow(RainbowParams { saturation, lightness, period, phase })
+                }),
+                NONE => seq.next_element::<()>()?.map(|_| Fill::None),
+                TRANSPARENT => seq.next_element::<()>()?.map(|_| Fill::Transparent),
+                // the colors in here go through `Color`'s impls, which see the same format and
+                // pick the compact form too
+                COLOR => seq.next_element()?.map(Fill::Color),
+                GRADIENT => seq.next_element()?.map(Fill::Gradient),
+                RADIAL => seq.next_element::<(f32, f32, Vec<Color>)>()?.map(|(x, y, stops)| Fill::Radial { center: (x, y), stops }),
+                CONIC => seq.next_element::<(f32, Vec<Color>)>()?.map(|(angle, stops)| Fill::Conic { angle, stops }),
+                RAW => seq.next_element::<String>()?.map(Fill::Raw),
+                tag => return Err(de::Error::invalid_value(de::Unexpected::Unsigned(tag.into()), &"a fill tag from 0 to 7")),
+            };
+
+            fill.ok_or_else(|| de::Error::invalid_length(1, &self))
+        }
+    }
+
+    deserializer.deserialize_tuple(2, TagAndPayload)
+}
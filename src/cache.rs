@@ -0,0 +1,251 @@
+//! Remembering parsed colors, for configs that repeat the same few strings many times over.
+
+use serde::de::{DeserializeSeed, Deserializer};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::color::{self, Color};
+use crate::error::ParseColorError;
+use crate::fill::{self, Fill};
+
+struct Entries {
+    colors: HashMap<String, (Color, u64)>,
+    // bumped on every lookup; each entry remembers when it was last used
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Memoizes the strings `Color` parses, keeping the `capacity` most recently used ones.
+///
+/// `color()` and `fill()` hand out `DeserializeSeed`s that go through the cache, so one cache can
+/// be shared by every document that's read. Only strings that parse are remembered, and the cache
+/// can be shared between threads.
+///
+/// ```
+/// use led_json::{Fill, ParserCache};
+/// use serde::de::DeserializeSeed;
+///
+/// let cache = ParserCache::new(64);
+///
+/// for _ in 0..3 {
+///     let mut json = serde_json::Deserializer::from_str(r##"["#000", "#fff", "#000"]"##);
+///     let fill: Fill = cache.fill().deserialize(&mut json).unwrap();
+/// }
+///
+/// assert_eq!(cache.misses(), 2);
+/// assert_eq!(cache.hits(), 7);
+/// ```
+pub struct ParserCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl ParserCache {
+    /// A cache holding up to `capacity` colors. A capacity of 0 remembers nothing.
+    pub fn new(capacity: usize) -> Self {
+        ParserCache {
+            capacity,
+            entries: Mutex::new(Entries { colors: HashMap::new(), clock: 0, hits: 0, misses: 0 }),
+        }
+    }
+
+    /// Parses `s` the way `Color::from_str` does, or returns the color it parsed to before.
+    pub fn parse(&self, s: &str) -> std::result::Result<Color, ParseColorError> {
+        // without a `#` it can't be a color, so there's nothing to remember or count
+        if !s.starts_with('#') {
+            return color::parse_hex(s)
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clock += 1;
+        let now = entries.clock;
+
+        if let Some((color, used)) = entries.colors.get_mut(s) {
+            *used = now;
+            let color = *color;
+            entries.hits += 1;
+
+            return Ok(color)
+        }
+
+        entries.misses += 1;
+        let color = color::parse_hex(s)?;
+
+        if self.capacity == 0 {
+            return Ok(color)
+        }
+
+        if entries.colors.len() >= self.capacity {
+            let oldest = entries.colors.iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                entries.colors.remove(&oldest);
+            }
+        }
+
+        entries.colors.insert(s.to_string(), (color, now));
+
+        Ok(color)
+    }
+
+    /// How many colors are remembered.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many lookups found a remembered color.
+    pub fn hits(&self) -> u64 {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).hits
+    }
+
+    /// How many lookups had to parse.
+    pub fn misses(&self) -> u64 {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).misses
+    }
+
+    /// Deserializes a `Color` through this cache.
+    pub fn color(&self) -> ColorSeed<'_> {
+        ColorSeed(self)
+    }
+
+    /// Deserializes a `Fill` through this cache, the stops of a tagged gradient object like
+    /// `{ "type": "radial", "stops": [...] }` included.
+    pub fn fill(&self) -> FillSeed<'_> {
+        FillSeed(self)
+    }
+}
+
+impl Default for ParserCache {
+    /// Room for 256 colors.
+    fn default() -> Self {
+        ParserCache::new(256)
+    }
+}
+
+/// See `ParserCache::color`.
+pub struct ColorSeed<'a>(&'a ParserCache);
+
+impl<'de> DeserializeSeed<'de> for ColorSeed<'_> {
+    type Value = Color;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Color, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        color::deserialize_with(deserializer, &|s| self.0.parse(s))
+    }
+}
+
+/// See `ParserCache::fill`.
+pub struct FillSeed<'a>(&'a ParserCache);
+
+impl<'de> DeserializeSeed<'de> for FillSeed<'_> {
+    type Value = Fill;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Fill, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fill::deserialize_with(deserializer, &|s| self.0.parse(s), None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+    fn fill(cache: &ParserCache, json: &str) -> serde_json::Result<Fill> {
+        cache.fill().deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn remembers_colors() {
+        let cache = ParserCache::new(4);
+
+        assert_eq!(cache.parse("#fff"), Ok(WHITE));
+        assert_eq!(cache.parse("#fff"), Ok(WHITE));
+        assert_eq!(cache.parse("#ffffff"), Ok(WHITE));
+
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let cache = ParserCache::new(2);
+
+        cache.parse("#000").unwrap();
+        cache.parse("#fff").unwrap();
+        cache.parse("#000").unwrap();
+        cache.parse("#f00").unwrap();
+
+        assert_eq!(cache.len(), 2);
+
+        // `#fff` was the one pushed out
+        cache.parse("#000").unwrap();
+        cache.parse("#fff").unwrap();
+
+        assert_eq!((cache.hits(), cache.misses()), (2, 4));
+    }
+
+    #[test]
+    fn errors_are_not_remembered() {
+        let cache = ParserCache::new(4);
+
+        assert_eq!(cache.parse("#ggg"), Err(ParseColorError::InvalidHexDigit { position: 1 }));
+        assert_eq!(cache.parse("#ggg"), Err(ParseColorError::InvalidHexDigit { position: 1 }));
+        assert!(cache.is_empty());
+        assert_eq!(cache.misses(), 2);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let cache = ParserCache::new(0);
+
+        assert_eq!(cache.parse("#000"), Ok(BLACK));
+        assert_eq!(cache.parse("#000"), Ok(BLACK));
+        assert_eq!((cache.hits(), cache.len()), (0, 0));
+    }
+
+    #[test]
+    fn seeds() {
+        let cache = ParserCache::default();
+
+        assert_eq!(fill(&cache, r##"["#000", "#fff", "#000"]"##).unwrap(), Fill::Gradient(vec![BLACK, WHITE, BLACK]));
+        assert_eq!(fill(&cache, r##""#fff""##).unwrap(), Fill::Color(WHITE));
+        assert_eq!(fill(&cache, r#""rainbow""#).unwrap(), Fill::rainbow());
+        assert_eq!(fill(&cache, "[0, 0, 0]").unwrap(), Fill::Color(BLACK));
+        assert!(fill(&cache, r#""black""#).is_err());
+
+        assert_eq!((cache.hits(), cache.misses()), (2, 2));
+
+        let color = cache.color().deserialize(&mut serde_json::Deserializer::from_str(r##""#000""##)).unwrap();
+
+        assert_eq!(color, BLACK);
+        assert_eq!(cache.hits(), 3);
+    }
+
+    #[test]
+    fn tagged_stops() {
+        let cache = ParserCache::default();
+        let json = r##"{ "type": "conic", "angle": 90, "stops": ["#000", "#fff", "#000"] }"##;
+
+        assert_eq!(fill(&cache, json).unwrap(), Fill::Conic { angle: 90.0, stops: vec![BLACK, WHITE, BLACK] });
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        let json = r##"{ "type": "radial", "center": [0.5, 0.5], "stops": ["#fff"] }"##;
+
+        assert_eq!(fill(&cache, json).unwrap(), Fill::Radial { center: (0.5, 0.5), stops: vec![WHITE] });
+        assert_eq!((cache.hits(), cache.misses()), (2, 2));
+    }
+}
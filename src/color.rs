@@ -0,0 +1,913 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor, MapAccess, SeqAccess, Unexpected};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+use core::str::FromStr;
+use core::fmt;
+
+use crate::ansi;
+use crate::binary;
+use crate::error::ParseColorError;
+use crate::format::{ColorFormat, Formatted};
+use crate::warning::{Warning, Warnings};
+
+/// The default is black. Colors order by red, then green, then blue, the same as their
+/// `to_u32` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    /// Parses `#rgb` or `#rrggbb` in a `const` context. Prefer the `color!` macro, which makes
+    /// the compiler do it.
+    ///
+    /// # Panics
+    ///
+    /// If `s` isn't a valid hex color. When evaluated as a constant that's a compile error.
+    pub const fn from_hex(s: &str) -> Color {
+        const fn digit(byte: u8) -> u8 {
+            match byte {
+                b'0'..=b'9' => byte - b'0',
+                b'a'..=b'f' => byte - b'a' + 10,
+                b'A'..=b'F' => byte - b'A' + 10,
+                _ => panic!("invalid hex digit in color"),
+            }
+        }
+
+        match s.as_bytes() {
+            [b'#', r, g, b] => Color::new(17 * digit(*r), 17 * digit(*g), 17 * digit(*b)),
+            [b'#', r1, r2, g1, g2, b1, b2] => Color::new(
+                digit(*r1) << 4 | digit(*r2),
+                digit(*g1) << 4 | digit(*g2),
+                digit(*b1) << 4 | digit(*b2),
+            ),
+            [b'#', ..] => panic!("a hex color needs 3 or 6 digits"),
+            _ => panic!("a hex color starts with `#`"),
+        }
+    }
+
+    /// The color packed as `0x00RRGGBB`: blue in the lowest byte, the top byte zero. That's
+    /// the number `#rrggbb` spells out in hex.
+    ///
+    /// ```
+    /// use led_json::Color;
+    ///
+    /// assert_eq!(Color::new(0xff, 0x00, 0xaa).to_u32(), 0x00ff00aa);
+    /// assert_eq!(Color::from_u32(0x00ff00aa), Color::new(0xff, 0x00, 0xaa));
+    /// ```
+    pub const fn to_u32(&self) -> u32 {
+        (self.red as u32) << 16 | (self.green as u32) << 8 | self.blue as u32
+    }
+
+    /// The color packed by `to_u32`. The top byte is ignored.
+    pub const fn from_u32(packed: u32) -> Color {
+        Color::new((packed >> 16) as u8, (packed >> 8) as u8, packed as u8)
+    }
+
+    /// The color and an alpha value packed as `0xRRGGBBAA`: alpha in the lowest byte.
+    ///
+    /// ```
+    /// use led_json::Color;
+    ///
+    /// assert_eq!(Color::new(0xff, 0x00, 0xaa).to_rgba_u32(0x80), 0xff00aa80);
+    /// assert_eq!(Color::from_rgba_u32(0xff00aa80), (Color::new(0xff, 0x00, 0xaa), 0x80));
+    /// ```
+    pub const fn to_rgba_u32(&self, alpha: u8) -> u32 {
+        self.to_u32() << 8 | alpha as u32
+    }
+
+    /// The color and alpha value packed by `to_rgba_u32`.
+    pub const fn from_rgba_u32(packed: u32) -> (Color, u8) {
+        (Color::from_u32(packed >> 8), packed as u8)
+    }
+
+    /// The color and an alpha value packed as `0xAARRGGBB`: alpha in the highest byte.
+    pub const fn to_argb_u32(&self, alpha: u8) -> u32 {
+        (alpha as u32) << 24 | self.to_u32()
+    }
+
+    /// The color and alpha value packed by `to_argb_u32`.
+    pub const fn from_argb_u32(packed: u32) -> (Color, u8) {
+        (Color::from_u32(packed), (packed >> 24) as u8)
+    }
+
+    /// `to_rgba_u32` as little-endian bytes: `[a, b, g, r]`.
+    pub const fn to_le_bytes_rgba(&self, alpha: u8) -> [u8; 4] {
+        self.to_rgba_u32(alpha).to_le_bytes()
+    }
+
+    /// `to_rgba_u32` as big-endian bytes: `[r, g, b, a]`.
+    pub const fn to_be_bytes_rgba(&self, alpha: u8) -> [u8; 4] {
+        self.to_rgba_u32(alpha).to_be_bytes()
+    }
+
+    /// `to_argb_u32` as little-endian bytes: `[b, g, r, a]`, the `ARGB8888` pixels of most
+    /// framebuffers.
+    ///
+    /// ```
+    /// use led_json::Color;
+    ///
+    /// let color = Color::new(0xff, 0x00, 0xaa);
+    ///
+    /// assert_eq!(color.to_le_bytes_argb(0x80), [0xaa, 0x00, 0xff, 0x80]);
+    /// assert_eq!(Color::from_le_bytes_argb([0xaa, 0x00, 0xff, 0x80]), (color, 0x80));
+    /// assert_eq!(color.to_be_bytes_rgba(0x80), [0xff, 0x00, 0xaa, 0x80]);
+    /// ```
+    pub const fn to_le_bytes_argb(&self, alpha: u8) -> [u8; 4] {
+        self.to_argb_u32(alpha).to_le_bytes()
+    }
+
+    /// `to_argb_u32` as big-endian bytes: `[a, r, g, b]`, the order of network protocols.
+    pub const fn to_be_bytes_argb(&self, alpha: u8) -> [u8; 4] {
+        self.to_argb_u32(alpha).to_be_bytes()
+    }
+
+    /// The color and alpha value in bytes from `to_le_bytes_rgba`.
+    pub const fn from_le_bytes_rgba(bytes: [u8; 4]) -> (Color, u8) {
+        Color::from_rgba_u32(u32::from_le_bytes(bytes))
+    }
+
+    /// The color and alpha value in bytes from `to_be_bytes_rgba`.
+    pub const fn from_be_bytes_rgba(bytes: [u8; 4]) -> (Color, u8) {
+        Color::from_rgba_u32(u32::from_be_bytes(bytes))
+    }
+
+    /// The color and alpha value in bytes from `to_le_bytes_argb`.
+    pub const fn from_le_bytes_argb(bytes: [u8; 4]) -> (Color, u8) {
+        Color::from_argb_u32(u32::from_le_bytes(bytes))
+    }
+
+    /// The color and alpha value in bytes from `to_be_bytes_argb`.
+    pub const fn from_be_bytes_argb(bytes: [u8; 4]) -> (Color, u8) {
+        Color::from_argb_u32(u32::from_be_bytes(bytes))
+    }
+}
+
+/// A `Color` from a `#rgb` or `#rrggbb` literal, checked at compile time. Works in `const` and
+/// `static` items.
+///
+/// ```
+/// use led_json::{color, Color};
+///
+/// static PALETTE: [Color; 2] = [color!("#ff00aa"), color!("#0f0")];
+///
+/// assert_eq!(PALETTE[0], Color::new(255, 0, 170));
+/// assert_eq!(PALETTE[1], Color::new(0, 255, 0));
+/// ```
+///
+/// Invalid literals don't compile:
+///
+/// ```compile_fail
+/// let c = led_json::color!("#ff00zz");
+/// ```
+#[macro_export]
+macro_rules! color {
+    ($hex:literal) => {{
+        const COLOR: $crate::Color = $crate::Color::from_hex($hex);
+        COLOR
+    }};
+}
+
+#[cfg(feature = "std")]
+impl Color {
+    /// Linear interpolation between the two colors, channel by channel. `t` is clamped to
+    /// `0.0..=1.0`, where `0.0` is `self` and `1.0` is `other`.
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| {
+            let (a, b) = (f32::from(a), f32::from(b));
+
+            (a + (b - a) * t).round() as u8
+        };
+
+        Color {
+            red: channel(self.red, other.red),
+            green: channel(self.green, other.green),
+            blue: channel(self.blue, other.blue),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Formatted::new(self, ColorFormat::LongHex).fmt(f)
+    }
+}
+
+
+/// Value of a single ASCII hex digit. `position` is the byte offset of the digit in the original
+/// input and is only used for the error.
+fn hex_digit(byte: u8, position: usize) -> core::result::Result<u8, ParseColorError> {
+    match byte {
+        b'0'..=b'9' => Ok(byte - b'0'),
+        b'a'..=b'f' => Ok(byte - b'a' + 10),
+        b'A'..=b'F' => Ok(byte - b'A' + 10),
+        _ => Err(ParseColorError::InvalidHexDigit { position }),
+    }
+}
+
+/// Controls which prefixes `Color::parse_with` accepts in front of the hex digits, and whether
+/// common mistakes are repaired. Hex digits themselves are always case-insensitive.
+///
+/// The default is the same strict form `FromStr` and `Deserialize` use: a leading `#` is required
+/// and `0x` is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject input that doesn't start with `#` (or `0x` when `allow_0x` is set).
+    pub require_hash: bool,
+
+    /// Accept `0x` or `0X` in place of `#`, like `0xff00aa`.
+    pub allow_0x: bool,
+
+    /// Repair common mistakes instead of rejecting them: surrounding whitespace, a missing `#`
+    /// and five hex digits with a dropped leading zero. `Color::parse_with_warnings` reports what
+    /// was repaired.
+    pub lenient: bool,
+
+    /// Also accept terminal colors: xterm palette indexes like `ansi(196)` and the ANSI color
+    /// names, `black` to `white` and `bright-black` to `bright-white`.
+    pub allow_ansi: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions { require_hash: true, allow_0x: false, lenient: false, allow_ansi: false }
+    }
+}
+
+impl ParseOptions {
+    /// The default options with `lenient` turned on.
+    pub fn lenient() -> Self {
+        ParseOptions { lenient: true, ..ParseOptions::default() }
+    }
+}
+
+impl Color {
+    /// Parses `rgb` or `rrggbb` hex digits behind whichever prefixes `options` allows.
+    ///
+    /// This works on bytes rather than `str` slices so that no input can make it slice in the
+    /// middle of a character; it never panics.
+    ///
+    /// ```
+    /// use led_json::{Color, ParseOptions};
+    ///
+    /// let options = ParseOptions { require_hash: false, allow_0x: true, lenient: false, allow_ansi: false };
+    /// let magenta = Color { red: 255, green: 0, blue: 170 };
+    ///
+    /// assert_eq!(Color::parse_with("FF00AA", options), Ok(magenta.clone()));
+    /// assert_eq!(Color::parse_with("0xff00aa", options), Ok(magenta.clone()));
+    /// assert_eq!(Color::parse_with("#FF00AA", options), Ok(magenta));
+    /// ```
+    pub fn parse_with(s: &str, options: ParseOptions) -> core::result::Result<Color, ParseColorError> {
+        parse(s, options, &mut Warnings::new())
+    }
+
+    /// Like `parse_with`, but also returns what lenient parsing had to repair.
+    ///
+    /// ```
+    /// use led_json::{Color, ParseOptions, Warning};
+    ///
+    /// let (color, warnings) = Color::parse_with_warnings(" ff00a\n", ParseOptions::lenient()).unwrap();
+    ///
+    /// assert_eq!(color, Color { red: 15, green: 240, blue: 10 });
+    /// assert_eq!(warnings.into_iter().collect::<Vec<_>>(), vec![
+    ///     Warning::TrimmedWhitespace,
+    ///     Warning::MissingHash,
+    ///     Warning::PaddedLeadingZero,
+    /// ]);
+    /// ```
+    pub fn parse_with_warnings(s: &str, options: ParseOptions) -> core::result::Result<(Color, Warnings), ParseColorError> {
+        let mut warnings = Warnings::new();
+        let color = parse(s, options, &mut warnings)?;
+
+        Ok((color, warnings))
+    }
+}
+
+/// `Color::parse_with`, adding whatever lenient parsing repairs to `warnings`.
+pub(crate) fn parse(s: &str, options: ParseOptions, warnings: &mut Warnings) -> core::result::Result<Color, ParseColorError> {
+    if !s.is_ascii() {
+        return Err(ParseColorError::NonAsciiInput)
+    }
+
+    let mut bytes = s.as_bytes();
+
+    // offset of `bytes` in the original input
+    let mut start = 0;
+
+    if options.lenient {
+        let trimmed = s.trim_start_matches(|c: char| c.is_ascii_whitespace());
+
+        start = s.len() - trimmed.len();
+        bytes = trimmed.trim_end_matches(|c: char| c.is_ascii_whitespace()).as_bytes();
+
+        if bytes.len() != s.len() {
+            warnings.push(Warning::TrimmedWhitespace);
+        }
+    }
+
+    if options.allow_ansi {
+        // `bytes` is a trimmed slice of `s`, which is all ASCII
+        if let Some(result) = core::str::from_utf8(bytes).ok().and_then(ansi::parse) {
+            return result
+        }
+    }
+
+    // number of prefix bytes in front of the first digit
+    let offset = match bytes {
+        [b'#', ..] => 1,
+        [b'0', b'x' | b'X', ..] if options.allow_0x => 2,
+        _ if !options.require_hash => 0,
+        _ if options.lenient => {
+            warnings.push(Warning::MissingHash);
+            0
+        },
+        _ => return Err(ParseColorError::MissingPrefix),
+    };
+
+    let digits = &bytes[offset..];
+
+    // an implied zero in front of five digits
+    let padding = match digits.len() {
+        5 if options.lenient => {
+            warnings.push(Warning::PaddedLeadingZero);
+            1
+        },
+        _ => 0,
+    };
+
+    if options.lenient && digits.iter().any(u8::is_ascii_lowercase) && digits.iter().any(u8::is_ascii_uppercase) {
+        warnings.push(Warning::MixedCase);
+    }
+
+    // positions are offsets into the original input
+    let digit = |i: usize| match i.checked_sub(padding) {
+        Some(i) => hex_digit(digits[i], start + offset + i),
+        None => Ok(0),
+    };
+
+    let (red, green, blue) =
+        match digits.len() + padding {
+            3 => {
+                // 17 * c
+                let red = 17 * digit(0)?;
+                let green = 17 * digit(1)?;
+                let blue = 17 * digit(2)?;
+
+                (red, green, blue)
+            },
+            6 => {
+                // parse the double-digit hex value
+                let red = digit(0)? << 4 | digit(1)?;
+                let green = digit(2)? << 4 | digit(3)?;
+                let blue = digit(4)? << 4 | digit(5)?;
+
+                (red, green, blue)
+            },
+            _ => {
+                return Err(ParseColorError::InvalidLength(s.len()))
+            }
+        };
+
+    Ok(Color {
+        red,
+        green,
+        blue,
+    })
+}
+
+/// Parses `#rgb` or `#rrggbb`.
+pub(crate) fn parse_hex(s: &str) -> core::result::Result<Color, ParseColorError> {
+    Color::parse_with(s, ParseOptions::default())
+}
+
+/// A color from a packed `0xRRGGBB` integer, like `16711935` for `#ff00ff`.
+pub(crate) fn from_packed<E>(value: u64) -> core::result::Result<Color, E>
+where
+    E: de::Error,
+{
+    if value > 0xff_ff_ff {
+        return Err(E::invalid_value(Unexpected::Unsigned(value), &"a packed 0xRRGGBB color"))
+    }
+
+    Ok(Color {
+        red: (value >> 16) as u8,
+        green: (value >> 8) as u8,
+        blue: value as u8,
+    })
+}
+
+/// A color from `[red, green, blue]` or `[red, green, blue, alpha]` channel values. The alpha is
+/// checked but otherwise ignored.
+pub(crate) fn from_channels<E>(channels: &[u64]) -> core::result::Result<Color, E>
+where
+    E: de::Error,
+{
+    if channels.len() != 3 && channels.len() != 4 {
+        return Err(E::invalid_length(channels.len(), &"3 or 4 channel values"))
+    }
+
+    let mut rgb = [0; 3];
+
+    for (i, &channel) in channels.iter().enumerate() {
+        if channel > 255 {
+            return Err(E::invalid_value(Unexpected::Unsigned(channel), &"a channel value from 0 to 255"))
+        }
+
+        if let Some(c) = rgb.get_mut(i) {
+            *c = channel as u8;
+        }
+    }
+
+    let [red, green, blue] = rgb;
+
+    Ok(Color { red, green, blue })
+}
+
+/// Integer formats like TOML only hand out `i64`s.
+pub(crate) fn unsigned<E>(value: i64) -> core::result::Result<u64, E>
+where
+    E: de::Error,
+{
+    u64::try_from(value).map_err(|_| E::invalid_value(Unexpected::Signed(value), &"a non-negative integer"))
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        parse_hex(s)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Formatted::new(self, ColorFormat::LongHex).serialize(serializer)
+    }
+}
+
+/// Turns the string form of a color into a `Color`. `Deserialize` uses `parse_hex`; a
+/// `ParserCache` puts itself in front of it.
+pub(crate) type ParseStr<'a> = &'a dyn Fn(&str) -> core::result::Result<Color, ParseColorError>;
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with(deserializer, &parse_hex)
+    }
+}
+
+/// `Color::deserialize`, with strings parsed by `parse`.
+pub(crate) fn deserialize_with<'de, D>(deserializer: D, parse: ParseStr<'_>) -> core::result::Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return binary::deserialize_color(deserializer)
+    }
+
+    // the structured form, `{ "r": 255, "g": 0, "b": 255 }`
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Channels {
+        #[serde(alias = "red")]
+        r: u8,
+        #[serde(alias = "green")]
+        g: u8,
+        #[serde(alias = "blue")]
+        b: u8,
+    }
+
+    struct HexStringOrMap<'a>(ParseStr<'a>);
+
+    impl<'de> Visitor<'de> for HexStringOrMap<'_> {
+        type Value = Color;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a hex color string, an object with r, g and b, a packed integer or an array of channels")
+        }
+
+        fn visit_u64<E>(self, value: u64) -> core::result::Result<Color, E>
+        where
+            E: de::Error,
+        {
+            from_packed(value)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> core::result::Result<Color, E>
+        where
+            E: de::Error,
+        {
+            from_packed(unsigned(value)?)
+        }
+
+        fn visit_seq<S>(self, mut seq: S) -> core::result::Result<Color, S::Error>
+        where
+            S: SeqAccess<'de>,
+        {
+            let mut channels: Vec<u64> = Vec::new();
+
+            while let Some(channel) = seq.next_element::<u64>()? {
+                // no need to keep reading once it's too long to be a color
+                if channels.len() == 4 {
+                    return Err(de::Error::invalid_length(5, &"3 or 4 channel values"))
+                }
+
+                channels.push(channel);
+            }
+
+            from_channels(&channels)
+        }
+
+        fn visit_str<E>(self, value: &str) -> core::result::Result<Color, E>
+        where
+            E: de::Error,
+        {
+            (self.0)(value).map_err(E::custom)
+        }
+
+        // Spelled out so neither ever copies the string: a string borrowed from the input is
+        // parsed in place, and an owned one, like a `Cow::Owned` or a JSON string with escapes
+        // that had to be rebuilt, is parsed and dropped.
+        fn visit_borrowed_str<E>(self, value: &'de str) -> core::result::Result<Color, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(value)
+        }
+
+        fn visit_string<E>(self, value: String) -> core::result::Result<Color, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(&value)
+        }
+
+        fn visit_map<M>(self, map: M) -> core::result::Result<Color, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let c = Channels::deserialize(de::value::MapAccessDeserializer::new(map))?;
+
+            Ok(Color { red: c.r, green: c.g, blue: c.b })
+        }
+    }
+
+    deserializer.deserialize_any(HexStringOrMap(parse))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn short_and_long() {
+            assert_eq!(parse_hex("#f0a"), Ok(Color { red: 255, green: 0, blue: 170 }));
+            assert_eq!(parse_hex("#FF00aA"), Ok(Color { red: 255, green: 0, blue: 170 }));
+            assert_eq!(parse_hex("#0f1e2d"), Ok(Color { red: 15, green: 30, blue: 45 }));
+        }
+
+        #[test]
+        fn missing_prefix() {
+            assert_eq!(parse_hex(""), Err(ParseColorError::MissingPrefix));
+            assert_eq!(parse_hex("fff"), Err(ParseColorError::MissingPrefix));
+        }
+
+        #[test]
+        fn invalid_length() {
+            assert_eq!(parse_hex("#"), Err(ParseColorError::InvalidLength(1)));
+            assert_eq!(parse_hex("#ffff"), Err(ParseColorError::InvalidLength(5)));
+        }
+
+        #[test]
+        fn non_ascii() {
+            assert_eq!(parse_hex("#é0f"), Err(ParseColorError::NonAsciiInput));
+            assert_eq!(parse_hex("#ffé0"), Err(ParseColorError::NonAsciiInput));
+            assert_eq!(parse_hex("é"), Err(ParseColorError::NonAsciiInput));
+        }
+
+        #[test]
+        fn invalid_hex_digit() {
+            assert_eq!(parse_hex("#fgf"), Err(ParseColorError::InvalidHexDigit { position: 2 }));
+            assert_eq!(parse_hex("#00000z"), Err(ParseColorError::InvalidHexDigit { position: 6 }));
+        }
+
+        #[test]
+        fn sign_is_not_a_digit() {
+            // u8::from_str_radix would have accepted these
+            assert_eq!(parse_hex("#+f+f+f"), Err(ParseColorError::InvalidHexDigit { position: 1 }));
+        }
+    }
+
+    mod from_hex {
+        use super::*;
+
+        const MAGENTA: Color = Color::from_hex("#FF00aa");
+
+        #[test]
+        fn matches_parse_hex() {
+            assert_eq!(MAGENTA, Color::new(255, 0, 170));
+            assert_eq!(color!("#f0a"), MAGENTA);
+
+            for s in ["#000", "#fff", "#0f1e2d", "#AbCdEf", "#9a9"].iter() {
+                assert_eq!(Ok(Color::from_hex(s)), parse_hex(s));
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "invalid hex digit")]
+        fn invalid_digit() {
+            Color::from_hex("#ffg");
+        }
+
+        #[test]
+        #[should_panic(expected = "3 or 6 digits")]
+        fn invalid_length() {
+            Color::from_hex("#ffff");
+        }
+
+        #[test]
+        #[should_panic(expected = "starts with `#`")]
+        fn missing_prefix() {
+            Color::from_hex("ff00aa");
+        }
+    }
+
+    mod packed {
+        use super::*;
+
+        #[test]
+        fn u32_round_trip() {
+            let color = Color::new(0x12, 0x34, 0x56);
+
+            assert_eq!(color.to_u32(), 0x123456);
+            assert_eq!(Color::from_u32(color.to_u32()), color);
+            assert_eq!(Color::from_u32(0xff123456), color);
+
+            assert_eq!(color.to_rgba_u32(0xff), 0x123456ff);
+            assert_eq!(Color::from_rgba_u32(0x12345600), (color, 0));
+
+            assert_eq!(color.to_argb_u32(0xff), 0xff123456);
+            assert_eq!(Color::from_argb_u32(0x00123456), (color, 0));
+        }
+
+        #[test]
+        fn bytes() {
+            let color = Color::new(0x12, 0x34, 0x56);
+
+            assert_eq!(color.to_le_bytes_rgba(0x78), [0x78, 0x56, 0x34, 0x12]);
+            assert_eq!(color.to_be_bytes_rgba(0x78), [0x12, 0x34, 0x56, 0x78]);
+            assert_eq!(color.to_le_bytes_argb(0x78), [0x56, 0x34, 0x12, 0x78]);
+            assert_eq!(color.to_be_bytes_argb(0x78), [0x78, 0x12, 0x34, 0x56]);
+
+            assert_eq!(Color::from_le_bytes_rgba(color.to_le_bytes_rgba(0x78)), (color, 0x78));
+            assert_eq!(Color::from_be_bytes_rgba(color.to_be_bytes_rgba(0x78)), (color, 0x78));
+            assert_eq!(Color::from_le_bytes_argb(color.to_le_bytes_argb(0x78)), (color, 0x78));
+            assert_eq!(Color::from_be_bytes_argb(color.to_be_bytes_argb(0x78)), (color, 0x78));
+        }
+
+        #[test]
+        fn order_matches_u32() {
+            let mut colors = vec![Color::new(0, 0, 255), Color::new(1, 0, 0), Color::new(0, 255, 0), Color::new(0, 0, 0)];
+            colors.sort();
+
+            assert_eq!(colors, vec![Color::new(0, 0, 0), Color::new(0, 0, 255), Color::new(0, 255, 0), Color::new(1, 0, 0)]);
+            assert!(colors.windows(2).all(|w| w[0].to_u32() < w[1].to_u32()));
+        }
+
+        #[test]
+        fn dedup() {
+            let parsed: Vec<Color> = ["#f00", "#ff0000", "#00f", "#F00"].iter().map(|s| s.parse().unwrap()).collect();
+            let unique: alloc::collections::BTreeSet<Color> = parsed.iter().copied().collect();
+
+            assert_eq!(unique.into_iter().collect::<Vec<_>>(), vec![Color::new(0, 0, 255), Color::new(255, 0, 0)]);
+
+            #[cfg(feature = "std")]
+            assert_eq!(parsed.into_iter().collect::<std::collections::HashSet<_>>().len(), 2);
+        }
+    }
+
+    mod lenient {
+        use super::*;
+
+        const MAGENTA: Color = Color { red: 255, green: 0, blue: 170 };
+
+        fn lenient(s: &str) -> core::result::Result<(Color, Vec<Warning>), ParseColorError> {
+            Color::parse_with_warnings(s, ParseOptions::lenient()).map(|(c, w)| (c, w.into_iter().collect()))
+        }
+
+        #[test]
+        fn valid_input_has_no_warnings() {
+            assert_eq!(lenient("#ff00aa"), Ok((MAGENTA, vec![])));
+            assert_eq!(lenient("#F0A"), Ok((MAGENTA, vec![])));
+        }
+
+        #[test]
+        fn repairs() {
+            assert_eq!(lenient(" #ff00aa\t"), Ok((MAGENTA, vec![Warning::TrimmedWhitespace])));
+            assert_eq!(lenient("ff00aa"), Ok((MAGENTA, vec![Warning::MissingHash])));
+            assert_eq!(lenient("#fff00"), Ok((Color { red: 15, green: 255, blue: 0 }, vec![Warning::PaddedLeadingZero])));
+            assert_eq!(lenient("#Ff00aA"), Ok((MAGENTA, vec![Warning::MixedCase])));
+        }
+
+        #[test]
+        fn still_rejects_garbage() {
+            assert_eq!(lenient("#ff"), Err(ParseColorError::InvalidLength(3)));
+            assert_eq!(lenient("  #ffg"), Err(ParseColorError::InvalidHexDigit { position: 5 }));
+            assert_eq!(lenient("#ff0g0"), Err(ParseColorError::InvalidHexDigit { position: 4 }));
+            assert_eq!(lenient(""), Err(ParseColorError::InvalidLength(0)));
+            assert_eq!(lenient("#é"), Err(ParseColorError::NonAsciiInput));
+        }
+
+        #[test]
+        fn strict_is_unchanged() {
+            assert_eq!(parse_hex(" #ff00aa"), Err(ParseColorError::MissingPrefix));
+            assert_eq!(parse_hex("#ff00aa "), Err(ParseColorError::InvalidLength(8)));
+            assert_eq!(parse_hex("#fff00"), Err(ParseColorError::InvalidLength(6)));
+        }
+    }
+
+    mod parse_with {
+        use super::*;
+
+        const LENIENT: ParseOptions = ParseOptions { require_hash: false, allow_0x: true, lenient: false, allow_ansi: false };
+        const MAGENTA: Color = Color { red: 255, green: 0, blue: 170 };
+
+        #[test]
+        fn default_is_strict() {
+            assert_eq!(Color::parse_with("#FF00AA", ParseOptions::default()), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("FF00AA", ParseOptions::default()), Err(ParseColorError::MissingPrefix));
+            assert_eq!(Color::parse_with("0xff00aa", ParseOptions::default()), Err(ParseColorError::MissingPrefix));
+        }
+
+        #[test]
+        fn lenient() {
+            assert_eq!(Color::parse_with("FF00AA", LENIENT), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("0xff00aa", LENIENT), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("0XFF00AA", LENIENT), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("#FF00AA", LENIENT), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("f0a", LENIENT), Ok(MAGENTA));
+        }
+
+        #[test]
+        fn only_0x() {
+            let options = ParseOptions { require_hash: true, allow_0x: true, lenient: false, allow_ansi: false };
+
+            assert_eq!(Color::parse_with("0xf0a", options), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("f0a", options), Err(ParseColorError::MissingPrefix));
+        }
+
+        #[test]
+        fn ansi() {
+            let options = ParseOptions { allow_ansi: true, ..ParseOptions::lenient() };
+
+            assert_eq!(Color::parse_with("ansi(196)", options), Ok(Color { red: 255, green: 0, blue: 0 }));
+            assert_eq!(Color::parse_with(" bright-blue ", options), Ok(Color { red: 92, green: 92, blue: 255 }));
+            assert_eq!(Color::parse_with("ansi(999)", options), Err(ParseColorError::InvalidAnsi("ansi(999)".into())));
+            assert_eq!(Color::parse_with("#f0a", options), Ok(MAGENTA));
+            assert_eq!(Color::parse_with("red", ParseOptions::lenient()), Err(ParseColorError::InvalidHexDigit { position: 0 }));
+        }
+
+        #[test]
+        fn errors_point_into_the_input() {
+            assert_eq!(Color::parse_with("0xff00ag", LENIENT), Err(ParseColorError::InvalidHexDigit { position: 7 }));
+            assert_eq!(Color::parse_with("fg0", LENIENT), Err(ParseColorError::InvalidHexDigit { position: 1 }));
+            assert_eq!(Color::parse_with("0x", LENIENT), Err(ParseColorError::InvalidLength(2)));
+            assert_eq!(Color::parse_with("", LENIENT), Err(ParseColorError::InvalidLength(0)));
+        }
+    }
+
+    mod serde_impls {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let color: Color = serde_json::from_str(r##""#f0a""##).unwrap();
+
+            assert_eq!(color, Color { red: 255, green: 0, blue: 170 });
+            assert_eq!(serde_json::to_string(&color).unwrap(), r##""#ff00aa""##);
+        }
+
+        #[test]
+        fn structured() {
+            let color: Color = serde_json::from_str(r#"{ "r": 255, "g": 0, "b": 170 }"#).unwrap();
+
+            assert_eq!(color, Color { red: 255, green: 0, blue: 170 });
+
+            let color: Color = serde_json::from_str(r#"{ "red": 1, "green": 2, "blue": 3 }"#).unwrap();
+
+            assert_eq!(color, Color { red: 1, green: 2, blue: 3 });
+        }
+
+        #[test]
+        fn structured_rejects_invalid() {
+            assert!(serde_json::from_str::<Color>(r#"{ "r": 256, "g": 0, "b": 0 }"#).is_err());
+            assert!(serde_json::from_str::<Color>(r#"{ "r": 0, "g": 0 }"#).is_err());
+            assert!(serde_json::from_str::<Color>(r#"{ "r": 0, "g": 0, "b": 0, "a": 0 }"#).is_err());
+        }
+
+        #[test]
+        fn packed() {
+            assert_eq!(serde_json::from_str::<Color>("16711935").unwrap(), Color { red: 255, green: 0, blue: 255 });
+            assert_eq!(serde_json::from_str::<Color>("0").unwrap(), Color { red: 0, green: 0, blue: 0 });
+        }
+
+        #[test]
+        fn packed_rejects_invalid() {
+            let err = serde_json::from_str::<Color>("16777216").unwrap_err();
+
+            assert!(err.to_string().contains("expected a packed 0xRRGGBB color"), "{}", err);
+            assert!(serde_json::from_str::<Color>("-1").is_err());
+        }
+
+        #[test]
+        fn channels() {
+            assert_eq!(serde_json::from_str::<Color>("[255, 0, 170]").unwrap(), Color { red: 255, green: 0, blue: 170 });
+            assert_eq!(serde_json::from_str::<Color>("[1, 2, 3, 255]").unwrap(), Color { red: 1, green: 2, blue: 3 });
+        }
+
+        #[test]
+        fn channels_reject_invalid() {
+            let err = serde_json::from_str::<Color>("[255, 256, 0]").unwrap_err();
+
+            assert!(err.to_string().contains("invalid value: integer `256`"), "{}", err);
+            assert!(serde_json::from_str::<Color>("[1, 2]").is_err());
+            assert!(serde_json::from_str::<Color>("[1, 2, 3, 4, 5]").is_err());
+            assert!(serde_json::from_str::<Color>("[1, 2, -3]").is_err());
+        }
+
+        #[test]
+        fn borrowed_owned_and_cow_strings() {
+            use alloc::borrow::Cow;
+            use de::value::{BorrowedStrDeserializer, CowStrDeserializer, Error, StringDeserializer};
+
+            let magenta = Color { red: 255, green: 0, blue: 255 };
+
+            assert_eq!(Color::deserialize(BorrowedStrDeserializer::<Error>::new("#f0f")), Ok(magenta));
+            assert_eq!(Color::deserialize(StringDeserializer::<Error>::new("#f0f".into())), Ok(magenta));
+            assert_eq!(Color::deserialize(CowStrDeserializer::<Error>::new(Cow::Borrowed("#f0f"))), Ok(magenta));
+            assert_eq!(Color::deserialize(CowStrDeserializer::<Error>::new(Cow::Owned("#f0f".into()))), Ok(magenta));
+            assert!(Color::deserialize(StringDeserializer::<Error>::new("f0f".into())).is_err());
+
+            // an escaped `#` can't be borrowed from the input
+            assert_eq!(serde_json::from_str::<Color>(r#""\u0023f0f""#).unwrap(), magenta);
+        }
+
+        #[test]
+        fn rejects_invalid() {
+            assert!(serde_json::from_str::<Color>(r##""rainbow""##).is_err());
+            assert!(serde_json::from_str::<Color>("true").is_err());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod lerp {
+        use super::*;
+
+        const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+        const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+        #[test]
+        fn endpoints() {
+            assert_eq!(BLACK.lerp(&WHITE, 0.0), BLACK);
+            assert_eq!(BLACK.lerp(&WHITE, 1.0), WHITE);
+        }
+
+        #[test]
+        fn midpoint() {
+            assert_eq!(BLACK.lerp(&WHITE, 0.5), Color { red: 128, green: 128, blue: 128 });
+
+            let a = Color { red: 255, green: 0, blue: 100 };
+            let b = Color { red: 0, green: 255, blue: 100 };
+
+            assert_eq!(a.lerp(&b, 0.25), Color { red: 191, green: 64, blue: 100 });
+        }
+
+        #[test]
+        fn clamps_t() {
+            assert_eq!(BLACK.lerp(&WHITE, -1.0), BLACK);
+            assert_eq!(BLACK.lerp(&WHITE, 2.0), WHITE);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Conversions to and from the `colorgrad` crate's gradients, and its presets as fill keywords.
+
+use colorgrad::{Gradient, GradientBuilder, LinearGradient};
+
+use crate::color::Color;
+use crate::custom::CustomFill;
+use crate::fill::Fill;
+use crate::fill_keywords;
+
+fn to_colorgrad(color: &Color) -> colorgrad::Color {
+    colorgrad::Color::from_rgba8(color.red, color.green, color.blue, 255)
+}
+
+fn from_colorgrad(color: colorgrad::Color) -> Color {
+    let [red, green, blue, _] = color.to_rgba8();
+
+    Color { red, green, blue }
+}
+
+impl Fill {
+    /// The stops of any kind of gradient as an evenly spaced `colorgrad::LinearGradient` over
+    /// `0.0..=1.0`, blending channel by channel like `Fill::sample`. Anything other than a
+    /// non-empty gradient gives `None`.
+    ///
+    /// ```
+    /// use colorgrad::Gradient;
+    /// use led_json::Fill;
+    ///
+    /// let fill: Fill = serde_json::from_str(r##"["#ff0000", "#0000ff"]"##).unwrap();
+    /// let gradient = fill.to_colorgrad().unwrap();
+    ///
+    /// assert_eq!(gradient.at(0.5).to_rgba8(), [128, 0, 128, 255]);
+    /// ```
+    pub fn to_colorgrad(&self) -> Option<LinearGradient> {
+        match self.stops() {
+            Some(colors) if !colors.is_empty() => {
+                let stops: Vec<colorgrad::Color> = colors.iter().map(to_colorgrad).collect();
+
+                GradientBuilder::new().colors(&stops).build().ok()
+            },
+            _ => None,
+        }
+    }
+
+    /// A `Fill::Gradient` of `stops` colors sampled evenly across any colorgrad gradient's
+    /// domain. The alpha channel is dropped. Asking for no stops gives one, the gradient's first
+    /// color, since an empty gradient isn't a valid fill.
+    pub fn from_colorgrad<G: Gradient + ?Sized>(gradient: &G, stops: usize) -> Fill {
+        Fill::Gradient(gradient.colors(stops.max(1)).map(from_colorgrad).collect())
+    }
+}
+
+fill_keywords! {
+    /// colorgrad's preset gradients, named as in `colorgrad::preset`. Deserialize a
+    /// `CustomFill<ColorgradPreset>` to accept them alongside colors and gradients, so
+    /// `"viridis"` is a valid fill.
+    ///
+    /// `"rainbow"` is colorgrad's rainbow here, not `Fill::Rainbow`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ColorgradPreset {
+        Sinebow = "sinebow",
+        Turbo = "turbo",
+        Cividis = "cividis",
+        Cubehelix = "cubehelix_default",
+        Warm = "warm",
+        Cool = "cool",
+        Rainbow = "rainbow",
+        BrBg = "br_bg",
+        PrGn = "pr_gn",
+        PiYg = "pi_yg",
+        PuOr = "pu_or",
+        RdBu = "rd_bu",
+        RdGy = "rd_gy",
+        RdYlBu = "rd_yl_bu",
+        RdYlGn = "rd_yl_gn",
+        Spectral = "spectral",
+        Blues = "blues",
+        Greens = "greens",
+        Greys = "greys",
+        Oranges = "oranges",
+        Purples = "purples",
+        Reds = "reds",
+        Viridis = "viridis",
+        Inferno = "inferno",
+        Magma = "magma",
+        Plasma = "plasma",
+        BuGn = "bu_gn",
+        BuPu = "bu_pu",
+        GnBu = "gn_bu",
+        OrRd = "or_rd",
+        PuBuGn = "pu_bu_gn",
+        PuBu = "pu_bu",
+        PuRd = "pu_rd",
+        RdPu = "rd_pu",
+        YlGnBu = "yl_gn_bu",
+        YlGn = "yl_gn",
+        YlOrBr = "yl_or_br",
+        YlOrRd = "yl_or_rd",
+    }
+}
+
+impl ColorgradPreset {
+    /// The preset's gradient.
+    pub fn gradient(&self) -> Box<dyn Gradient> {
+        use colorgrad::preset;
+
+        match self {
+            ColorgradPreset::Sinebow => preset::sinebow().boxed(),
+            ColorgradPreset::Turbo => preset::turbo().boxed(),
+            ColorgradPreset::Cividis => preset::cividis().boxed(),
+            ColorgradPreset::Cubehelix => preset::cubehelix_default().boxed(),
+            ColorgradPreset::Warm => preset::warm().boxed(),
+            ColorgradPreset::Cool => preset::cool().boxed(),
+            ColorgradPreset::Rainbow => preset::rainbow().boxed(),
+            ColorgradPreset::BrBg => preset::br_bg().boxed(),
+            ColorgradPreset::PrGn => preset::pr_gn().boxed(),
+            ColorgradPreset::PiYg => preset::pi_yg().boxed(),
+            ColorgradPreset::PuOr => preset::pu_or().boxed(),
+            ColorgradPreset::RdBu => preset::rd_bu().boxed(),
+            ColorgradPreset::RdGy => preset::rd_gy().boxed(),
+            ColorgradPreset::RdYlBu => preset::rd_yl_bu().boxed(),
+            ColorgradPreset::RdYlGn => preset::rd_yl_gn().boxed(),
+            ColorgradPreset::Spectral => preset::spectral().boxed(),
+            ColorgradPreset::Blues => preset::blues().boxed(),
+            ColorgradPreset::Greens => preset::greens().boxed(),
+            ColorgradPreset::Greys => preset::greys().boxed(),
+            ColorgradPreset::Oranges => preset::oranges().boxed(),
+            ColorgradPreset::Purples => preset::purples().boxed(),
+            ColorgradPreset::Reds => preset::reds().boxed(),
+            ColorgradPreset::Viridis => preset::viridis().boxed(),
+            ColorgradPreset::Inferno => preset::inferno().boxed(),
+            ColorgradPreset::Magma => preset::magma().boxed(),
+            ColorgradPreset::Plasma => preset::plasma().boxed(),
+            ColorgradPreset::BuGn => preset::bu_gn().boxed(),
+            ColorgradPreset::BuPu => preset::bu_pu().boxed(),
+            ColorgradPreset::GnBu => preset::gn_bu().boxed(),
+            ColorgradPreset::OrRd => preset::or_rd().boxed(),
+            ColorgradPreset::PuBuGn => preset::pu_bu_gn().boxed(),
+            ColorgradPreset::PuBu => preset::pu_bu().boxed(),
+            ColorgradPreset::PuRd => preset::pu_rd().boxed(),
+            ColorgradPreset::RdPu => preset::rd_pu().boxed(),
+            ColorgradPreset::YlGnBu => preset::yl_gn_bu().boxed(),
+            ColorgradPreset::YlGn => preset::yl_gn().boxed(),
+            ColorgradPreset::YlOrBr => preset::yl_or_br().boxed(),
+            ColorgradPreset::YlOrRd => preset::yl_or_rd().boxed(),
+        }
+    }
+
+    /// The preset as a `Fill::Gradient` of `stops` colors, or of one if `stops` is `0`.
+    pub fn to_fill(&self, stops: usize) -> Fill {
+        Fill::from_colorgrad(self.gradient().as_ref(), stops)
+    }
+}
+
+impl CustomFill<ColorgradPreset> {
+    /// A plain `Fill`, with a preset sampled into `stops` colors.
+    ///
+    /// ```
+    /// use led_json::{ColorgradPreset, CustomFill, Fill};
+    ///
+    /// let fill: CustomFill<ColorgradPreset> = serde_json::from_str(r#""viridis""#).unwrap();
+    ///
+    /// assert_eq!(fill.to_fill(8).stops().map(<[_]>::len), Some(8));
+    /// ```
+    pub fn to_fill(&self, stops: usize) -> Fill {
+        match self {
+            CustomFill::Keyword(preset) => preset.to_fill(stops),
+            CustomFill::Color(color) => Fill::Color(*color),
+            CustomFill::Gradient(colors) => Fill::Gradient(colors.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    #[test]
+    fn round_trip() {
+        let fill = Fill::Gradient(vec![RED, Color { red: 0, green: 255, blue: 0 }, BLUE]);
+        let gradient = fill.to_colorgrad().unwrap();
+
+        assert_eq!(Fill::from_colorgrad(&gradient, 3), fill);
+        assert_eq!(from_colorgrad(gradient.at(0.25)), Color { red: 128, green: 128, blue: 0 });
+    }
+
+    #[test]
+    fn other_gradients() {
+        let conic = Fill::Conic { angle: 45.0, stops: vec![RED, BLUE] };
+
+        assert_eq!(Fill::from_colorgrad(&conic.to_colorgrad().unwrap(), 2), Fill::Gradient(vec![RED, BLUE]));
+        assert_eq!(Fill::from_colorgrad(&Fill::Gradient(vec![RED]).to_colorgrad().unwrap(), 2), Fill::Gradient(vec![RED, RED]));
+
+        assert!(Fill::Gradient(vec![]).to_colorgrad().is_none());
+        assert!(Fill::Color(RED).to_colorgrad().is_none());
+        assert!(Fill::rainbow().to_colorgrad().is_none());
+    }
+
+    #[test]
+    fn at_least_one_stop() {
+        let gradient = Fill::Gradient(vec![RED, BLUE]).to_colorgrad().unwrap();
+        let fill = Fill::from_colorgrad(&gradient, 0);
+
+        assert_eq!(fill, Fill::Gradient(vec![RED]));
+        assert_eq!(serde_json::from_str::<Fill>(&serde_json::to_string(&fill).unwrap()).unwrap(), fill);
+        assert_eq!(ColorgradPreset::Viridis.to_fill(0).stops().map(<[_]>::len), Some(1));
+    }
+
+    #[test]
+    fn presets() {
+        let fill: CustomFill<ColorgradPreset> = serde_json::from_str(r#""rd_yl_bu""#).unwrap();
+
+        assert_eq!(fill, CustomFill::Keyword(ColorgradPreset::RdYlBu));
+        assert_eq!(fill.to_fill(2), Fill::Gradient(vec![
+            Color { red: 165, green: 0, blue: 38 },
+            Color { red: 49, green: 54, blue: 149 },
+        ]));
+
+        assert_eq!(serde_json::to_string(&CustomFill::Keyword(ColorgradPreset::Viridis)).unwrap(), r#""viridis""#);
+        assert!(serde_json::from_str::<CustomFill<ColorgradPreset>>(r#""viridian""#).is_err());
+    }
+
+    #[test]
+    fn colors_pass_through() {
+        let fill: CustomFill<ColorgradPreset> = serde_json::from_str(r##"["#ff0000", "#0000ff"]"##).unwrap();
+
+        assert_eq!(fill.to_fill(16), Fill::Gradient(vec![RED, BLUE]));
+    }
+}
@@ -0,0 +1,194 @@
+//! The numbers behind this crate's color space conversions, for numeric code that needs the same
+//! ones: the sRGB matrices and transfer function, CIE white points, and the Bradford and Oklab
+//! matrices.
+//!
+//! XYZ values are normalized so that white has Y = 1. Matrices multiply column vectors: the first
+//! row of `SRGB_TO_XYZ` dotted with linear `[r, g, b]` is X.
+//!
+//! ```
+//! use led_json::consts::{SRGB_TO_XYZ, WHITE_D65};
+//!
+//! // linear sRGB white is the D65 white point
+//! let y: f64 = SRGB_TO_XYZ[1].iter().sum();
+//!
+//! assert!((y - WHITE_D65[1]).abs() < 1e-6);
+//! ```
+
+/// Linear sRGB to XYZ under D65.
+pub const SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+
+/// XYZ under D65 to linear sRGB, the inverse of `SRGB_TO_XYZ`.
+pub const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// The exponent of the sRGB transfer function's curved part.
+pub const SRGB_GAMMA: f64 = 2.4;
+
+/// The offset of the sRGB transfer function's curved part, `1.055 * c ^ (1 / 2.4) - 0.055`.
+pub const SRGB_OFFSET: f64 = 0.055;
+
+/// The slope of the sRGB transfer function's linear part near black.
+pub const SRGB_SLOPE: f64 = 12.92;
+
+/// The encoded value below which decoding sRGB is linear.
+pub const SRGB_DECODE_THRESHOLD: f64 = 0.04045;
+
+/// The linear value below which encoding sRGB is linear.
+pub const SRGB_ENCODE_THRESHOLD: f64 = 0.0031308;
+
+/// CIE L\*a\*b\*'s ε, `(6 / 29) ^ 3`: below it, the cube root is replaced by a straight line.
+pub const LAB_EPSILON: f64 = 216.0 / 24389.0;
+
+/// CIE L\*a\*b\*'s κ, `(29 / 3) ^ 3`, the slope of that line.
+pub const LAB_KAPPA: f64 = 24389.0 / 27.0;
+
+/// White points in XYZ for the CIE 1931 2° observer. `WHITE_D65` is sRGB's.
+pub const WHITE_A: [f64; 3] = [1.09850, 1.0, 0.35585];
+pub const WHITE_D50: [f64; 3] = [0.96422, 1.0, 0.82521];
+pub const WHITE_D55: [f64; 3] = [0.95682, 1.0, 0.92149];
+pub const WHITE_D65: [f64; 3] = [0.95047, 1.0, 1.08883];
+pub const WHITE_D75: [f64; 3] = [0.94972, 1.0, 1.22638];
+pub const WHITE_F2: [f64; 3] = [0.99187, 1.0, 0.67395];
+pub const WHITE_F11: [f64; 3] = [1.00962, 1.0, 0.64350];
+
+/// XYZ to the Bradford cone responses, for chromatic adaptation.
+pub const BRADFORD: [[f64; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// The inverse of `BRADFORD`.
+pub const BRADFORD_INVERSE: [[f64; 3]; 3] = [
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+];
+
+/// Linear sRGB to Oklab's LMS cone responses, which are cube rooted before `LMS_TO_OKLAB`.
+pub const SRGB_TO_LMS: [[f64; 3]; 3] = [
+    [0.4122214708, 0.5363325363, 0.0514459929],
+    [0.2119034982, 0.6806995451, 0.1073969566],
+    [0.0883024619, 0.2817188376, 0.6299787005],
+];
+
+/// Cube rooted LMS to Oklab's `[L, a, b]`.
+pub const LMS_TO_OKLAB: [[f64; 3]; 3] = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+/// Oklab to cube rooted LMS, the inverse of `LMS_TO_OKLAB`.
+pub const OKLAB_TO_LMS: [[f64; 3]; 3] = [
+    [1.0, 0.3963377774, 0.2158037573],
+    [1.0, -0.1055613458, -0.0638541728],
+    [1.0, -0.0894841775, -1.2914855480],
+];
+
+/// LMS, once cubed, to linear sRGB, the inverse of `SRGB_TO_LMS`.
+pub const LMS_TO_SRGB: [[f64; 3]; 3] = [
+    [4.0767416621, -3.3077115913, 0.2309699292],
+    [-1.2684380046, 2.6097574011, -0.3413193965],
+    [-0.0041960863, -0.7034186147, 1.7076147010],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Matrix = [[f64; 3]; 3];
+
+    fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+        let mut product = [[0.0; 3]; 3];
+
+        for (i, row) in product.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+            }
+        }
+
+        product
+    }
+
+    fn assert_identity(m: Matrix, tolerance: f64) {
+        for (i, row) in m.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+
+                assert!((cell - expected).abs() < tolerance, "{:?}", m);
+            }
+        }
+    }
+
+    fn row_sums(m: &Matrix) -> [f64; 3] {
+        [m[0].iter().sum(), m[1].iter().sum(), m[2].iter().sum()]
+    }
+
+    #[test]
+    fn inverses() {
+        assert_identity(multiply(&SRGB_TO_XYZ, &XYZ_TO_SRGB), 1e-6);
+        assert_identity(multiply(&BRADFORD, &BRADFORD_INVERSE), 1e-6);
+        assert_identity(multiply(&LMS_TO_OKLAB, &OKLAB_TO_LMS), 1e-6);
+        assert_identity(multiply(&SRGB_TO_LMS, &LMS_TO_SRGB), 1e-6);
+    }
+
+    #[test]
+    fn srgb_white_is_d65() {
+        for (x, white) in row_sums(&SRGB_TO_XYZ).iter().zip(WHITE_D65) {
+            assert!((x - white).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn oklab_white() {
+        // white has L = 1 and no color, and the LMS of white is 1, 1, 1
+        let [l, a, b] = row_sums(&LMS_TO_OKLAB);
+
+        assert!((l - 1.0).abs() < 1e-6 && a.abs() < 1e-6 && b.abs() < 1e-6);
+
+        for lms in row_sums(&SRGB_TO_LMS) {
+            assert!((lms - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn transfer_function_is_continuous() {
+        assert!((SRGB_DECODE_THRESHOLD / SRGB_SLOPE - SRGB_ENCODE_THRESHOLD).abs() < 1e-6);
+
+        let curved = ((SRGB_DECODE_THRESHOLD + SRGB_OFFSET) / (1.0 + SRGB_OFFSET)).powf(SRGB_GAMMA);
+
+        assert!((curved - SRGB_DECODE_THRESHOLD / SRGB_SLOPE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn lab() {
+        assert!((LAB_EPSILON - (6.0f64 / 29.0).powi(3)).abs() < 1e-12);
+        assert!((LAB_KAPPA * LAB_EPSILON - 8.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn white_point_chromaticities() {
+        // x, y from CIE 15:2004
+        let reference = [
+            (WHITE_A, 0.44757, 0.40745),
+            (WHITE_D50, 0.34567, 0.35850),
+            (WHITE_D65, 0.31271, 0.32902),
+            (WHITE_D75, 0.29902, 0.31485),
+        ];
+
+        for ([x, y, z], expected_x, expected_y) in reference {
+            let sum = x + y + z;
+
+            assert!((x / sum - expected_x).abs() < 1e-3, "{} {}", x / sum, expected_x);
+            assert!((y / sum - expected_y).abs() < 1e-3, "{} {}", y / sum, expected_y);
+        }
+    }
+}
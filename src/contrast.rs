@@ -0,0 +1,121 @@
+use crate::color::Color;
+use crate::lab::srgb_to_linear;
+
+impl Color {
+    /// WCAG 2 relative luminance, from `0.0` for black to `1.0` for white.
+    pub fn relative_luminance(&self) -> f32 {
+        let (r, g, b) = (srgb_to_linear(self.red), srgb_to_linear(self.green), srgb_to_linear(self.blue));
+
+        (0.2126 * r + 0.7152 * g + 0.0722 * b) as f32
+    }
+
+    /// WCAG 2 contrast ratio between the two colors, from `1.0` to `21.0`. The order doesn't
+    /// matter. 4.5 is the minimum for normal text at level AA, 7.0 for level AAA.
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+
+        (lighter + 0.05) / (darker + 0.05)
+    }
+}
+
+/// Walks the HSL lightness of `color` one way until the contrast ratio against `against` is at
+/// least `minimum`. Returns `None` if black or white is reached first.
+fn step_towards_contrast(color: &Color, against: &Color, minimum: f32, step: f32) -> Option<Color> {
+    let mut hsl = color.to_hsl();
+    let mut color = *color;
+
+    while color.contrast_ratio(against) < minimum {
+        if hsl.lightness <= 0.0 || hsl.lightness >= 1.0 {
+            return None
+        }
+
+        hsl.lightness = (hsl.lightness + step).clamp(0.0, 1.0);
+        color = hsl.into();
+    }
+
+    Some(color)
+}
+
+/// The color closest in lightness to `color`, with the same hue and saturation, whose contrast
+/// ratio against `against` is at least `minimum`. If that's impossible, black or white, whichever
+/// contrasts more.
+pub(crate) fn ensure_contrast(color: Color, against: &Color, minimum: f32) -> Color {
+    if color.contrast_ratio(against) >= minimum {
+        return color
+    }
+
+    let lightness = color.to_hsl().lightness;
+    let lighter = step_towards_contrast(&color, against, minimum, 0.01);
+    let darker = step_towards_contrast(&color, against, minimum, -0.01);
+
+    match (lighter, darker) {
+        (Some(lighter), Some(darker)) => {
+            let change = |c: &Color| (c.to_hsl().lightness - lightness).abs();
+
+            if change(&lighter) <= change(&darker) { lighter } else { darker }
+        },
+        (Some(lighter), None) => lighter,
+        (None, Some(darker)) => darker,
+        (None, None) => {
+            let (black, white) = (Color { red: 0, green: 0, blue: 0 }, Color { red: 255, green: 255, blue: 255 });
+
+            if black.contrast_ratio(against) > white.contrast_ratio(against) { black } else { white }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(c: u8) -> Color {
+        Color { red: c, green: c, blue: c }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.01, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn luminance() {
+        assert_eq!(gray(0).relative_luminance(), 0.0);
+        assert_close(gray(255).relative_luminance(), 1.0);
+        assert_close(Color { red: 255, green: 0, blue: 0 }.relative_luminance(), 0.2126);
+    }
+
+    #[test]
+    fn ensure_contrast_keeps_good_colors() {
+        assert_eq!(ensure_contrast(gray(0), &gray(255), 4.5), gray(0));
+    }
+
+    #[test]
+    fn ensure_contrast_moves_the_shortest_way() {
+        let dark_bg = gray(20);
+        let light_bg = gray(240);
+        let mid = Color { red: 200, green: 40, blue: 40 };
+
+        let on_dark = ensure_contrast(mid, &dark_bg, 7.0);
+        let on_light = ensure_contrast(mid, &light_bg, 7.0);
+
+        assert!(on_dark.contrast_ratio(&dark_bg) >= 7.0);
+        assert!(on_dark.to_hsl().lightness > mid.to_hsl().lightness);
+        assert!(on_light.contrast_ratio(&light_bg) >= 7.0);
+        assert!(on_light.to_hsl().lightness < mid.to_hsl().lightness);
+    }
+
+    #[test]
+    fn ensure_contrast_impossible() {
+        // nothing reaches 21:1 against mid gray, black gets closest
+        assert_eq!(ensure_contrast(gray(100), &gray(128), 21.0), gray(0));
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        assert_close(gray(0).contrast_ratio(&gray(255)), 21.0);
+        assert_close(gray(255).contrast_ratio(&gray(0)), 21.0);
+        assert_close(gray(0x77).contrast_ratio(&gray(255)), 4.48);
+        assert_close(gray(0x76).contrast_ratio(&gray(255)), 4.54);
+        assert_close(gray(42).contrast_ratio(&gray(42)), 1.0);
+    }
+}
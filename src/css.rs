@@ -0,0 +1,61 @@
+//! Conversions to and from `csscolorparser::Color`.
+//!
+//! `csscolorparser` colors are floating point with an alpha channel. Going to a `Color` rounds each
+//! channel to the nearest `u8` and drops the alpha; coming from one always gives an opaque color.
+
+use crate::color::Color;
+
+impl From<&Color> for csscolorparser::Color {
+    fn from(color: &Color) -> Self {
+        csscolorparser::Color::from_rgba8(color.red, color.green, color.blue, 255)
+    }
+}
+
+impl From<Color> for csscolorparser::Color {
+    fn from(color: Color) -> Self {
+        csscolorparser::Color::from(&color)
+    }
+}
+
+impl From<&csscolorparser::Color> for Color {
+    fn from(color: &csscolorparser::Color) -> Self {
+        let [red, green, blue, _] = color.to_rgba8();
+
+        Color { red, green, blue }
+    }
+}
+
+impl From<csscolorparser::Color> for Color {
+    fn from(color: csscolorparser::Color) -> Self {
+        Color::from(&color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGENTA: Color = Color { red: 255, green: 0, blue: 170 };
+
+    #[test]
+    fn round_trip() {
+        let css = csscolorparser::Color::from(&MAGENTA);
+
+        assert_eq!(css.to_rgba8(), [255, 0, 170, 255]);
+        assert_eq!(Color::from(css), MAGENTA);
+    }
+
+    #[test]
+    fn from_parsed_css() {
+        let css: csscolorparser::Color = "rgb(100% 0% 66.7%)".parse().unwrap();
+
+        assert_eq!(Color::from(&css), MAGENTA);
+    }
+
+    #[test]
+    fn drops_alpha() {
+        let css = csscolorparser::Color::from_rgba8(1, 2, 3, 0);
+
+        assert_eq!(Color::from(css), Color { red: 1, green: 2, blue: 3 });
+    }
+}
@@ -0,0 +1,318 @@
+use serde::{Serialize, Deserialize};
+
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+
+use crate::color::Color;
+use crate::error::CurveError;
+use crate::fill::Fill;
+
+/// Which channel a `Curve` applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+
+    /// The same curve on every channel.
+    All,
+}
+
+/// A tone curve through `(input, output)` control points in `0.0..=1.0`, like the curves tool of an
+/// image editor.
+///
+/// The points are joined with a monotone cubic spline, so the curve never overshoots between
+/// them: a curve through increasing points only ever increases. Inputs before the first point or
+/// after the last map to that point's output.
+///
+/// Serialized as the list of points, `[[0.0, 0.0], [0.5, 0.35], [1.0, 1.0]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(f32, f32)>", into = "Vec<(f32, f32)>")]
+pub struct Curve {
+    points: Vec<(f32, f32)>,
+}
+
+impl Curve {
+    /// Points must be in `0.0..=1.0` and sorted by strictly increasing input.
+    pub fn new(points: Vec<(f32, f32)>) -> core::result::Result<Curve, CurveError> {
+        if points.len() < 2 {
+            return Err(CurveError::NotEnoughPoints(points.len()))
+        }
+
+        for (index, &(x, y)) in points.iter().enumerate() {
+            if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+                return Err(CurveError::OutOfRange { index })
+            }
+
+            if index > 0 && x <= points[index - 1].0 {
+                return Err(CurveError::NotIncreasing { index })
+            }
+        }
+
+        Ok(Curve { points })
+    }
+
+    /// The straight line from `(0, 0)` to `(1, 1)`, which changes nothing.
+    pub fn identity() -> Curve {
+        Curve { points: vec![(0.0, 0.0), (1.0, 1.0)] }
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    /// Tangents for a monotone cubic Hermite spline (Fritsch and Carlson).
+    fn tangents(&self) -> Vec<f32> {
+        let p = &self.points;
+        let secants: Vec<f32> = p.windows(2).map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0)).collect();
+
+        let mut tangents = Vec::with_capacity(p.len());
+        tangents.push(secants[0]);
+
+        for pair in secants.windows(2) {
+            tangents.push(if pair[0] * pair[1] <= 0.0 { 0.0 } else { (pair[0] + pair[1]) / 2.0 });
+        }
+
+        tangents.push(secants[secants.len() - 1]);
+
+        for (k, &secant) in secants.iter().enumerate() {
+            if secant == 0.0 {
+                tangents[k] = 0.0;
+                tangents[k + 1] = 0.0;
+                continue
+            }
+
+            let (a, b) = (tangents[k] / secant, tangents[k + 1] / secant);
+            let length = (a * a + b * b).sqrt();
+
+            if length > 3.0 {
+                tangents[k] = 3.0 / length * a * secant;
+                tangents[k + 1] = 3.0 / length * b * secant;
+            }
+        }
+
+        tangents
+    }
+
+    /// The output for input `x`, in `0.0..=1.0`.
+    pub fn eval(&self, x: f32) -> f32 {
+        self.eval_with(x, &self.tangents())
+    }
+
+    fn eval_with(&self, x: f32, tangents: &[f32]) -> f32 {
+        let p = &self.points;
+        let (first, last) = (p[0], p[p.len() - 1]);
+
+        if x <= first.0 {
+            return first.1
+        }
+
+        if x >= last.0 {
+            return last.1
+        }
+
+        // the segment `x` falls in; there is one since it's strictly between the ends
+        let k = p.windows(2).position(|w| x < w[1].0).unwrap_or(p.len() - 2);
+        let ((x0, y0), (x1, y1)) = (p[k], p[k + 1]);
+
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let (t2, t3) = (t * t, t * t * t);
+
+        let y = (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+            + (t3 - 2.0 * t2 + t) * h * tangents[k]
+            + (-2.0 * t3 + 3.0 * t2) * y1
+            + (t3 - t2) * h * tangents[k + 1];
+
+        y.clamp(0.0, 1.0)
+    }
+
+    fn apply(&self, channel: u8, tangents: &[f32]) -> u8 {
+        (self.eval_with(f32::from(channel) / 255.0, tangents) * 255.0).round() as u8
+    }
+}
+
+impl TryFrom<Vec<(f32, f32)>> for Curve {
+    type Error = CurveError;
+
+    fn try_from(points: Vec<(f32, f32)>) -> core::result::Result<Self, Self::Error> {
+        Curve::new(points)
+    }
+}
+
+impl From<Curve> for Vec<(f32, f32)> {
+    fn from(curve: Curve) -> Self {
+        curve.points
+    }
+}
+
+impl Color {
+    /// Maps `channel` through `curve`, leaving the others alone.
+    pub fn apply_curve(&self, channel: Channel, curve: &Curve) -> Color {
+        self.apply_curve_with(channel, curve, &curve.tangents())
+    }
+
+    fn apply_curve_with(&self, channel: Channel, curve: &Curve, tangents: &[f32]) -> Color {
+        let mut color = *self;
+
+        if let Channel::Red | Channel::All = channel {
+            color.red = curve.apply(color.red, tangents);
+        }
+
+        if let Channel::Green | Channel::All = channel {
+            color.green = curve.apply(color.green, tangents);
+        }
+
+        if let Channel::Blue | Channel::All = channel {
+            color.blue = curve.apply(color.blue, tangents);
+        }
+
+        color
+    }
+}
+
+impl Fill {
+    /// Maps `channel` of a solid color or every stop of a gradient through `curve`.
+    ///
+    /// The keyword fills are returned as they are; `Rainbow` is defined by its hue sweep rather
+    /// than by colors a curve could change. A `Raw` string has no channels to map, so it's kept
+    /// as written.
+    pub fn apply_curve(&self, channel: Channel, curve: &Curve) -> Fill {
+        let tangents = curve.tangents();
+        let apply = |stops: &[Color]| stops.iter().map(|c| c.apply_curve_with(channel, curve, &tangents)).collect();
+
+        match self {
+            Fill::Rainbow(params) => Fill::Rainbow(*params),
+            Fill::None => Fill::None,
+            Fill::Transparent => Fill::Transparent,
+            Fill::Raw(raw) => Fill::Raw(raw.clone()),
+            Fill::Color(color) => Fill::Color(color.apply_curve_with(channel, curve, &tangents)),
+            Fill::Gradient(stops) => Fill::Gradient(apply(stops)),
+            Fill::Radial { center, stops } => Fill::Radial { center: *center, stops: apply(stops) },
+            Fill::Conic { angle, stops } => Fill::Conic { angle: *angle, stops: apply(stops) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(points: &[(f32, f32)]) -> Curve {
+        Curve::new(points.to_vec()).unwrap()
+    }
+
+    mod curve {
+        use super::*;
+
+        #[test]
+        fn passes_through_points() {
+            let c = curve(&[(0.0, 0.0), (0.25, 0.1), (0.5, 0.6), (1.0, 1.0)]);
+
+            for &(x, y) in c.points() {
+                assert!((c.eval(x) - y).abs() < 1e-6);
+            }
+        }
+
+        #[test]
+        fn identity() {
+            for i in 0..=10 {
+                let x = i as f32 / 10.0;
+
+                assert!((Curve::identity().eval(x) - x).abs() < 1e-6);
+            }
+        }
+
+        #[test]
+        fn monotone_without_overshoot() {
+            let c = curve(&[(0.0, 0.0), (0.1, 0.9), (0.2, 0.95), (1.0, 1.0)]);
+            let mut previous = 0.0;
+
+            for i in 0..=100 {
+                let y = c.eval(i as f32 / 100.0);
+
+                assert!(y >= previous && y <= 1.0, "{} at {}", y, i);
+                previous = y;
+            }
+        }
+
+        #[test]
+        fn flat_outside_the_points() {
+            let c = curve(&[(0.2, 0.3), (0.8, 0.6)]);
+
+            assert_eq!(c.eval(0.0), 0.3);
+            assert_eq!(c.eval(1.0), 0.6);
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(Curve::new(vec![(0.0, 0.0)]), Err(CurveError::NotEnoughPoints(1)));
+            assert_eq!(Curve::new(vec![(0.0, 0.0), (1.5, 1.0)]), Err(CurveError::OutOfRange { index: 1 }));
+            assert_eq!(Curve::new(vec![(0.5, 0.0), (0.5, 1.0)]), Err(CurveError::NotIncreasing { index: 1 }));
+        }
+
+        #[test]
+        fn serde() {
+            let c: Curve = serde_json::from_str("[[0.0, 0.0], [0.5, 0.25], [1.0, 1.0]]").unwrap();
+
+            assert_eq!(c, curve(&[(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]));
+            assert_eq!(serde_json::to_string(&c).unwrap(), "[[0.0,0.0],[0.5,0.25],[1.0,1.0]]");
+
+            let err = serde_json::from_str::<Curve>("[[0.5, 0.0], [0.1, 1.0]]").unwrap_err();
+
+            assert!(err.to_string().contains("doesn't come after"), "{}", err);
+        }
+
+        #[test]
+        fn channel_serde() {
+            assert_eq!(serde_json::from_str::<Channel>(r#""green""#).unwrap(), Channel::Green);
+            assert_eq!(serde_json::to_string(&Channel::All).unwrap(), r#""all""#);
+        }
+    }
+
+    mod apply {
+        use super::*;
+
+        const GRAY: Color = Color { red: 128, green: 128, blue: 128 };
+
+        #[test]
+        fn single_channel() {
+            let invert = curve(&[(0.0, 1.0), (1.0, 0.0)]);
+            let color = Color { red: 255, green: 10, blue: 0 };
+
+            assert_eq!(color.apply_curve(Channel::Red, &invert), Color { red: 0, green: 10, blue: 0 });
+            assert_eq!(color.apply_curve(Channel::Blue, &invert), Color { red: 255, green: 10, blue: 255 });
+            assert_eq!(color.apply_curve(Channel::All, &invert), Color { red: 0, green: 245, blue: 255 });
+        }
+
+        #[test]
+        fn identity_changes_nothing() {
+            let color = Color { red: 1, green: 100, blue: 254 };
+
+            assert_eq!(color.apply_curve(Channel::All, &Curve::identity()), color);
+        }
+
+        #[test]
+        fn darkening_midtones() {
+            let c = curve(&[(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]);
+
+            assert_eq!(GRAY.apply_curve(Channel::All, &c).red, 64);
+        }
+
+        #[test]
+        fn fills() {
+            let boost = curve(&[(0.0, 0.5), (1.0, 1.0)]);
+
+            let gradient = Fill::Gradient(vec![Color { red: 0, green: 0, blue: 0 }, GRAY]);
+
+            assert_eq!(gradient.apply_curve(Channel::Green, &boost), Fill::Gradient(vec![
+                Color { red: 0, green: 128, blue: 0 },
+                Color { red: 128, green: 192, blue: 128 },
+            ]));
+            assert_eq!(Fill::Color(GRAY).apply_curve(Channel::Red, &boost), Fill::Color(Color { red: 192, green: 128, blue: 128 }));
+            assert_eq!(Fill::rainbow().apply_curve(Channel::All, &boost), Fill::rainbow());
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de;
+
+use alloc::vec::Vec;
+
+use core::str::FromStr;
+
+use crate::binary;
+use crate::color::{self, Color};
+use crate::error::ParseColorError;
+use crate::fill::{self, Fill};
+
+/// A set of bare-word fills, like `"rainbow"`. Usually implemented with `fill_keywords!`.
+pub trait FillKeyword: Sized {
+    fn from_keyword(s: &str) -> Option<Self>;
+
+    fn as_keyword(&self) -> &'static str;
+}
+
+/// Declares an enum of fill keywords and implements `FillKeyword` for it. Attributes, including
+/// derives, are passed through to the enum.
+///
+/// ```
+/// use led_json::{fill_keywords, CustomFill, Color};
+///
+/// fill_keywords! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Effect {
+///         Pulse = "pulse",
+///         Chase = "chase",
+///     }
+/// }
+///
+/// let fill: CustomFill<Effect> = serde_json::from_str(r#""chase""#).unwrap();
+/// assert_eq!(fill, CustomFill::Keyword(Effect::Chase));
+///
+/// let fill: CustomFill<Effect> = serde_json::from_str(r##"["#fff", "#000"]"##).unwrap();
+/// assert_eq!(fill, CustomFill::Gradient(vec![
+///     Color { red: 255, green: 255, blue: 255 },
+///     Color { red: 0, green: 0, blue: 0 },
+/// ]));
+/// ```
+#[macro_export]
+macro_rules! fill_keywords {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident = $keyword:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $crate::FillKeyword for $name {
+            fn from_keyword(s: &str) -> ::core::option::Option<Self> {
+                match s {
+                    $($keyword => ::core::option::Option::Some($name::$variant),)+
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn as_keyword(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $keyword,)+
+                }
+            }
+        }
+    };
+}
+
+/// Like `Fill`, but with an application-defined set of keywords instead of `"rainbow"`. It's read
+/// with `Fill`'s parsing, so it takes the same forms of colors and linear gradients, from strings
+/// and from compact formats alike, and a gradient needs a stop here too.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomFill<K> {
+    Keyword(K),
+    Color(Color),
+    Gradient(Vec<Color>),
+}
+
+impl<K: FillKeyword> CustomFill<K> {
+    /// What `Fill`'s parsing read, with a `Fill::Raw` standing in for a keyword. Anything a
+    /// `CustomFill` can't hold is handed back.
+    fn from_fill(fill: Fill) -> core::result::Result<Self, Fill> {
+        match fill {
+            Fill::Color(color) => Ok(CustomFill::Color(color)),
+            Fill::Gradient(colors) => Ok(CustomFill::Gradient(colors)),
+            Fill::Raw(word) => match K::from_keyword(&word) {
+                Some(keyword) => Ok(CustomFill::Keyword(keyword)),
+                None => Err(Fill::Raw(word)),
+            },
+            fill => Err(fill),
+        }
+    }
+}
+
+impl<K: FillKeyword> FromStr for CustomFill<K> {
+    type Err = ParseColorError;
+
+    /// A keyword, or anything `Fill::from_str` reads as a color or a linear gradient, like
+    /// `linear-gradient(#fff, #000)`.
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        if let Some(keyword) = K::from_keyword(s) {
+            return Ok(CustomFill::Keyword(keyword))
+        }
+
+        match CustomFill::from_fill(Fill::from_str(s)?) {
+            Ok(fill) => Ok(fill),
+            Err(Fill::Radial { .. } | Fill::Conic { .. }) => Err(ParseColorError::InvalidGradient(s.into())),
+            Err(_) => Err(ParseColorError::UnknownKeyword(s.into())),
+        }
+    }
+}
+
+impl<'de, K: FillKeyword> Deserialize<'de> for CustomFill<K> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let keywords = |s: &str| K::from_keyword(s).map(|_| Fill::Raw(s.into()));
+        let fill = fill::deserialize_with(deserializer, &color::parse_hex, Some(&keywords), None)?;
+
+        match CustomFill::from_fill(fill) {
+            Ok(fill) => Ok(fill),
+            Err(Fill::Raw(word)) => Err(de::Error::custom(ParseColorError::UnknownKeyword(word))),
+            Err(_) => Err(de::Error::custom("expected a keyword, a color or a linear gradient")),
+        }
+    }
+}
+
+impl<K: FillKeyword> Serialize for CustomFill<K> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return match self {
+                CustomFill::Keyword(keyword) => binary::serialize_tagged(binary::RAW, keyword.as_keyword(), serializer),
+                CustomFill::Color(color) => binary::serialize_tagged(binary::COLOR, color, serializer),
+                CustomFill::Gradient(colors) => binary::serialize_tagged(binary::GRADIENT, colors, serializer),
+            }
+        }
+
+        match self {
+            CustomFill::Keyword(keyword) => serializer.serialize_str(keyword.as_keyword()),
+            CustomFill::Color(color) => color.serialize(serializer),
+            CustomFill::Gradient(colors) => colors.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fill_keywords! {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Effect {
+            Pulse = "pulse",
+            Chase = "chase",
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    struct MyData {
+        pub color: CustomFill<Effect>,
+    }
+
+    #[test]
+    fn keywords() {
+        let v: MyData = serde_json::from_str(r#"{ "color": "pulse" }"#).unwrap();
+
+        assert_eq!(v.color, CustomFill::Keyword(Effect::Pulse));
+        assert_eq!(serde_json::to_string(&v).unwrap(), r#"{"color":"pulse"}"#);
+    }
+
+    #[test]
+    fn color() {
+        let v: MyData = serde_json::from_str(r##"{ "color": "#f0f" }"##).unwrap();
+
+        assert_eq!(v.color, CustomFill::Color(Color { red: 255, green: 0, blue: 255 }));
+        assert_eq!(serde_json::to_string(&v).unwrap(), r##"{"color":"#ff00ff"}"##);
+    }
+
+    #[test]
+    fn gradient() {
+        let v: MyData = serde_json::from_str(r##"{ "color": ["#f0f", "#000000"] }"##).unwrap();
+
+        assert_eq!(v.color, CustomFill::Gradient(vec![
+            Color { red: 255, green: 0, blue: 255 },
+            Color { red: 0, green: 0, blue: 0 },
+        ]));
+        assert_eq!(serde_json::to_string(&v).unwrap(), r##"{"color":["#ff00ff","#000000"]}"##);
+    }
+
+    #[test]
+    fn structured_colors() {
+        let v: MyData = serde_json::from_str(r##"{ "color": { "r": 1, "g": 2, "b": 3 } }"##).unwrap();
+
+        assert_eq!(v.color, CustomFill::Color(Color { red: 1, green: 2, blue: 3 }));
+
+        let v: MyData = serde_json::from_str(r##"{ "color": 16711935 }"##).unwrap();
+
+        assert_eq!(v.color, CustomFill::Color(Color { red: 255, green: 0, blue: 255 }));
+
+        let v: MyData = serde_json::from_str(r##"{ "color": [{ "r": 1, "g": 2, "b": 3 }, [4, 5, 6]] }"##).unwrap();
+
+        assert_eq!(v.color, CustomFill::Gradient(vec![Color { red: 1, green: 2, blue: 3 }, Color { red: 4, green: 5, blue: 6 }]));
+        assert!(serde_json::from_str::<MyData>(r#"{ "color": { "r": 1, "g": 2 } }"#).is_err());
+    }
+
+    #[test]
+    fn channel_arrays() {
+        let magenta = Color { red: 255, green: 0, blue: 255 };
+        let v: MyData = serde_json::from_str(r#"{ "color": [255, 0, 255] }"#).unwrap();
+
+        // read the same as `Fill` reads them
+        assert_eq!(serde_json::from_str::<Fill>("[255, 0, 255]").unwrap(), Fill::Color(magenta));
+        assert_eq!(v.color, CustomFill::Color(magenta));
+
+        let err = serde_json::from_str::<MyData>(r##"{ "color": [255, "#fff"] }"##).unwrap_err();
+
+        assert!(err.to_string().contains("can't mix colors and channel values"), "{}", err);
+    }
+
+    #[test]
+    fn empty_gradient() {
+        let err = serde_json::from_str::<MyData>(r#"{ "color": [] }"#).unwrap_err();
+
+        assert!(err.to_string().starts_with("A gradient needs at least one stop"), "{}", err);
+    }
+
+    #[test]
+    fn builtin_keywords_are_not_included() {
+        assert!(serde_json::from_str::<MyData>(r#"{ "color": "rainbow" }"#).is_err());
+    }
+
+    #[test]
+    fn keywords_in_gradient_fail() {
+        assert!(serde_json::from_str::<MyData>(r#"{ "color": ["chase"] }"#).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(CustomFill::<Effect>::from_str("chase").unwrap(), CustomFill::Keyword(Effect::Chase));
+        assert!(CustomFill::<Effect>::from_str("hello").is_err());
+    }
+
+    #[test]
+    fn from_str_reads_what_fill_does() {
+        let white = Color { red: 255, green: 255, blue: 255 };
+        let black = Color { red: 0, green: 0, blue: 0 };
+
+        assert_eq!(CustomFill::<Effect>::from_str("linear-gradient(#fff, #000)"), Ok(CustomFill::Gradient(vec![white, black])));
+        assert_eq!(CustomFill::<Effect>::from_str("#fff,#000"), Ok(CustomFill::Gradient(vec![white, black])));
+        assert_eq!(CustomFill::<Effect>::from_str("#fff"), Ok(CustomFill::Color(white)));
+        assert_eq!(CustomFill::<Effect>::from_str("rainbow"), Err(ParseColorError::UnknownKeyword("rainbow".into())));
+        assert_eq!(
+            CustomFill::<Effect>::from_str("conic-gradient(#fff, #000)"),
+            Err(ParseColorError::InvalidGradient("conic-gradient(#fff, #000)".into()))
+        );
+        assert_eq!(CustomFill::<Effect>::from_str("linear-gradient()"), Err(ParseColorError::InvalidGradient("linear-gradient()".into())));
+    }
+
+    #[test]
+    fn only_what_it_can_hold() {
+        for json in [r##"{ "type": "radial", "stops": ["#fff"] }"##, r#"{ "rainbow": { "period": 2 } }"#, r#""none""#] {
+            assert!(serde_json::from_str::<CustomFill<Effect>>(json).is_err(), "{}", json);
+        }
+
+        let fill: CustomFill<Effect> = serde_json::from_str(r##"{ "type": "linear", "stops": ["#fff"] }"##).unwrap();
+
+        assert_eq!(fill, CustomFill::Gradient(vec![Color { red: 255, green: 255, blue: 255 }]));
+    }
+}
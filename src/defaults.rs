@@ -0,0 +1,106 @@
+//! Functions for `#[serde(default = "...")]` on `Fill` fields, and `default_fill!` for defining
+//! more of them.
+//!
+//! ```
+//! use led_json::Fill;
+//! use serde::Deserialize;
+//!
+//! led_json::default_fill!(fn accent() = "#ff00aa");
+//!
+//! #[derive(Deserialize)]
+//! struct Strip {
+//!     #[serde(default = "led_json::defaults::black")]
+//!     background: Fill,
+//!     #[serde(default = "accent")]
+//!     highlight: Fill,
+//! }
+//!
+//! let strip: Strip = serde_json::from_str("{}").unwrap();
+//!
+//! assert_eq!(strip.background, Fill::Color(led_json::Color::new(0, 0, 0)));
+//! assert_eq!(strip.highlight, Fill::Color(led_json::Color::new(255, 0, 170)));
+//! ```
+
+use crate::color::Color;
+use crate::fill::Fill;
+
+pub fn black() -> Fill {
+    Fill::Color(Color::new(0, 0, 0))
+}
+
+pub fn white() -> Fill {
+    Fill::Color(Color::new(255, 255, 255))
+}
+
+pub fn rainbow() -> Fill {
+    Fill::rainbow()
+}
+
+pub fn none() -> Fill {
+    Fill::None
+}
+
+pub fn transparent() -> Fill {
+    Fill::Transparent
+}
+
+/// Defines a function returning a `Fill`, for `#[serde(default = "...")]`. The fill is a hex
+/// literal, checked at compile time like `color!`, a list of them for a gradient, or any
+/// expression.
+///
+/// ```
+/// use led_json::{Color, Fill};
+///
+/// led_json::default_fill!(fn amber() = "#ffbf00");
+/// led_json::default_fill!(pub(crate) fn sunset() = ["#f00", "#ff0"]);
+/// led_json::default_fill!(fn centered() = Fill::Radial { center: (0.5, 0.5), stops: vec![Color::new(0, 0, 0)] });
+///
+/// assert_eq!(amber(), Fill::Color(Color::new(255, 191, 0)));
+/// assert_eq!(sunset(), Fill::Gradient(vec![Color::new(255, 0, 0), Color::new(255, 255, 0)]));
+/// assert_eq!(centered().stops().map(|stops| stops.len()), Some(1));
+/// ```
+#[macro_export]
+macro_rules! default_fill {
+    ($vis:vis fn $name:ident() = $hex:literal) => {
+        $vis fn $name() -> $crate::Fill {
+            $crate::Fill::Color($crate::color!($hex))
+        }
+    };
+    ($vis:vis fn $name:ident() = [$($hex:literal),+ $(,)?]) => {
+        $vis fn $name() -> $crate::Fill {
+            $crate::Fill::Gradient($crate::__private::vec![$($crate::color!($hex)),+])
+        }
+    };
+    ($vis:vis fn $name:ident() = $fill:expr) => {
+        $vis fn $name() -> $crate::Fill {
+            $fill
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    #[test]
+    fn builtin() {
+        assert_eq!(black(), Fill::Color(Color { red: 0, green: 0, blue: 0 }));
+        assert_eq!(white(), Fill::Color(Color { red: 255, green: 255, blue: 255 }));
+        assert_eq!(rainbow(), Fill::rainbow());
+        assert_eq!(none(), Fill::None);
+        assert_eq!(transparent(), Fill::Transparent);
+    }
+
+    #[test]
+    fn macro_forms() {
+        default_fill!(fn magenta() = "#f0f");
+        default_fill!(fn fade() = ["#000", "#ffffff",]);
+        default_fill!(fn off() = Fill::None);
+
+        assert_eq!(magenta(), Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        assert_eq!(fade(), Fill::Gradient(vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 255, green: 255, blue: 255 }]));
+        assert_eq!(off(), Fill::None);
+    }
+}
@@ -0,0 +1,88 @@
+//! How different two fills look, for matching a user's gradient to the closest preset.
+
+use crate::fill::Fill;
+
+/// Where along `0.0..=1.0` `Fill::distance` compares the fills: every 1/64th.
+const SAMPLES: usize = 65;
+
+impl Fill {
+    /// The CIEDE2000 difference between the two fills, averaged over their length: `0.0` for
+    /// fills that look the same and about `1.0` for ones most people can just tell apart. The
+    /// fills are compared at 65 evenly spaced positions, as `Fill::sample` sees them, so a
+    /// gradient of one color matches that color and a radial or conic gradient's center and angle
+    /// don't count.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill};
+    ///
+    /// let presets = [
+    ///     Fill::Gradient(vec![Color::new(255, 0, 0), Color::new(0, 0, 255)]),
+    ///     Fill::Gradient(vec![Color::new(0, 0, 0), Color::new(255, 255, 255)]),
+    ///     Fill::rainbow(),
+    /// ];
+    ///
+    /// let sunset = Fill::Gradient(vec![Color::new(230, 40, 20), Color::new(40, 0, 200)]);
+    /// let closest = presets.iter().min_by(|a, b| sunset.distance(a).total_cmp(&sunset.distance(b)));
+    ///
+    /// assert_eq!(closest, Some(&presets[0]));
+    /// ```
+    pub fn distance(&self, other: &Fill) -> f32 {
+        let difference = |i: usize| {
+            let t = i as f32 / (SAMPLES - 1) as f32;
+
+            self.sample(t).delta_e(&other.sample(t))
+        };
+
+        // the trapezoid rule: the ends count half
+        let ends = (difference(0) + difference(SAMPLES - 1)) / 2.0;
+        let middle: f32 = (1..SAMPLES - 1).map(difference).sum();
+
+        (ends + middle) / (SAMPLES - 1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::color::Color;
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    #[test]
+    fn same_looks_are_zero() {
+        let gradient = Fill::Gradient(vec![RED, BLUE]);
+
+        assert_eq!(gradient.distance(&gradient), 0.0);
+        assert_eq!(Fill::Color(RED).distance(&Fill::Gradient(vec![RED, RED])), 0.0);
+        assert_eq!(Fill::None.distance(&Fill::Color(Color::new(0, 0, 0))), 0.0);
+        assert_eq!(gradient.distance(&Fill::Conic { angle: 90.0, stops: vec![RED, BLUE] }), 0.0);
+    }
+
+    #[test]
+    fn symmetric() {
+        let a = Fill::Gradient(vec![RED, BLUE]);
+        let b = Fill::rainbow();
+
+        assert!((a.distance(&b) - b.distance(&a)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn averages_the_differences() {
+        let solid = Fill::Color(RED);
+        let half = Fill::Gradient(vec![RED, RED, BLUE]);
+        let blue = Fill::Color(BLUE);
+
+        assert!(solid.distance(&half) > 0.0);
+        assert!(solid.distance(&half) < solid.distance(&blue));
+    }
+
+    #[test]
+    fn reversed_gradients_differ() {
+        let forward = Fill::Gradient(vec![RED, BLUE]);
+        let backward = Fill::Gradient(vec![BLUE, RED]);
+
+        assert!(forward.distance(&backward) > 10.0);
+    }
+}
@@ -0,0 +1,183 @@
+//! Rasterizing fills with dithering, so that slow gradients don't band on 8-bit outputs.
+
+use alloc::vec::Vec;
+
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::rng::Rng;
+
+/// How much dithering `Fill::rasterize_dithered` adds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Noise {
+    /// Peak noise in 8-bit levels. `1.0` is enough to hide banding; `0.0` turns dithering off.
+    pub amplitude: f32,
+
+    /// The same seed always gives the same output.
+    pub seed: u64,
+}
+
+impl Noise {
+    pub fn new(amplitude: f32, seed: u64) -> Self {
+        Noise { amplitude, seed }
+    }
+}
+
+impl Fill {
+    /// Like `sample`, but with channels as unrounded `0.0..=255.0` values.
+    fn sample_levels(&self, t: f32) -> [f32; 3] {
+        let t = t.clamp(0.0, 1.0);
+        let levels = |c: &Color| [f32::from(c.red), f32::from(c.green), f32::from(c.blue)];
+
+        match self {
+            Fill::Rainbow(params) => {
+                // `params.sample(t)` without the rounding
+                let hue = params.turn(t) * 12.0;
+                let (s, l) = (params.saturation.clamp(0.0, 1.0), params.lightness.clamp(0.0, 1.0));
+                let a = s * l.min(1.0 - l);
+                let channel = |n: f32| {
+                    let k = (n + hue) % 12.0;
+
+                    255.0 * (l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0))
+                };
+
+                [channel(0.0), channel(8.0), channel(4.0)]
+            },
+            Fill::None | Fill::Transparent | Fill::Raw(_) => [0.0; 3],
+            Fill::Color(color) => levels(color),
+            Fill::Gradient(colors) | Fill::Radial { stops: colors, .. } | Fill::Conic { stops: colors, .. } => {
+                match colors.len() {
+                    0 => [0.0; 3],
+                    1 => levels(&colors[0]),
+                    len => {
+                        let position = t * (len - 1) as f32;
+                        let index = (position.floor() as usize).min(len - 2);
+                        let (a, b) = (levels(&colors[index]), levels(&colors[index + 1]));
+                        let t = position - index as f32;
+
+                        [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+                    }
+                }
+            },
+        }
+    }
+
+    /// Draws the fill across a row of `width` pixels, positioned like `sample_xy` with a height of
+    /// one, adding noise before rounding each pixel to 8 bits.
+    ///
+    /// The noise is blue: white noise run through a first difference, so it has almost no low
+    /// frequency content. Averaged over a few pixels it cancels out, leaving the exact gradient
+    /// instead of stripes of flat color. The same noise is added to all three channels, so it
+    /// shows as grain rather than colored speckles.
+    pub fn rasterize_dithered(&self, width: u32, noise: Noise) -> Vec<Color> {
+        let mut rng = Rng(noise.seed);
+        let mut previous = rng.range(-0.5, 0.5);
+
+        (0..width).map(|x| {
+            let t = self.position(x, 0, width, 1);
+
+            let white = rng.range(-0.5, 0.5);
+            let offset = noise.amplitude * (white - previous);
+            previous = white;
+
+            let [red, green, blue] = self.sample_levels(t).map(|c| (c + offset + 0.5).floor().clamp(0.0, 255.0) as u8);
+
+            Color { red, green, blue }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rainbow::RainbowParams;
+
+    fn gray(level: u8) -> Color {
+        Color { red: level, green: level, blue: level }
+    }
+
+    fn slow_gradient() -> Fill {
+        Fill::Gradient(vec![gray(100), gray(110)])
+    }
+
+    /// Largest error between the mean of any `window` consecutive pixels and the exact gradient.
+    fn max_window_error(pixels: &[Color], window: usize) -> f32 {
+        let exact = |x: usize| 100.0 + 10.0 * x as f32 / (pixels.len() - 1) as f32;
+
+        (0..=pixels.len() - window).map(|start| {
+            let range = start..start + window;
+            let actual: f32 = pixels[range.clone()].iter().map(|c| f32::from(c.red)).sum();
+            let expected: f32 = range.map(exact).sum();
+
+            (actual - expected).abs() / window as f32
+        }).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn no_noise_is_plain_rasterization() {
+        let fill = slow_gradient();
+        let pixels = fill.rasterize_dithered(1000, Noise::new(0.0, 1));
+
+        for (x, pixel) in pixels.iter().enumerate() {
+            assert_eq!(*pixel, fill.sample_xy(x as u32, 0, 1000, 1));
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let fill = slow_gradient();
+
+        assert_eq!(fill.rasterize_dithered(200, Noise::new(1.0, 7)), fill.rasterize_dithered(200, Noise::new(1.0, 7)));
+        assert_ne!(fill.rasterize_dithered(200, Noise::new(1.0, 7)), fill.rasterize_dithered(200, Noise::new(1.0, 8)));
+    }
+
+    #[test]
+    fn stays_close_to_the_gradient() {
+        let fill = slow_gradient();
+
+        for (x, pixel) in fill.rasterize_dithered(1000, Noise::new(1.0, 3)).iter().enumerate() {
+            let plain = fill.sample_xy(x as u32, 0, 1000, 1);
+
+            assert!((i16::from(pixel.red) - i16::from(plain.red)).abs() <= 1);
+            assert!(pixel.red == pixel.green && pixel.green == pixel.blue);
+        }
+    }
+
+    #[test]
+    fn hides_banding() {
+        let fill = slow_gradient();
+
+        let plain = fill.rasterize_dithered(1000, Noise::new(0.0, 3));
+        let dithered = fill.rasterize_dithered(1000, Noise::new(1.0, 3));
+
+        assert!(max_window_error(&plain, 50) > 0.2);
+        assert!(max_window_error(&dithered, 50) < 0.15, "{}", max_window_error(&dithered, 50));
+    }
+
+    #[test]
+    fn clamps_at_the_ends() {
+        let fill = Fill::Gradient(vec![gray(0), gray(255)]);
+        let pixels = fill.rasterize_dithered(100, Noise::new(4.0, 3));
+
+        assert_eq!(pixels.len(), 100);
+        assert!(pixels.iter().all(|c| c.red == c.green));
+    }
+
+    #[test]
+    fn rainbow_matches_sample() {
+        for (x, pixel) in Fill::rainbow().rasterize_dithered(13, Noise::new(0.0, 0)).iter().enumerate() {
+            assert_eq!(*pixel, Fill::rainbow().sample_xy(x as u32, 0, 13, 1));
+        }
+
+        let pastel = Fill::Rainbow(RainbowParams { saturation: 0.5, lightness: 0.75, phase: 0.25, period: 2.0 });
+
+        for (x, pixel) in pastel.rasterize_dithered(13, Noise::new(0.0, 0)).iter().enumerate() {
+            assert_eq!(*pixel, pastel.sample_xy(x as u32, 0, 13, 1));
+        }
+    }
+
+    #[test]
+    fn degenerate_widths() {
+        assert!(slow_gradient().rasterize_dithered(0, Noise::new(1.0, 0)).is_empty());
+        assert_eq!(slow_gradient().rasterize_dithered(1, Noise::new(0.0, 0)), vec![gray(100)]);
+    }
+}
@@ -0,0 +1,267 @@
+use alloc::string::String;
+
+use thiserror::Error;
+
+use crate::color::Color;
+use crate::policy::Gamut;
+
+/// Why a string couldn't be parsed as a `Color` or `Fill`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    #[error("Missing leading '#' descriptor")]
+    MissingPrefix,
+
+    #[error("Invalid length")]
+    InvalidLength(usize),
+
+    #[error("Color must only contain ASCII characters")]
+    NonAsciiInput,
+
+    #[error("Invalid hex digit at position {position}")]
+    InvalidHexDigit { position: usize },
+
+    /// Only for fills: the string wasn't a hex color or one of the fill keywords.
+    #[error("Unknown keyword {0:?}")]
+    UnknownKeyword(String),
+
+    /// Only for fills: a `linear-gradient(...)`, `radial-gradient(...)`, `conic-gradient(...)` or
+    /// `rainbow(...)` that isn't closed, whose `at x y` or `from angle` can't be read, that breaks
+    /// the default `GradientRules`, or whose rainbow parameters are unknown or fail
+    /// `RainbowParams::validate`.
+    #[error("Invalid gradient {0:?}")]
+    InvalidGradient(String),
+
+    /// With `ParseOptions::allow_ansi`: an `ansi(...)` whose index isn't a number from 0 to 255.
+    #[error("Invalid ANSI color {0:?}")]
+    InvalidAnsi(String),
+
+    /// Only for fills: one of a `FillParser`'s `FillInputExtension`s claimed the string but couldn't
+    /// parse it.
+    #[error("Invalid fill {input:?}: {reason}")]
+    Extension { input: String, reason: String },
+}
+
+/// Why a gradient broke one of the `GradientRules`.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GradientError {
+    #[error("A gradient needs at least one stop")]
+    Empty,
+
+    #[error("A gradient needs at least two stops")]
+    SingleStop,
+
+    #[error("Gradient stop {index} repeats the one before it")]
+    RepeatedStop { index: usize },
+
+    #[error("Radial gradient center ({x}, {y}) is outside 0.0..=1.0")]
+    CenterOutOfRange { x: f32, y: f32 },
+
+    #[error("Conic gradient angle {0} isn't a finite number")]
+    InvalidAngle(f32),
+}
+
+/// Why a `RainbowParams` failed `RainbowParams::validate`.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RainbowError {
+    #[error("Rainbow {component} {value} is outside 0.0..=1.0")]
+    OutOfRange { component: &'static str, value: f32 },
+
+    #[error("Rainbow period {0} isn't a non-zero number")]
+    InvalidPeriod(f32),
+
+    #[error("Rainbow phase {0} isn't a finite number")]
+    InvalidPhase(f32),
+}
+
+/// Why a fill failed `Fill::validate`.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PolicyError {
+    #[error(transparent)]
+    Gradient(#[from] GradientError),
+
+    #[error(transparent)]
+    Rainbow(#[from] RainbowError),
+
+    #[error("Transparency isn't allowed")]
+    Transparent,
+
+    #[error("A gradient can have at most {max} stops, not {count}")]
+    TooManyStops { count: usize, max: usize },
+
+    #[error("{color} is outside the {gamut:?} gamut")]
+    OutOfGamut { color: Color, gamut: Gamut },
+}
+
+#[cfg(feature = "std")]
+#[derive(Error, Debug)]
+pub enum PaletteError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Missing or unrecognized palette header")]
+    InvalidHeader,
+
+    #[error("Unsupported palette version {0:?}")]
+    UnsupportedVersion(String),
+
+    #[error("Invalid color count {0:?}")]
+    InvalidCount(String),
+
+    #[error("Invalid color entry on line {line}: {entry:?}")]
+    InvalidEntry { line: usize, entry: String },
+
+    #[error("Expected {expected} colors, found {actual}")]
+    CountMismatch { expected: usize, actual: usize },
+
+    #[error("Raw palette length {0} is not a multiple of 3")]
+    InvalidLength(usize),
+
+    #[error("Unsupported color model {0:?}")]
+    UnsupportedColorModel(String),
+
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("Unrecognized palette format")]
+    UnknownFormat,
+}
+
+#[cfg(feature = "std")]
+#[derive(Error, Debug, PartialEq)]
+pub enum ThemeError {
+    #[error("Invalid color {value:?} on line {line}")]
+    InvalidColor { line: usize, value: String },
+
+    #[error("Invalid color {value:?} for {slot:?}")]
+    InvalidSlot { slot: String, value: String },
+
+    #[error("Theme is missing the {0:?} slot")]
+    MissingSlot(String),
+
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("{slot:?} refers to ${reference}, which isn't a slot")]
+    UnknownReference { slot: String, reference: String },
+
+    #[error("{0:?} refers back to itself")]
+    ReferenceCycle(String),
+}
+
+/// A component of an `Hsl`, `Hsv` or `Lab` color that `FloatPolicy::Reject` turned down.
+#[cfg(feature = "std")]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ComponentError {
+    #[error("{component} {value} isn't a finite number")]
+    NotFinite { component: &'static str, value: f32 },
+
+    #[error("{component} {value} is outside {min}..={max}")]
+    OutOfRange { component: &'static str, value: f32, min: f32, max: f32 },
+}
+
+#[cfg(feature = "std")]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CurveError {
+    #[error("A curve needs at least 2 points, found {0}")]
+    NotEnoughPoints(usize),
+
+    #[error("Curve point {index} is outside 0.0..=1.0")]
+    OutOfRange { index: usize },
+
+    #[error("Curve point {index} doesn't come after the one before it")]
+    NotIncreasing { index: usize },
+}
+
+/// One of the problems `GradientBuilder::build` reports.
+#[cfg(feature = "std")]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BuildError {
+    #[error("Gradient stop {index} at {position} is outside 0.0..=1.0")]
+    PositionOutOfRange { index: usize, position: f32 },
+
+    #[error("Gradient stop {index} at {position} comes before the one ahead of it, at {previous}")]
+    UnorderedStop { index: usize, position: f32, previous: f32 },
+
+    #[error("Gradient stop {index} is at {position}, the same as the one before it")]
+    DuplicatePosition { index: usize, position: f32 },
+
+    #[error("Conic gradient angle {0} is outside 0.0..360.0")]
+    AngleOutOfRange(f32),
+
+    /// The built gradient breaks one of the `GradientRules`.
+    #[error(transparent)]
+    Gradient(#[from] GradientError),
+}
+
+#[cfg(feature = "tokio")]
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Can't tell the format of {0:?} from its extension")]
+    UnknownExtension(std::path::PathBuf),
+
+    #[error("Invalid {format}: {message}")]
+    Invalid { format: &'static str, message: String },
+
+    /// A `ThemeWatcher`'s validator turned the theme down.
+    #[error("Theme rejected: {0}")]
+    Rejected(String),
+
+    #[cfg(feature = "notify")]
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::boxed::Box;
+    use alloc::string::ToString;
+
+    use core::str::FromStr;
+
+    use crate::color::Color;
+
+    #[test]
+    fn color_errors() {
+        assert_eq!(Color::from_str("fff"), Err(ParseColorError::MissingPrefix));
+        assert_eq!(Color::from_str("#ffff"), Err(ParseColorError::InvalidLength(5)));
+        assert_eq!(Color::from_str("#ffé"), Err(ParseColorError::NonAsciiInput));
+        assert_eq!(Color::from_str("#fxf"), Err(ParseColorError::InvalidHexDigit { position: 2 }));
+
+        // keywords are only a thing for fills
+        assert_eq!(Color::from_str("rainbow"), Err(ParseColorError::MissingPrefix));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ParseColorError::MissingPrefix.to_string(), "Missing leading '#' descriptor");
+        assert_eq!(ParseColorError::InvalidLength(5).to_string(), "Invalid length");
+        assert_eq!(ParseColorError::NonAsciiInput.to_string(), "Color must only contain ASCII characters");
+        assert_eq!(ParseColorError::InvalidHexDigit { position: 2 }.to_string(), "Invalid hex digit at position 2");
+        assert_eq!(ParseColorError::UnknownKeyword("hello".into()).to_string(), r#"Unknown keyword "hello""#);
+        assert_eq!(ParseColorError::InvalidGradient("conic-gradient(".into()).to_string(), r#"Invalid gradient "conic-gradient(""#);
+        assert_eq!(ParseColorError::InvalidAnsi("ansi(300)".into()).to_string(), r#"Invalid ANSI color "ansi(300)""#);
+    }
+
+    #[test]
+    fn gradient_errors() {
+        assert_eq!(GradientError::RepeatedStop { index: 3 }.to_string(), "Gradient stop 3 repeats the one before it");
+        assert_eq!(GradientError::CenterOutOfRange { x: 0.5, y: 1.25 }.to_string(), "Radial gradient center (0.5, 1.25) is outside 0.0..=1.0");
+    }
+
+    #[test]
+    fn converts_to_boxed_errors() {
+        fn parse(s: &str) -> Result<Color, Box<dyn core::error::Error + Send + Sync + 'static>> {
+            Ok(Color::from_str(s)?)
+        }
+
+        let err = parse("#12").unwrap_err();
+
+        assert_eq!(err.downcast_ref::<ParseColorError>(), Some(&ParseColorError::InvalidLength(3)));
+    }
+}
+
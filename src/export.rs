@@ -0,0 +1,348 @@
+//! Writing themes and fills out in other tools' formats.
+//!
+//! Each format implements `ExportFormat`. `Exporters::builtin()` holds the ones that ship with the
+//! crate, and applications can `register` their own to make them available by name or extension.
+
+use std::io::{self, Write};
+
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::palette::Palette;
+use crate::theme::Theme;
+
+mod lottie;
+
+pub use lottie::Lottie;
+
+/// A file format that themes and fills can be exported to.
+pub trait ExportFormat {
+    /// A short, lowercase name to pick the format by, like `"css"`.
+    fn name(&self) -> &str;
+
+    /// The file extension, without the dot.
+    fn extension(&self) -> &str;
+
+    /// Writes the slots in the theme's order.
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()>;
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+/// A set of export formats, looked up by name or extension. Later registrations win.
+pub struct Exporters {
+    formats: Vec<Box<dyn ExportFormat>>,
+}
+
+impl Exporters {
+    /// No formats at all.
+    pub fn new() -> Self {
+        Exporters { formats: Vec::new() }
+    }
+
+    /// `Css`, `Scss`, `Gpl`, the default `Lottie` and `Json`. Lottie files are `.json` too, but
+    /// `Json` comes later, so it's the one `by_extension("json")` finds.
+    pub fn builtin() -> Self {
+        let mut exporters = Exporters::new();
+
+        exporters.register(Css);
+        exporters.register(Scss);
+        exporters.register(Gpl);
+        exporters.register(Lottie::default());
+        exporters.register(Json);
+
+        exporters
+    }
+
+    pub fn register(&mut self, format: impl ExportFormat + 'static) {
+        self.formats.push(Box::new(format));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn ExportFormat> {
+        self.formats.iter().rev().find(|f| f.name() == name).map(|f| f.as_ref())
+    }
+
+    pub fn by_extension(&self, extension: &str) -> Option<&dyn ExportFormat> {
+        let extension = extension.trim_start_matches('.');
+
+        self.formats.iter().rev()
+            .find(|f| f.extension().eq_ignore_ascii_case(extension))
+            .map(|f| f.as_ref())
+    }
+
+    /// The names of every registered format, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.formats.iter().map(|f| f.name())
+    }
+}
+
+impl Default for Exporters {
+    fn default() -> Self {
+        Exporters::builtin()
+    }
+}
+
+fn hex(color: &Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+fn percent(fraction: f32) -> String {
+    format!("{}%", fraction * 100.0)
+}
+
+/// The fill as a CSS `<image>` or `<color>` value.
+fn css_value(fill: &Fill) -> String {
+    let stops = |colors: &[Color]| colors.iter().map(hex).collect::<Vec<_>>().join(", ");
+
+    match fill {
+        Fill::None => "none".to_string(),
+        Fill::Transparent => "transparent".to_string(),
+        Fill::Color(color) => hex(color),
+        // most likely CSS to begin with, like `var(--accent)`
+        Fill::Raw(raw) => raw.clone(),
+        Fill::Rainbow(_) => {
+            let colors = (0..=6).map(|i| fill.sample(i as f32 / 6.0)).collect::<Vec<_>>();
+
+            format!("linear-gradient(to right, {})", stops(&colors))
+        },
+        Fill::Gradient(colors) if colors.is_empty() => "none".to_string(),
+        Fill::Gradient(colors) => format!("linear-gradient(to right, {})", stops(colors)),
+        Fill::Radial { stops: colors, .. } if colors.is_empty() => "none".to_string(),
+        Fill::Radial { center: (x, y), stops: colors } => {
+            format!("radial-gradient(farthest-corner at {} {}, {})", percent(*x), percent(*y), stops(colors))
+        },
+        Fill::Conic { stops: colors, .. } if colors.is_empty() => "none".to_string(),
+        Fill::Conic { angle, stops: colors } => format!("conic-gradient(from {}deg, {})", angle, stops(colors)),
+    }
+}
+
+/// Custom properties on `:root`, one per slot, like `--background: #000000;`. A fill is written
+/// as `--fill`.
+pub struct Css;
+
+impl ExportFormat for Css {
+    fn name(&self) -> &str {
+        "css"
+    }
+
+    fn extension(&self) -> &str {
+        "css"
+    }
+
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+        if let Some(name) = &theme.name {
+            writeln!(writer, "/* {} */", name)?;
+        }
+
+        writeln!(writer, ":root {{")?;
+
+        for (slot, color) in &theme.colors {
+            writeln!(writer, "  --{}: {};", slot, hex(color))?;
+        }
+
+        writeln!(writer, "}}")
+    }
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, ":root {{\n  --fill: {};\n}}", css_value(fill))
+    }
+}
+
+/// One SCSS variable per slot, like `$background: #000000;`. A fill is written as `$fill`.
+pub struct Scss;
+
+impl ExportFormat for Scss {
+    fn name(&self) -> &str {
+        "scss"
+    }
+
+    fn extension(&self) -> &str {
+        "scss"
+    }
+
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+        if let Some(name) = &theme.name {
+            writeln!(writer, "// {}", name)?;
+        }
+
+        for (slot, color) in &theme.colors {
+            writeln!(writer, "${}: {};", slot, hex(color))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "$fill: {};", css_value(fill))
+    }
+}
+
+/// A GIMP palette, see `Palette::write_gpl`. Theme slots become named entries; a fill's stops
+/// become unnamed ones. Fills without colors of their own, like `"rainbow"`, can't be written.
+pub struct Gpl;
+
+impl ExportFormat for Gpl {
+    fn name(&self) -> &str {
+        "gpl"
+    }
+
+    fn extension(&self) -> &str {
+        "gpl"
+    }
+
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+        let mut palette = Palette::new();
+        palette.name = theme.name.clone();
+
+        for (slot, color) in &theme.colors {
+            palette.push_named(slot.as_str(), *color);
+        }
+
+        palette.write_gpl(writer)
+    }
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+        let colors = match fill {
+            Fill::Color(color) => std::slice::from_ref(color),
+            _ => fill.stops().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "only colors and gradients can be written as a GIMP palette")
+            })?,
+        };
+
+        Palette::from(colors.to_vec()).write_gpl(writer)
+    }
+}
+
+/// Pretty-printed JSON, in the same form the `Deserialize` impls read.
+pub struct Json;
+
+impl ExportFormat for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, theme)?;
+        writeln!(writer)
+    }
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, fill)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const MAGENTA: Color = Color { red: 255, green: 0, blue: 255 };
+
+    fn theme() -> Theme {
+        let mut theme = Theme::new();
+        theme.name = Some("Night".to_string());
+        theme.set("background", BLACK);
+        theme.set("accent", MAGENTA);
+
+        theme
+    }
+
+    fn theme_as(format: &dyn ExportFormat) -> String {
+        let mut out = Vec::new();
+        format.write_theme(&theme(), &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    fn fill_as(format: &dyn ExportFormat, fill: &Fill) -> io::Result<String> {
+        let mut out = Vec::new();
+        format.write_fill(fill, &mut out)?;
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn css() {
+        assert_eq!(theme_as(&Css), "/* Night */\n:root {\n  --background: #000000;\n  --accent: #ff00ff;\n}\n");
+        assert_eq!(fill_as(&Css, &Fill::Color(MAGENTA)).unwrap(), ":root {\n  --fill: #ff00ff;\n}\n");
+    }
+
+    #[test]
+    fn css_gradients() {
+        let value = |fill| css_value(&fill);
+
+        assert_eq!(value(Fill::Gradient(vec![BLACK, MAGENTA])), "linear-gradient(to right, #000000, #ff00ff)");
+        assert_eq!(value(Fill::Radial { center: (0.25, 0.5), stops: vec![BLACK, MAGENTA] }), "radial-gradient(farthest-corner at 25% 50%, #000000, #ff00ff)");
+        assert_eq!(value(Fill::Conic { angle: 90.0, stops: vec![BLACK, MAGENTA] }), "conic-gradient(from 90deg, #000000, #ff00ff)");
+        assert_eq!(value(Fill::Gradient(vec![])), "none");
+        assert!(value(Fill::rainbow()).starts_with("linear-gradient(to right, #ff0000, "));
+        assert_eq!(value(Fill::Transparent), "transparent");
+    }
+
+    #[test]
+    fn scss() {
+        assert_eq!(theme_as(&Scss), "// Night\n$background: #000000;\n$accent: #ff00ff;\n");
+        assert_eq!(fill_as(&Scss, &Fill::None).unwrap(), "$fill: none;\n");
+    }
+
+    #[test]
+    fn gpl() {
+        assert_eq!(theme_as(&Gpl), "GIMP Palette\nName: Night\n#\n  0   0   0\tbackground\n255   0 255\taccent\n");
+        assert_eq!(fill_as(&Gpl, &Fill::Gradient(vec![MAGENTA, BLACK])).unwrap(), "GIMP Palette\n#\n255   0 255\n  0   0   0\n");
+        assert_eq!(fill_as(&Gpl, &Fill::rainbow()).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        assert_eq!(serde_json::from_str::<Theme>(&theme_as(&Json)).unwrap(), theme());
+
+        let fill = Fill::Conic { angle: 45.0, stops: vec![BLACK, MAGENTA] };
+
+        assert_eq!(serde_json::from_str::<Fill>(&fill_as(&Json, &fill).unwrap()).unwrap(), fill);
+    }
+
+    #[test]
+    fn lookup() {
+        let exporters = Exporters::builtin();
+
+        assert_eq!(exporters.names().collect::<Vec<_>>(), vec!["css", "scss", "gpl", "lottie", "json"]);
+        assert_eq!(exporters.get("scss").unwrap().extension(), "scss");
+        assert_eq!(exporters.by_extension(".GPL").unwrap().name(), "gpl");
+        assert_eq!(exporters.by_extension("json").unwrap().name(), "json");
+        assert!(exporters.get("svg").is_none());
+    }
+
+    #[test]
+    fn custom_formats_override_builtins() {
+        struct Shouty;
+
+        impl ExportFormat for Shouty {
+            fn name(&self) -> &str {
+                "css"
+            }
+
+            fn extension(&self) -> &str {
+                "txt"
+            }
+
+            fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+                writeln!(writer, "{} SLOTS", theme.colors.len())
+            }
+
+            fn write_fill(&self, _: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+                writeln!(writer, "A FILL")
+            }
+        }
+
+        let mut exporters = Exporters::builtin();
+        exporters.register(Shouty);
+
+        assert_eq!(theme_as(exporters.get("css").unwrap()), "2 SLOTS\n");
+        assert_eq!(exporters.by_extension("css").unwrap().name(), "css");
+        assert_eq!(exporters.by_extension("txt").unwrap().extension(), "txt");
+    }
+}
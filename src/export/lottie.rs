@@ -0,0 +1,342 @@
+//! Lottie shapes, the JSON After Effects animations are exported to with Bodymovin.
+
+use serde_json::{json, Value};
+
+use std::io::{self, Write};
+
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::theme::Theme;
+
+use super::ExportFormat;
+
+/// A fill as a Lottie fill shape, the object that goes in a shape layer's `shapes`: `"gf"` for a
+/// linear or radial gradient and `"fl"` for a solid color. Gradient points are in a `width` by
+/// `height` layer with its origin at the top left, and a `Rainbow` is a looping color animation
+/// at `frame_rate` frames per second, taking `t` as seconds.
+///
+/// A theme becomes the animation's `slots`, one color per theme slot, for players that support
+/// slot theming. Conic gradients and `"none"` have no Lottie equivalent and can't be written.
+///
+/// `write_keyframes` animates between gradients, for fills that change over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lottie {
+    pub width: f32,
+    pub height: f32,
+    pub frame_rate: f32,
+}
+
+impl Default for Lottie {
+    /// A 100 by 100 layer at 60 frames per second.
+    fn default() -> Self {
+        Lottie { width: 100.0, height: 100.0, frame_rate: 60.0 }
+    }
+}
+
+/// How many keyframes a rainbow gets per trip around the hue wheel, one every 30°.
+const RAINBOW_KEYFRAMES: usize = 12;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
+
+/// A channel as Lottie's `0.0..=1.0`.
+fn unit(c: u8) -> f64 {
+    f64::from(c) / 255.0
+}
+
+fn rgba(color: &Color, alpha: f64) -> Vec<f64> {
+    vec![unit(color.red), unit(color.green), unit(color.blue), alpha]
+}
+
+/// `[offset, r, g, b, ...]` with the stops evenly spaced.
+fn gradient_colors(stops: &[Color]) -> Vec<f64> {
+    let last = stops.len().saturating_sub(1).max(1) as f64;
+
+    stops.iter().enumerate()
+        .flat_map(|(i, c)| vec![i as f64 / last, unit(c.red), unit(c.green), unit(c.blue)])
+        .collect()
+}
+
+fn fixed(value: Value) -> Value {
+    json!({ "a": 0, "k": value })
+}
+
+/// Keyframes that move linearly from each value to the next, at the given frames.
+fn animated(keys: Vec<(f64, Value)>) -> Value {
+    let last = keys.len().saturating_sub(1);
+
+    let keys: Vec<Value> = keys.into_iter().enumerate().map(|(i, (frame, value))| {
+        if i == last {
+            json!({ "t": frame, "s": value })
+        } else {
+            json!({ "t": frame, "s": value, "o": { "x": [0.0], "y": [0.0] }, "i": { "x": [1.0], "y": [1.0] } })
+        }
+    }).collect();
+
+    json!({ "a": 1, "k": keys })
+}
+
+fn solid(color: Value, opacity: u32) -> Value {
+    json!({ "ty": "fl", "nm": "Fill", "c": color, "o": fixed(json!(opacity)), "r": 1 })
+}
+
+impl Lottie {
+    /// The `"gf"` kind, start and end points of a gradient fill.
+    fn geometry(&self, fill: &Fill) -> io::Result<(u32, [f32; 2], [f32; 2])> {
+        let (w, h) = (self.width, self.height);
+
+        match fill {
+            Fill::Gradient(_) => Ok((1, [0.0, h / 2.0], [w, h / 2.0])),
+            Fill::Radial { center: (x, y), .. } => {
+                let start = [x * w, y * h];
+                // the farthest corner, like `Fill::sample_xy`
+                let end = [if *x < 0.5 { w } else { 0.0 }, if *y < 0.5 { h } else { 0.0 }];
+
+                Ok((2, start, end))
+            },
+            _ => Err(invalid("only linear and radial gradients can be Lottie gradient keyframes")),
+        }
+    }
+
+    fn gradient(&self, fill: &Fill, stops: &[Color]) -> io::Result<Value> {
+        if stops.is_empty() {
+            return Err(invalid("a gradient without stops can't be written as Lottie"))
+        }
+
+        let (kind, start, end) = self.geometry(fill)?;
+
+        Ok(json!({
+            "ty": "gf",
+            "nm": "Gradient Fill",
+            "t": kind,
+            "s": fixed(json!(start)),
+            "e": fixed(json!(end)),
+            "g": { "p": stops.len(), "k": fixed(json!(gradient_colors(stops))) },
+            "o": fixed(json!(100)),
+            "r": 1,
+        }))
+    }
+
+    /// The fill shape for `fill`.
+    pub fn shape(&self, fill: &Fill) -> io::Result<Value> {
+        match fill {
+            Fill::Color(color) => Ok(solid(fixed(json!(rgba(color, 1.0))), 100)),
+            Fill::Transparent => Ok(solid(fixed(json!([0.0, 0.0, 0.0, 1.0])), 0)),
+            Fill::Rainbow(params) => {
+                let seconds = f64::from(params.period.abs());
+
+                let keys = (0..=RAINBOW_KEYFRAMES).map(|i| {
+                    let t = seconds * i as f64 / RAINBOW_KEYFRAMES as f64;
+                    let frame = t * f64::from(self.frame_rate);
+
+                    (frame, json!(rgba(&params.sample(t as f32), 1.0)))
+                }).collect();
+
+                Ok(solid(animated(keys), 100))
+            },
+            Fill::Gradient(stops) | Fill::Radial { stops, .. } => self.gradient(fill, stops),
+            Fill::None => Err(invalid("\"none\" draws nothing, so it has no Lottie fill")),
+            Fill::Conic { .. } => Err(invalid("Lottie has no conic gradients")),
+            Fill::Raw(raw) => Err(invalid(&format!("{:?} isn't a fill Lottie can show", raw))),
+        }
+    }
+
+    /// A gradient fill shape that animates through `keys`, each a time in seconds and the
+    /// gradient to show then. The gradients have to be the same kind, with the same number of
+    /// stops, and their start and end points are those of the first.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill};
+    /// use led_json::export::Lottie;
+    ///
+    /// let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+    /// let keys = [(0.0, Fill::Gradient(vec![red.clone(), blue.clone()])), (2.0, Fill::Gradient(vec![blue, red]))];
+    ///
+    /// let mut out = Vec::new();
+    /// Lottie::default().write_keyframes(&keys, &mut out).unwrap();
+    ///
+    /// let shape: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    ///
+    /// assert_eq!(shape["g"]["k"]["k"][1]["t"], 120.0);
+    /// ```
+    pub fn write_keyframes(&self, keys: &[(f32, Fill)], writer: &mut dyn Write) -> io::Result<()> {
+        let (first, stops) = match keys.first() {
+            Some((_, fill)) => (fill, fill.stops().unwrap_or(&[])),
+            None => return Err(invalid("an animation needs at least one keyframe")),
+        };
+
+        let mut shape = self.gradient(first, stops)?;
+        let mut values = Vec::new();
+
+        for (t, fill) in keys {
+            let same_kind = core::mem::discriminant(fill) == core::mem::discriminant(first);
+
+            match fill.stops() {
+                Some(s) if same_kind && s.len() == stops.len() => {
+                    values.push((f64::from(*t) * f64::from(self.frame_rate), json!(gradient_colors(s))));
+                },
+                _ => return Err(invalid("every keyframe has to be the same kind of gradient with the same number of stops")),
+            }
+        }
+
+        shape["g"]["k"] = animated(values);
+
+        serde_json::to_writer_pretty(&mut *writer, &shape)?;
+        writeln!(writer)
+    }
+}
+
+impl ExportFormat for Lottie {
+    fn name(&self) -> &str {
+        "lottie"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn write_theme(&self, theme: &Theme, writer: &mut dyn Write) -> io::Result<()> {
+        let slots: serde_json::Map<String, Value> = theme.colors.iter()
+            .map(|(slot, color)| (slot.to_string(), json!({ "p": fixed(json!(rgba(color, 1.0))) })))
+            .collect();
+
+        let mut out = json!({ "slots": slots });
+
+        if let Some(name) = &theme.name {
+            out["nm"] = json!(name);
+        }
+
+        serde_json::to_writer_pretty(&mut *writer, &out)?;
+        writeln!(writer)
+    }
+
+    fn write_fill(&self, fill: &Fill, writer: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, &self.shape(fill)?)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::rainbow::RainbowParams;
+
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    fn shape(fill: &Fill) -> Value {
+        let mut out = Vec::new();
+        Lottie::default().write_fill(fill, &mut out).unwrap();
+
+        serde_json::from_slice(&out).unwrap()
+    }
+
+    fn error(fill: &Fill) -> io::ErrorKind {
+        Lottie::default().write_fill(fill, &mut Vec::new()).unwrap_err().kind()
+    }
+
+    #[test]
+    fn linear() {
+        let shape = shape(&Fill::Gradient(vec![RED, BLACK, BLUE]));
+
+        assert_eq!(shape["ty"], "gf");
+        assert_eq!(shape["t"], 1);
+        assert_eq!(shape["s"]["k"], json!([0.0, 50.0]));
+        assert_eq!(shape["e"]["k"], json!([100.0, 50.0]));
+        assert_eq!(shape["g"]["p"], 3);
+        assert_eq!(shape["g"]["k"], json!({ "a": 0, "k": [0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0] }));
+    }
+
+    #[test]
+    fn radial() {
+        let lottie = Lottie { width: 200.0, height: 100.0, frame_rate: 30.0 };
+        let shape = lottie.shape(&Fill::Radial { center: (0.25, 0.75), stops: vec![RED] }).unwrap();
+
+        assert_eq!(shape["t"], 2);
+        assert_eq!(shape["s"]["k"], json!([50.0, 75.0]));
+        assert_eq!(shape["e"]["k"], json!([200.0, 0.0]));
+        assert_eq!(shape["g"]["k"]["k"], json!([0.0, 1.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn solid_colors() {
+        let shape = shape(&Fill::Color(BLUE));
+
+        assert_eq!(shape["ty"], "fl");
+        assert_eq!(shape["c"], json!({ "a": 0, "k": [0.0, 0.0, 1.0, 1.0] }));
+        assert_eq!(shape["o"]["k"], 100);
+
+        assert_eq!(self::shape(&Fill::Transparent)["o"]["k"], 0);
+    }
+
+    #[test]
+    fn rainbow_loops() {
+        let fill = Fill::Rainbow(RainbowParams { period: -2.0, ..RainbowParams::DEFAULT });
+        let keys = shape(&fill)["c"]["k"].as_array().unwrap().clone();
+
+        assert_eq!(shape(&fill)["c"]["a"], 1);
+        assert_eq!(keys.len(), RAINBOW_KEYFRAMES + 1);
+        assert_eq!(keys[0]["t"], 0.0);
+        assert_eq!(keys[RAINBOW_KEYFRAMES]["t"], 120.0);
+        assert_eq!(keys[0]["s"], keys[RAINBOW_KEYFRAMES]["s"]);
+        // a negative period runs the wheel backwards, from red to magenta
+        assert_eq!(keys[2]["s"], json!([1.0, 0.0, 1.0, 1.0]));
+        assert!(keys[RAINBOW_KEYFRAMES].get("o").is_none());
+        assert_eq!(keys[0]["o"], json!({ "x": [0.0], "y": [0.0] }));
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(error(&Fill::None), io::ErrorKind::InvalidInput);
+        assert_eq!(error(&Fill::Conic { angle: 0.0, stops: vec![RED] }), io::ErrorKind::InvalidInput);
+        assert_eq!(error(&Fill::Gradient(vec![])), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn keyframes() {
+        let lottie = Lottie::default();
+        let keys = [
+            (0.0, Fill::Radial { center: (0.5, 0.5), stops: vec![RED, BLUE] }),
+            (0.5, Fill::Radial { center: (0.5, 0.5), stops: vec![BLUE, RED] }),
+        ];
+
+        let mut out = Vec::new();
+        lottie.write_keyframes(&keys, &mut out).unwrap();
+        let shape: Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(shape["t"], 2);
+        assert_eq!(shape["g"]["k"]["a"], 1);
+        assert_eq!(shape["g"]["k"]["k"][1], json!({ "t": 30.0, "s": [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0] }));
+    }
+
+    #[test]
+    fn keyframes_must_match() {
+        let lottie = Lottie::default();
+        let write = |keys: &[(f32, Fill)]| lottie.write_keyframes(keys, &mut Vec::new()).map_err(|e| e.kind());
+
+        assert_eq!(write(&[]), Err(io::ErrorKind::InvalidInput));
+        assert_eq!(write(&[(0.0, Fill::Gradient(vec![RED])), (1.0, Fill::Gradient(vec![RED, BLUE]))]), Err(io::ErrorKind::InvalidInput));
+        assert_eq!(
+            write(&[(0.0, Fill::Gradient(vec![RED])), (1.0, Fill::Radial { center: (0.5, 0.5), stops: vec![RED] })]),
+            Err(io::ErrorKind::InvalidInput)
+        );
+        assert_eq!(write(&[(0.0, Fill::Color(RED))]), Err(io::ErrorKind::InvalidInput));
+        assert_eq!(write(&[(0.0, Fill::Gradient(vec![RED]))]), Ok(()));
+    }
+
+    #[test]
+    fn theme_slots() {
+        let mut theme = Theme::new();
+        theme.name = Some("Night".to_string());
+        theme.set("accent", RED);
+
+        let mut out = Vec::new();
+        Lottie::default().write_theme(&theme, &mut out).unwrap();
+        let value: Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(value, json!({ "nm": "Night", "slots": { "accent": { "p": { "a": 0, "k": [1.0, 0.0, 0.0, 1.0] } } } }));
+    }
+}
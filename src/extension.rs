@@ -0,0 +1,272 @@
+//! Application-defined string forms for fills, like `"pantone:186C"`, read by the `FillParser`s
+//! they're given to.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::error::ParseColorError;
+use crate::fill::Fill;
+
+/// Parses the strings that start with `prefix()`. A `FillParser` with it in its
+/// `InputExtensions` hands it any string that isn't one of the keywords, a name or a color, in
+/// place of failing with `ParseColorError::UnknownKeyword`. Only whole fills go through
+/// extensions, not gradient stops.
+pub trait FillInputExtension: Send + Sync {
+    /// What the strings this handles start with, like `"pantone:"`.
+    fn prefix(&self) -> &str;
+
+    /// The fill for what follows the prefix, like `"186C"`, or why it isn't one.
+    fn parse(&self, rest: &str) -> core::result::Result<Fill, String>;
+}
+
+/// The extensions a `FillParser` reads, given to it with `FillParser::extensions`. Plain
+/// `Fill::from_str` and `Deserialize` never use any, so what a fill parses as doesn't depend on
+/// what some other part of the program set up.
+///
+/// ```
+/// use led_json::{Color, Fill, FillInputExtension, FillParser, InputExtensions, ParseOptions};
+/// use serde::de::DeserializeSeed;
+///
+/// struct Pantone;
+///
+/// impl FillInputExtension for Pantone {
+///     fn prefix(&self) -> &str {
+///         "pantone:"
+///     }
+///
+///     fn parse(&self, rest: &str) -> Result<Fill, String> {
+///         match rest {
+///             "186C" => Ok(Fill::Color(Color::new(0xc8, 0x10, 0x2e))),
+///             _ => Err(format!("no Pantone color called {}", rest)),
+///         }
+///     }
+/// }
+///
+/// let mut extensions = InputExtensions::new();
+/// extensions.register(Pantone);
+///
+/// let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+/// let fill = parser.deserialize(&mut serde_json::Deserializer::from_str(r#""pantone:186C""#)).unwrap();
+///
+/// assert_eq!(fill, Fill::Color(Color::new(0xc8, 0x10, 0x2e)));
+/// assert!(parser.parse("pantone:999X").is_err());
+/// assert!(serde_json::from_str::<Fill>(r#""pantone:186C""#).is_err());
+/// ```
+#[derive(Default)]
+pub struct InputExtensions {
+    extensions: Vec<Box<dyn FillInputExtension>>,
+}
+
+/// What the parsing functions take: no extensions at all, or some to try last.
+pub(crate) type Extensions<'a> = Option<&'a InputExtensions>;
+
+impl InputExtensions {
+    pub fn new() -> Self {
+        InputExtensions::default()
+    }
+
+    /// Adds `extension`, replacing one registered before it with the same prefix.
+    pub fn register(&mut self, extension: impl FillInputExtension + 'static) {
+        self.extensions.retain(|e| e.prefix() != extension.prefix());
+        self.extensions.push(Box::new(extension));
+    }
+
+    /// The prefixes of every registered extension, in registration order.
+    pub fn prefixes(&self) -> impl Iterator<Item = &str> {
+        self.extensions.iter().map(|e| e.prefix())
+    }
+
+    /// What the first extension whose prefix `s` starts with makes of it, if there is one.
+    pub(crate) fn parse(&self, s: &str) -> Option<core::result::Result<Fill, ParseColorError>> {
+        self.extensions.iter().find_map(|extension| {
+            let rest = s.strip_prefix(extension.prefix())?;
+
+            Some(extension.parse(rest).map_err(|reason| ParseColorError::Extension { input: s.into(), reason }))
+        })
+    }
+}
+
+impl fmt::Debug for InputExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.prefixes()).finish()
+    }
+}
+
+/// An extension that keeps every string starting with its prefix as a `Fill::Raw`, like the CSS
+/// values `url(...)` and `var(...)`, so documents that mix them with fills round-trip instead of
+/// failing to parse.
+///
+/// ```
+/// use led_json::{Fill, FillParser, InputExtensions, ParseOptions, RawPrefix};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut extensions = InputExtensions::new();
+/// extensions.register(RawPrefix::new("var("));
+///
+/// let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+/// let fill = parser.deserialize(&mut serde_json::Deserializer::from_str(r#""var(--accent)""#)).unwrap();
+///
+/// assert_eq!(fill, Fill::Raw("var(--accent)".into()));
+/// assert_eq!(serde_json::to_string(&fill).unwrap(), r#""var(--accent)""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawPrefix(String);
+
+impl RawPrefix {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        RawPrefix(prefix.into())
+    }
+}
+
+impl FillInputExtension for RawPrefix {
+    fn prefix(&self) -> &str {
+        &self.0
+    }
+
+    fn parse(&self, rest: &str) -> core::result::Result<Fill, String> {
+        Ok(Fill::Raw(format!("{}{}", self.0, rest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::collections::BTreeMap;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use serde::de::DeserializeSeed;
+
+    use crate::color::{Color, ParseOptions};
+    use crate::resolver::FillParser;
+
+    struct Gray(&'static str);
+
+    impl FillInputExtension for Gray {
+        fn prefix(&self) -> &str {
+            self.0
+        }
+
+        fn parse(&self, rest: &str) -> core::result::Result<Fill, String> {
+            let level: u8 = rest.parse().map_err(|_| format!("{:?} isn't a level from 0 to 255", rest))?;
+
+            Ok(Fill::Color(Color::new(level, level, level)))
+        }
+    }
+
+    fn extensions(list: impl IntoIterator<Item = &'static str>) -> InputExtensions {
+        let mut extensions = InputExtensions::new();
+
+        for prefix in list {
+            extensions.register(Gray(prefix));
+        }
+
+        extensions
+    }
+
+    fn deserialize(parser: FillParser<'_>, json: &str) -> serde_json::Result<Fill> {
+        parser.deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn parse_and_deserialize() {
+        let extensions = extensions(["gray:"]);
+        let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+
+        assert_eq!(parser.parse("gray:128"), Ok(Fill::Color(Color::new(128, 128, 128))));
+        assert_eq!(deserialize(parser, r#""gray:0""#).unwrap(), Fill::Color(Color::new(0, 0, 0)));
+        assert_eq!(deserialize(parser, r##""#fff""##).unwrap(), Fill::Color(Color::new(255, 255, 255)));
+        assert_eq!(deserialize(parser, r#""rainbow""#).unwrap(), Fill::rainbow());
+    }
+
+    #[test]
+    fn only_where_given() {
+        let extensions = extensions(["gray:"]);
+        let unknown = Err(ParseColorError::UnknownKeyword("gray:1".into()));
+
+        assert_eq!("gray:1".parse::<Fill>(), unknown);
+        assert_eq!(FillParser::new(ParseOptions::default()).parse("gray:1"), unknown);
+        assert!(serde_json::from_str::<Fill>(r#""gray:1""#).is_err());
+        assert!(FillParser::new(ParseOptions::default()).extensions(&extensions).parse("gray:1").is_ok());
+    }
+
+    #[test]
+    fn errors() {
+        let extensions = extensions(["grey:"]);
+        let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+
+        assert_eq!(
+            parser.parse("grey:300"),
+            Err(ParseColorError::Extension { input: "grey:300".into(), reason: "\"300\" isn't a level from 0 to 255".into() })
+        );
+
+        let err = deserialize(parser, r#""grey:300""#).unwrap_err();
+
+        assert!(err.to_string().contains("isn't a level"), "{}", err);
+        assert_eq!(parser.parse("greige:1"), Err(ParseColorError::UnknownKeyword("greige:1".into())));
+    }
+
+    #[test]
+    fn keywords_and_colors_come_first() {
+        let extensions = extensions(["#", "rain"]);
+        let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+
+        assert_eq!(parser.parse("#fff"), Ok(Fill::Color(Color::new(255, 255, 255))));
+        assert_eq!(deserialize(parser, r#""rainbow""#).unwrap(), Fill::rainbow());
+
+        // stops aren't handed to extensions
+        assert!(deserialize(parser, r#"["rain1", "rain2"]"#).is_err());
+    }
+
+    #[test]
+    fn replaces_the_same_prefix() {
+        struct Black;
+
+        impl FillInputExtension for Black {
+            fn prefix(&self) -> &str {
+                "level:"
+            }
+
+            fn parse(&self, _: &str) -> core::result::Result<Fill, String> {
+                Ok(Fill::Color(Color::new(0, 0, 0)))
+            }
+        }
+
+        let mut extensions = InputExtensions::new();
+        extensions.register(Black);
+        extensions.register(Gray("other:"));
+        extensions.register(Gray("level:"));
+
+        assert_eq!(extensions.prefixes().collect::<Vec<_>>(), ["other:", "level:"]);
+        assert_eq!(format!("{:?}", extensions), r#"["other:", "level:"]"#);
+        assert_eq!(
+            FillParser::new(ParseOptions::default()).extensions(&extensions).parse("level:9"),
+            Ok(Fill::Color(Color::new(9, 9, 9)))
+        );
+    }
+
+    #[test]
+    fn raw_prefixes_round_trip() {
+        let mut extensions = InputExtensions::new();
+        extensions.register(RawPrefix::new("url("));
+        extensions.register(RawPrefix::new("env("));
+
+        let parser = FillParser::new(ParseOptions::default()).extensions(&extensions);
+        let json = r##"{"background":"url(stars.png)","accent":"#ff0000","glow":"env(glow)"}"##;
+        let values: BTreeMap<String, serde_json::Value> = serde_json::from_str(json).unwrap();
+        let fills: BTreeMap<&String, Fill> = values.iter().map(|(key, value)| (key, parser.deserialize(value).unwrap())).collect();
+
+        assert_eq!(fills[&"background".to_string()], Fill::Raw("url(stars.png)".into()));
+        assert_eq!(fills[&"glow".to_string()].to_string(), "env(glow)");
+        assert_eq!(serde_json::to_string(&fills).unwrap(), r##"{"accent":"#ff0000","background":"url(stars.png)","glow":"env(glow)"}"##);
+
+        // anything else is still an error
+        assert_eq!(parser.parse("calc(1)"), Err(ParseColorError::UnknownKeyword("calc(1)".into())));
+        assert_eq!(vec![parser.parse("url(a)")], [Ok(Fill::Raw("url(a)".into()))]);
+    }
+}
@@ -0,0 +1,1808 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor, SeqAccess, MapAccess, DeserializeSeed};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::str::FromStr;
+use core::fmt;
+
+use crate::binary;
+use crate::color::{self, Color, ParseOptions, ParseStr};
+use crate::error::{GradientError, ParseColorError, PolicyError};
+use crate::extension::Extensions;
+use crate::format::{ColorFormat, Formatted};
+use crate::rainbow::RainbowParams;
+use crate::resolver::Resolver;
+use crate::warning::{Warning, Warnings};
+
+/// Capacity to reserve for a sequence, from its size hint. The hint comes from the input, so it's
+/// capped to keep a bogus one from allocating a huge buffer up front.
+pub(crate) fn cautious(hint: Option<usize>) -> usize {
+    hint.unwrap_or(0).min(4096)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+// #[serde(untagged)]
+pub enum Fill {
+    /// `"rainbow"`, or `{ "rainbow": { "period": 2.0, ... } }`: cycles through the hues; see
+    /// `RainbowParams`.
+    Rainbow(RainbowParams),
+
+    /// `"none"`: nothing should be drawn at all.
+    None,
+
+    /// `"transparent"`: something is drawn, but it's fully see-through.
+    Transparent,
+
+    Color(Color),
+
+    /// Stops spread evenly from left to right. Serialized as a bare array of stops.
+    Gradient(Vec<Color>),
+
+    /// `{ "type": "radial", "center": [0.5, 0.5], "stops": [...] }`: stops spread from `center`,
+    /// given as fractions of the surface, out to its farthest corner. `center` defaults to the
+    /// middle.
+    Radial { center: (f32, f32), stops: Vec<Color> },
+
+    /// `{ "type": "conic", "angle": 90.0, "stops": [...] }`: stops sweep clockwise around the
+    /// middle of the surface, starting `angle` degrees clockwise from straight up. `angle`
+    /// defaults to `0.0`.
+    Conic { angle: f32, stops: Vec<Color> },
+
+    /// A string that isn't a fill this crate understands, like `"var(--accent)"`, kept as it was
+    /// written so the document round-trips. Only parsed by a `FillParser` given a `RawPrefix`
+    /// that the string starts with.
+    Raw(String),
+}
+
+impl Fill {
+    /// `Fill::Rainbow` with the default `RainbowParams`, which is what `"rainbow"` is.
+    pub const fn rainbow() -> Fill {
+        Fill::Rainbow(RainbowParams::DEFAULT)
+    }
+
+    /// The stops of a linear, radial or conic gradient.
+    pub fn stops(&self) -> Option<&[Color]> {
+        match self {
+            Fill::Gradient(stops) | Fill::Radial { stops, .. } | Fill::Conic { stops, .. } => Some(stops),
+            _ => None,
+        }
+    }
+}
+
+/// What `Fill::default()` returns, once an application has picked one.
+#[cfg(feature = "std")]
+static GLOBAL_DEFAULT: std::sync::RwLock<Option<Fill>> = std::sync::RwLock::new(None);
+
+#[cfg(feature = "std")]
+impl Fill {
+    /// Makes `Fill::default()`, and so every `#[serde(default)]` fill field, return `fill` from now
+    /// on, in every thread. Meant to be called once at startup.
+    ///
+    /// ```
+    /// use led_json::Fill;
+    ///
+    /// Fill::set_global_default(Fill::rainbow());
+    ///
+    /// assert_eq!(Fill::default(), Fill::rainbow());
+    /// ```
+    pub fn set_global_default(fill: Fill) {
+        *GLOBAL_DEFAULT.write().unwrap_or_else(|e| e.into_inner()) = Some(fill);
+    }
+
+    /// Goes back to `Fill::None` as the default.
+    pub fn clear_global_default() {
+        *GLOBAL_DEFAULT.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+impl Default for Fill {
+    /// The fill passed to `Fill::set_global_default`, or `Fill::None` if there hasn't been one.
+    /// Without the `std` feature, always `Fill::None`.
+    fn default() -> Self {
+        #[cfg(feature = "std")]
+        {
+            if let Some(fill) = &*GLOBAL_DEFAULT.read().unwrap_or_else(|e| e.into_inner()) {
+                return fill.clone()
+            }
+        }
+
+        Fill::None
+    }
+}
+
+/// What `Fill::validate_gradient` checks on top of the basics: every gradient needs a stop, a
+/// radial gradient's center has to be on the surface and a conic gradient's angle has to be a
+/// number. Deserializing checks the default rules.
+///
+/// Stops are evenly spaced from `0.0` to `1.0`, so their positions are always in range and in
+/// order. Stops at positions of their own go through `GradientBuilder`, which checks those too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GradientRules {
+    /// Accept a single stop, which draws as a flat color.
+    pub allow_single_stop: bool,
+
+    /// Accept the same color twice in a row, which draws as a flat band.
+    pub allow_repeated_stops: bool,
+}
+
+impl Default for GradientRules {
+    fn default() -> Self {
+        GradientRules { allow_single_stop: true, allow_repeated_stops: true }
+    }
+}
+
+impl GradientRules {
+    /// Only gradients that actually change color: at least two stops, none repeated.
+    pub fn strict() -> Self {
+        GradientRules { allow_single_stop: false, allow_repeated_stops: false }
+    }
+}
+
+impl Fill {
+    /// Checks a gradient against `rules`. Every other kind of fill passes. This stops at the first
+    /// broken rule; `gradient_violations` lists all of them.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill, GradientError, GradientRules};
+    ///
+    /// let flat = Fill::Gradient(vec![Color::new(255, 0, 0)]);
+    ///
+    /// assert_eq!(flat.validate_gradient(&GradientRules::default()), Ok(()));
+    /// assert_eq!(flat.validate_gradient(&GradientRules::strict()), Err(GradientError::SingleStop));
+    /// ```
+    pub fn validate_gradient(&self, rules: &GradientRules) -> core::result::Result<(), GradientError> {
+        match self.gradient_violations(rules).into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Every way a gradient breaks `rules`, in the order `validate_gradient` checks them, for
+    /// editors that show them all at once. Each repeated stop is reported on its own. Empty if the
+    /// gradient passes, or isn't one.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill, GradientError, GradientRules};
+    ///
+    /// let red = Color::new(255, 0, 0);
+    /// let fill = Fill::Radial { center: (1.5, 0.5), stops: vec![red, red, red] };
+    ///
+    /// assert_eq!(fill.gradient_violations(&GradientRules::strict()), [
+    ///     GradientError::CenterOutOfRange { x: 1.5, y: 0.5 },
+    ///     GradientError::RepeatedStop { index: 1 },
+    ///     GradientError::RepeatedStop { index: 2 },
+    /// ]);
+    /// ```
+    pub fn gradient_violations(&self, rules: &GradientRules) -> Vec<GradientError> {
+        let mut violations = Vec::new();
+
+        let stops = match self.stops() {
+            Some(stops) => stops,
+            None => return violations,
+        };
+
+        match self {
+            Fill::Radial { center: (x, y), .. } if !(0.0..=1.0).contains(x) || !(0.0..=1.0).contains(y) => {
+                violations.push(GradientError::CenterOutOfRange { x: *x, y: *y });
+            },
+            Fill::Conic { angle, .. } if !angle.is_finite() => violations.push(GradientError::InvalidAngle(*angle)),
+            _ => {},
+        }
+
+        match stops.len() {
+            0 => violations.push(GradientError::Empty),
+            1 if !rules.allow_single_stop => violations.push(GradientError::SingleStop),
+            _ => {},
+        }
+
+        if !rules.allow_repeated_stops {
+            let repeats = stops.windows(2).enumerate().filter(|(_, pair)| pair[0] == pair[1]);
+
+            violations.extend(repeats.map(|(index, _)| GradientError::RepeatedStop { index: index + 1 }));
+        }
+
+        violations
+    }
+}
+
+/// Checks `fill` against the default `GradientRules` and, for a rainbow,
+/// `RainbowParams::validate`: what every way of reading a fill has to accept or reject alike.
+pub(crate) fn check(fill: &Fill) -> core::result::Result<(), PolicyError> {
+    fill.validate_gradient(&GradientRules::default())?;
+
+    if let Fill::Rainbow(params) = fill {
+        params.validate()?;
+    }
+
+    Ok(())
+}
+
+/// `fill`, if it passes `check`.
+fn validated<E>(fill: Fill) -> core::result::Result<Fill, E>
+where
+    E: de::Error,
+{
+    check(&fill).map_err(E::custom)?;
+
+    Ok(fill)
+}
+
+/// Interpolates between evenly spaced stops. No stops is black.
+#[cfg(feature = "std")]
+fn sample_stops(colors: &[Color], t: f32) -> Color {
+    match colors.len() {
+        0 => Color { red: 0, green: 0, blue: 0 },
+        1 => colors[0],
+        len => {
+            let position = t * (len - 1) as f32;
+            let index = (position.floor() as usize).min(len - 2);
+
+            colors[index].lerp(&colors[index + 1], position - index as f32)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Fill {
+    /// The color of the fill at position `t`, clamped to `0.0..=1.0`.
+    ///
+    /// `Rainbow` is `RainbowParams::sample` at `t`, a solid `Color` is the same everywhere, and
+    /// every kind of gradient interpolates between evenly spaced stops. `None`, `Transparent`,
+    /// `Raw` and an empty gradient sample as black, i.e. the LED is off. Animate a rainbow through
+    /// `RainbowParams::sample`, which doesn't clamp `t`.
+    pub fn sample(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Fill::Rainbow(params) => params.sample(t),
+            Fill::None | Fill::Transparent | Fill::Raw(_) => Color { red: 0, green: 0, blue: 0 },
+            Fill::Color(color) => *color,
+            Fill::Gradient(stops) | Fill::Radial { stops, .. } | Fill::Conic { stops, .. } => sample_stops(stops, t),
+        }
+    }
+
+    /// The color of the pixel at `x`, `y` on a `width` by `height` surface.
+    ///
+    /// Linear gradients and `Rainbow` run left to right: the first column samples `t = 0.0` and
+    /// the last `t = 1.0`, whatever the row. `Radial` and `Conic` gradients place their center
+    /// and angle relative to the whole surface. Coordinates outside the surface are clamped to
+    /// its edges.
+    pub fn sample_xy(&self, x: u32, y: u32, width: u32, height: u32) -> Color {
+        self.sample(self.position(x, y, width, height))
+    }
+
+    /// The `t` that `sample_xy` samples at.
+    pub(crate) fn position(&self, x: u32, y: u32, width: u32, height: u32) -> f32 {
+        // 0.0 at the first pixel, 1.0 at the last
+        let unit = |i: u32, n: u32| match n {
+            0 | 1 => 0.0,
+            n => i.min(n - 1) as f32 / (n - 1) as f32,
+        };
+
+        let (u, v) = (unit(x, width), unit(y, height));
+
+        match self {
+            Fill::Radial { center: (cx, cy), .. } => {
+                let (dx, dy) = (cx.abs().max((1.0 - cx).abs()), cy.abs().max((1.0 - cy).abs()));
+
+                (u - cx).hypot(v - cy) / dx.hypot(dy)
+            },
+            Fill::Conic { angle, .. } => {
+                // clockwise from straight up, with y growing downwards
+                let theta = (u - 0.5).atan2(0.5 - v).to_degrees();
+
+                (theta - angle).rem_euclid(360.0) / 360.0
+            },
+            _ => u,
+        }
+    }
+}
+
+fn keyword(s: &str, ignore_case: bool) -> Option<Fill> {
+    let is = |keyword: &str| s == keyword || (ignore_case && s.eq_ignore_ascii_case(keyword));
+
+    if is("rainbow") {
+        Some(Fill::rainbow())
+    } else if is("none") {
+        Some(Fill::None)
+    } else if is("transparent") {
+        Some(Fill::Transparent)
+    } else {
+        None
+    }
+}
+
+/// A color `resolver` knows by name, or else one parsed according to `options`.
+fn resolved(s: &str, options: ParseOptions, resolver: Resolver<'_>, warnings: &mut Warnings) -> core::result::Result<Color, ParseColorError> {
+    match resolver.and_then(|r| r.resolve(s)) {
+        Some(color) => Ok(color),
+        None => color::parse(s, options, warnings),
+    }
+}
+
+/// A keyword, a color name, a single hex color or a form one of `extensions` adds: the only string
+/// forms the structured formats accept.
+fn keyword_or_color(
+    s: &str,
+    options: ParseOptions,
+    resolver: Resolver<'_>,
+    extensions: Extensions<'_>,
+    warnings: &mut Warnings,
+) -> core::result::Result<Fill, ParseColorError> {
+    if let Some(fill) = keyword(s, false) {
+        return Ok(fill)
+    }
+
+    if let Some(fill) = keyword(s, options.lenient) {
+        warnings.push(Warning::MixedCase);
+
+        return Ok(fill)
+    }
+
+    if let Some(color) = resolver.and_then(|r| r.resolve(s)) {
+        return Ok(Fill::Color(color))
+    }
+
+    if s.starts_with('#') {
+        return Ok(Fill::Color(color::parse(s, options, warnings)?))
+    }
+
+    // without a `#`, it's only a color if `options` let it parse as one
+    let mut repairs = Warnings::new();
+
+    match color::parse(s, options, &mut repairs) {
+        Ok(color) => {
+            warnings.extend(repairs);
+
+            Ok(Fill::Color(color))
+        },
+        Err(e) => match extensions.and_then(|extensions| extensions.parse(s)) {
+            Some(fill) => fill,
+            None => Err(unknown(s, e)),
+        },
+    }
+}
+
+/// The error for a string that's neither a keyword nor a color. One that's only missing the `#`
+/// of a hex color, like `"ff00aa"`, gets the color's error, `MissingPrefix`, which says how to
+/// fix it.
+fn unknown(s: &str, color_error: ParseColorError) -> ParseColorError {
+    if matches!(s.len(), 3 | 6) && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return color_error
+    }
+
+    ParseColorError::UnknownKeyword(s.into())
+}
+
+/// The arguments of `name(...)`, if `s` is a call to it.
+fn arguments<'a>(s: &'a str, name: &str) -> Option<core::result::Result<&'a str, ParseColorError>> {
+    let rest = s.strip_prefix(name)?.trim_start().strip_prefix('(')?;
+
+    Some(rest.strip_suffix(')').ok_or_else(|| ParseColorError::InvalidGradient(s.into())))
+}
+
+/// Comma-separated hex colors or color names. Nothing at all is no stops.
+fn stops<'a>(list: impl Iterator<Item = &'a str>, options: ParseOptions, resolver: Resolver<'_>, warnings: &mut Warnings) -> core::result::Result<Vec<Color>, ParseColorError> {
+    list.map(str::trim).filter(|s| !s.is_empty()).map(|s| resolved(s, options, resolver, warnings)).collect()
+}
+
+/// Splits a leading `prefix ...` argument, like `at 0.5 0.5`, off the gradient arguments.
+fn parameter<'a>(args: &'a str, prefix: &str) -> (Option<&'a str>, core::str::Split<'a, char>) {
+    let mut list = args.split(',');
+
+    match list.clone().next().and_then(|first| first.trim_start().strip_prefix(prefix)) {
+        Some(first) => {
+            list.next();
+
+            (Some(first.trim()), list)
+        },
+        None => (None, list),
+    }
+}
+
+/// The `name value` arguments of `rainbow(...)`, like `period 2, phase 0.5`. Missing ones keep
+/// their defaults.
+fn rainbow_params(args: &str) -> Option<RainbowParams> {
+    let mut params = RainbowParams::DEFAULT;
+
+    for arg in args.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+        let mut words = arg.split_whitespace();
+
+        let field = match words.next()? {
+            "saturation" => &mut params.saturation,
+            "lightness" => &mut params.lightness,
+            "period" => &mut params.period,
+            "phase" => &mut params.phase,
+            _ => return None,
+        };
+
+        *field = words.next()?.parse().ok()?;
+
+        if words.next().is_some() {
+            return None
+        }
+    }
+
+    Some(params).filter(|params| params.validate().is_ok())
+}
+
+impl Fill {
+    /// Parses the same textual forms as `FromStr`, with each color read according to `options`.
+    /// With `options.lenient`, keywords are also accepted in any case.
+    pub fn parse_with(s: &str, options: ParseOptions) -> core::result::Result<Fill, ParseColorError> {
+        parse(s, options, None, None, &mut Warnings::new())
+    }
+
+    /// Like `parse_with`, but also returns what lenient parsing had to repair.
+    ///
+    /// ```
+    /// use led_json::{Fill, ParseOptions, Warning};
+    ///
+    /// let (fill, warnings) = Fill::parse_with_warnings("Rainbow ", ParseOptions::lenient()).unwrap();
+    ///
+    /// assert_eq!(fill, Fill::rainbow());
+    /// assert_eq!(warnings.into_iter().collect::<Vec<_>>(), vec![Warning::TrimmedWhitespace, Warning::MixedCase]);
+    /// ```
+    pub fn parse_with_warnings(s: &str, options: ParseOptions) -> core::result::Result<(Fill, Warnings), ParseColorError> {
+        let mut warnings = Warnings::new();
+        let fill = parse(s, options, None, None, &mut warnings)?;
+
+        Ok((fill, warnings))
+    }
+}
+
+/// The textual forms, with color names looked up in `resolver` first and `extensions` tried last.
+pub(crate) fn parse(
+    s: &str,
+    options: ParseOptions,
+    resolver: Resolver<'_>,
+    extensions: Extensions<'_>,
+    warnings: &mut Warnings,
+) -> core::result::Result<Fill, ParseColorError> {
+    let invalid = || ParseColorError::InvalidGradient(s.into());
+
+    // what `Deserialize` accepts, so everything parsed here serializes and reads back
+    let checked = |fill: Fill| match fill.validate_gradient(&GradientRules::default()) {
+        Ok(()) => Ok(fill),
+        Err(_) => Err(invalid()),
+    };
+
+    let s = match s.trim_matches(|c: char| c.is_ascii_whitespace()) {
+        trimmed if options.lenient && trimmed.len() != s.len() => {
+            warnings.push(Warning::TrimmedWhitespace);
+            trimmed
+        },
+        _ => s,
+    };
+
+    if let Some(args) = arguments(s, "linear-gradient") {
+        return checked(Fill::Gradient(stops(args?.split(','), options, resolver, warnings)?))
+    }
+
+    if let Some(args) = arguments(s, "radial-gradient") {
+        let (center, list) = parameter(args?, "at ");
+
+        let center = match center {
+            Some(center) => {
+                let mut xy = center.split_whitespace().map(f32::from_str);
+
+                match (xy.next(), xy.next(), xy.next()) {
+                    (Some(Ok(x)), Some(Ok(y)), None) => (x, y),
+                    _ => return Err(invalid()),
+                }
+            },
+            None => (0.5, 0.5),
+        };
+
+        return checked(Fill::Radial { center, stops: stops(list, options, resolver, warnings)? })
+    }
+
+    if let Some(args) = arguments(s, "conic-gradient") {
+        let (angle, list) = parameter(args?, "from ");
+
+        let angle = match angle {
+            Some(angle) => angle.strip_suffix("deg").and_then(|a| a.trim().parse().ok()).ok_or_else(invalid)?,
+            None => 0.0,
+        };
+
+        return checked(Fill::Conic { angle, stops: stops(list, options, resolver, warnings)? })
+    }
+
+    if let Some(args) = arguments(s, "rainbow") {
+        return rainbow_params(args?).map(Fill::Rainbow).ok_or_else(invalid)
+    }
+
+    if s.contains(',') {
+        return checked(Fill::Gradient(stops(s.split(','), options, resolver, warnings)?))
+    }
+
+    keyword_or_color(s, options, resolver, extensions, warnings)
+}
+
+impl FromStr for Fill {
+    type Err = ParseColorError;
+
+    /// Parses everything `Display` writes: a keyword, a hex color, `linear-gradient(#fff, #000)`,
+    /// `radial-gradient(at 0.25 0.5, #fff, #000)` with the center as fractions of the surface,
+    /// or `conic-gradient(from 90deg, #fff, #000)`. `at` and `from` are optional. A bare
+    /// comma-separated list like `#fff,#000` is a linear gradient too. A rainbow with other than
+    /// the default `RainbowParams` is `rainbow(period 2, phase 0.5)`, with any of `saturation`,
+    /// `lightness`, `period` and `phase` in any order. Gradients have to pass the default
+    /// `GradientRules`, the same as when deserializing.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill};
+    ///
+    /// let fill: Fill = "#fff, #000".parse().unwrap();
+    ///
+    /// assert_eq!(fill.to_string(), "linear-gradient(#ffffff, #000000)");
+    /// assert_eq!(fill.to_string().parse::<Fill>().unwrap(), fill);
+    /// ```
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        Fill::parse_with(s, ParseOptions::default())
+    }
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Formatted::new(self, ColorFormat::LongHex).fmt(f)
+    }
+}
+
+// An array holds either gradient stops or the channels of a single color, like
+// `[255, 0, 255]`. Which one it is can only be told from the entries.
+enum Entry {
+    Channel(u64),
+    Color(Color),
+}
+
+// a seed rather than a `Deserialize` impl, so the entries are parsed with `parse` too
+struct EntrySeed<'a>(ParseStr<'a>);
+
+impl<'de> DeserializeSeed<'de> for EntrySeed<'_> {
+    type Value = Entry;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Entry, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct EntryVisitor<'a>(ParseStr<'a>);
+
+        impl<'de> Visitor<'de> for EntryVisitor<'_> {
+            type Value = Entry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a color or a channel value")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> core::result::Result<Entry, E>
+            where
+                E: de::Error,
+            {
+                Ok(Entry::Channel(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> core::result::Result<Entry, E>
+            where
+                E: de::Error,
+            {
+                Ok(Entry::Channel(color::unsigned(value)?))
+            }
+
+            fn visit_str<E>(self, value: &str) -> core::result::Result<Entry, E>
+            where
+                E: de::Error,
+            {
+                Ok(Entry::Color((self.0)(value).map_err(E::custom)?))
+            }
+
+            // like `Color`'s visitor, neither copies the string
+            fn visit_borrowed_str<E>(self, value: &'de str) -> core::result::Result<Entry, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(value)
+            }
+
+            fn visit_string<E>(self, value: String) -> core::result::Result<Entry, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(&value)
+            }
+
+            fn visit_seq<S>(self, seq: S) -> core::result::Result<Entry, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                Ok(Entry::Color(color::deserialize_with(de::value::SeqAccessDeserializer::new(seq), self.0)?))
+            }
+
+            fn visit_map<M>(self, map: M) -> core::result::Result<Entry, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                Ok(Entry::Color(color::deserialize_with(de::value::MapAccessDeserializer::new(map), self.0)?))
+            }
+        }
+
+        deserializer.deserialize_any(EntryVisitor(self.0))
+    }
+}
+
+/// What an array holds, told apart by its entries.
+enum Array {
+    Stops(Vec<Color>),
+    Color(Color),
+}
+
+/// Reads an array of gradient stops, with strings parsed by `parse`, or of the channels of a
+/// single color. An empty array is no stops.
+fn array<'de, S>(mut seq: S, parse: ParseStr<'_>) -> core::result::Result<Array, S::Error>
+where
+    S: SeqAccess<'de>,
+{
+    let mut colors: Vec<Color> = Vec::with_capacity(cautious(seq.size_hint()));
+    let mut channels: Vec<u64> = Vec::new();
+
+    // visiting any string rather than borrowing `&str` means formats that can only hand out
+    // owned strings (and JSON strings with escapes in them) work too
+    while let Some(entry) = seq.next_element_seed(EntrySeed(parse))? {
+        match entry {
+            Entry::Color(c) => colors.push(c),
+            Entry::Channel(c) => channels.push(c),
+        }
+    }
+
+    match (colors.is_empty(), channels.is_empty()) {
+        (_, true) => Ok(Array::Stops(colors)),
+        (true, false) => Ok(Array::Color(color::from_channels(&channels)?)),
+        (false, false) => Err(de::Error::custom("an array can't mix colors and channel values")),
+    }
+}
+
+impl<'de> Deserialize<'de> for Fill {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with(deserializer, &color::parse_hex, None, None)
+    }
+}
+
+/// The bare words a string is checked against before it's parsed as a color, in place of
+/// `"rainbow"`, `"none"` and `"transparent"`. `CustomFill` reads its own keywords as a `Fill::Raw`
+/// of the word.
+pub(crate) type Keywords<'a> = Option<&'a dyn Fn(&str) -> Option<Fill>>;
+
+/// `Fill::deserialize`, with color strings parsed by `parse`, bare words checked against
+/// `keywords` and other strings handed to `extensions`.
+pub(crate) fn deserialize_with<'de, D>(
+    deserializer: D,
+    parse: ParseStr<'_>,
+    keywords: Keywords<'_>,
+    extensions: Extensions<'_>,
+) -> core::result::Result<Fill, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return validated(binary::deserialize_fill(deserializer)?)
+    }
+
+    // This is a Visitor that hands color strings to `parse`, other strings to `extensions` and
+    // everything else to the structured forms.
+    struct StringOrVec<'a>(ParseStr<'a>, Keywords<'a>, Extensions<'a>);
+
+    #[derive(Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Linear,
+        Radial,
+        Conic,
+    }
+
+    // Every field an object can have, either a structured color like `{ "r": 255, ... }` or
+    // a tagged gradient like `{ "type": "radial", ... }`. Which one it is gets sorted out
+    // once it's been read.
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct MapForm {
+        #[serde(rename = "type")]
+        kind: Option<Kind>,
+        center: Option<(f32, f32)>,
+        angle: Option<f32>,
+        stops: Option<Vec<Stop>>,
+        rainbow: Option<RainbowParams>,
+        #[serde(alias = "red")]
+        r: Option<u8>,
+        #[serde(alias = "green")]
+        g: Option<u8>,
+        #[serde(alias = "blue")]
+        b: Option<u8>,
+    }
+
+    // A stop of a tagged gradient. The derived `MapForm` can't hand strings to `parse`, so they're
+    // kept until it's been read.
+    enum Stop {
+        Text(String),
+        Color(Color),
+    }
+
+    impl<'de> Deserialize<'de> for Stop {
+        fn deserialize<D>(deserializer: D) -> core::result::Result<Stop, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct StopVisitor;
+
+            impl<'de> Visitor<'de> for StopVisitor {
+                type Value = Stop;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a color")
+                }
+
+                fn visit_str<E>(self, value: &str) -> core::result::Result<Stop, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(Stop::Text(value.into()))
+                }
+
+                fn visit_string<E>(self, value: String) -> core::result::Result<Stop, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(Stop::Text(value))
+                }
+
+                fn visit_u64<E>(self, value: u64) -> core::result::Result<Stop, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(Stop::Color(color::from_packed(value)?))
+                }
+
+                fn visit_i64<E>(self, value: i64) -> core::result::Result<Stop, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(Stop::Color(color::from_packed(color::unsigned(value)?)?))
+                }
+
+                fn visit_seq<S>(self, seq: S) -> core::result::Result<Stop, S::Error>
+                where
+                    S: SeqAccess<'de>,
+                {
+                    Color::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Stop::Color)
+                }
+
+                fn visit_map<M>(self, map: M) -> core::result::Result<Stop, M::Error>
+                where
+                    M: MapAccess<'de>,
+                {
+                    Color::deserialize(de::value::MapAccessDeserializer::new(map)).map(Stop::Color)
+                }
+            }
+
+            deserializer.deserialize_any(StopVisitor)
+        }
+    }
+
+    impl<'de> Visitor<'de> for StringOrVec<'_> {
+        type Value = Fill;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("string, array, integer or color object")
+        }
+
+        fn visit_str<E>(self, value: &str) -> core::result::Result<Fill, E>
+        where
+            E: de::Error,
+        {
+            // gradients have their own structure here, so `FromStr`'s gradient syntax isn't
+            // accepted
+            let keyword = match self.1 {
+                Some(keywords) => keywords(value),
+                None => keyword(value, false),
+            };
+
+            if let Some(fill) = keyword {
+                return Ok(fill)
+            }
+
+            // anything else has to be a color, which `parse` may also know by name, or a form an
+            // extension adds
+            match (self.0)(value) {
+                Ok(color) => Ok(Fill::Color(color)),
+                Err(e) if !value.starts_with('#') => match self.2.and_then(|e| e.parse(value)) {
+                    Some(fill) => validated(fill.map_err(E::custom)?),
+                    None => Err(E::custom(unknown(value, e))),
+                },
+                Err(e) => Err(E::custom(e)),
+            }
+        }
+
+        fn visit_borrowed_str<E>(self, value: &'de str) -> core::result::Result<Fill, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(value)
+        }
+
+        fn visit_string<E>(self, value: String) -> core::result::Result<Fill, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(&value)
+        }
+
+        fn visit_seq<S>(self, seq: S) -> core::result::Result<Fill, S::Error>
+        where
+            S: SeqAccess<'de>,
+        {
+            match array(seq, self.0)? {
+                Array::Stops(colors) => validated(Fill::Gradient(colors)),
+                Array::Color(color) => Ok(Fill::Color(color)),
+            }
+        }
+
+        fn visit_u64<E>(self, value: u64) -> core::result::Result<Fill, E>
+        where
+            E: de::Error,
+        {
+            Ok(Fill::Color(color::from_packed(value)?))
+        }
+
+        fn visit_i64<E>(self, value: i64) -> core::result::Result<Fill, E>
+        where
+            E: de::Error,
+        {
+            Ok(Fill::Color(color::from_packed(color::unsigned(value)?)?))
+        }
+
+        fn visit_map<M>(self, map: M) -> core::result::Result<Fill, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            // `MapAccessDeserializer` is a wrapper that turns a `MapAccess`
+            // into a `Deserializer`, so the map can be handed to a derived
+            // `Deserialize` impl.
+            let form = MapForm::deserialize(de::value::MapAccessDeserializer::new(map))?;
+            let has_channels = form.r.is_some() || form.g.is_some() || form.b.is_some();
+
+            if let Some(params) = form.rainbow {
+                if has_channels || form.kind.is_some() || form.center.is_some() || form.angle.is_some() || form.stops.is_some() {
+                    return Err(de::Error::custom("a rainbow can't have other fields"))
+                }
+
+                return validated(Fill::Rainbow(params))
+            }
+
+            let kind = match form.kind {
+                Some(kind) => kind,
+                None if form.stops.is_some() => return Err(de::Error::missing_field("type")),
+                None => {
+                    let channel = |c: Option<u8>, name| c.ok_or_else(|| de::Error::missing_field(name));
+
+                    return Ok(Fill::Color(Color {
+                        red: channel(form.r, "r")?,
+                        green: channel(form.g, "g")?,
+                        blue: channel(form.b, "b")?,
+                    }))
+                },
+            };
+
+            if has_channels {
+                return Err(de::Error::custom("a gradient can't also have color channels"))
+            }
+
+            if form.center.is_some() && kind != Kind::Radial {
+                return Err(de::Error::custom("only radial gradients have a center"))
+            }
+
+            if form.angle.is_some() && kind != Kind::Conic {
+                return Err(de::Error::custom("only conic gradients have an angle"))
+            }
+
+            let stops = form.stops.ok_or_else(|| de::Error::missing_field("stops"))?;
+            let stops = stops
+                .into_iter()
+                .map(|stop| match stop {
+                    Stop::Text(s) => (self.0)(&s).map_err(de::Error::custom),
+                    Stop::Color(color) => Ok(color),
+                })
+                .collect::<core::result::Result<Vec<_>, _>>()?;
+
+            validated(match kind {
+                Kind::Linear => Fill::Gradient(stops),
+                Kind::Radial => Fill::Radial { center: form.center.unwrap_or((0.5, 0.5)), stops },
+                Kind::Conic => Fill::Conic { angle: form.angle.unwrap_or(0.0), stops },
+            })
+        }
+    }
+
+    deserializer.deserialize_any(StringOrVec(parse, keywords, extensions))
+}
+
+impl Serialize for Fill
+{
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Formatted::new(self, ColorFormat::LongHex).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[derive(Deserialize, Serialize, Debug)]
+    struct MyData {
+        pub color: Fill,
+    }
+
+    mod deserialize {
+        use super::*;
+
+        #[test]
+        fn rainbow() {
+            let data = r##"
+                { "color": "rainbow" }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::rainbow());
+        }
+
+        #[test]
+        fn rainbow_params() {
+            let data = r##"
+                { "color": { "rainbow": { "period": 4.0, "phase": 0.25 } } }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Rainbow(RainbowParams { period: 4.0, phase: 0.25, ..RainbowParams::DEFAULT }));
+
+            let v: MyData = serde_json::from_str(r#"{ "color": { "rainbow": {} } }"#).unwrap();
+
+            assert_eq!(v.color, Fill::rainbow());
+        }
+
+        #[test]
+        fn borrowed_owned_and_cow_strings() {
+            use alloc::borrow::Cow;
+            use de::value::{BorrowedStrDeserializer, CowStrDeserializer, Error, StringDeserializer};
+
+            assert_eq!(Fill::deserialize(BorrowedStrDeserializer::<Error>::new("none")), Ok(Fill::None));
+            assert_eq!(Fill::deserialize(StringDeserializer::<Error>::new("rainbow".into())), Ok(Fill::rainbow()));
+            assert_eq!(Fill::deserialize(CowStrDeserializer::<Error>::new(Cow::Owned("#fff".into()))), Ok(Fill::Color(Color { red: 255, green: 255, blue: 255 })));
+            assert_eq!(Fill::deserialize(CowStrDeserializer::<Error>::new(Cow::Borrowed("transparent"))), Ok(Fill::Transparent));
+
+            let v: MyData = serde_json::from_str(r##"{ "color": ["\u0023000", "#fff"] }"##).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 255, green: 255, blue: 255 }]));
+        }
+
+        #[test]
+        fn invalid_rainbow_params() {
+            let err = |data: &str| serde_json::from_str::<MyData>(data).unwrap_err().to_string();
+
+            assert!(err(r#"{ "color": { "rainbow": { "period": 0.0 } } }"#).contains("Rainbow period 0 isn't a non-zero number"));
+            assert!(err(r#"{ "color": { "rainbow": { "lightness": 2.0 } } }"#).contains("Rainbow lightness 2 is outside 0.0..=1.0"));
+            assert!(err(r#"{ "color": { "rainbow": { "speed": 2.0 } } }"#).contains("unknown field `speed`"));
+            assert!(err(r#"{ "color": { "rainbow": {}, "r": 255 } }"#).contains("a rainbow can't have other fields"));
+            assert!(err(r#"{ "color": { "rainbow": {}, "type": "linear", "stops": [] } }"#).contains("a rainbow can't have other fields"));
+        }
+
+        #[test]
+        fn none() {
+            let data = r##"
+                { "color": "none" }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::None);
+        }
+
+        #[test]
+        fn transparent() {
+            let data = r##"
+                { "color": "transparent" }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Transparent);
+        }
+
+        #[test]
+        fn short_color() {
+            let data = r##"
+                { "color": "#f0f" }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        }
+
+        #[test]
+        fn long_color() {
+            let data = r##"
+                { "color": "#ff00ff" }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        }
+
+        #[test]
+        fn gradient() {
+            let data = r##"
+                { "color": [ "#fff", "#00ff00", "#00f" ] }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![
+                Color { red: 255, green: 255, blue: 255 },
+                Color { red: 0, green: 255, blue: 0 },
+                Color { red: 0, green: 0, blue: 255 },
+            ]));
+        }
+
+        #[test]
+        fn structured_color() {
+            let data = r##"
+                { "color": { "r": 255, "g": 0, "b": 255 } }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        }
+
+        #[test]
+        fn mixed_gradient() {
+            let data = r##"
+                { "color": [ { "r": 255, "g": 0, "b": 0 }, "#00f" ] }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![
+                Color { red: 255, green: 0, blue: 0 },
+                Color { red: 0, green: 0, blue: 255 },
+            ]));
+        }
+
+        #[test]
+        fn packed_integer() {
+            let v: MyData = serde_json::from_str(r#"{ "color": 16711935 }"#).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        }
+
+        #[test]
+        fn channel_array() {
+            let v: MyData = serde_json::from_str(r#"{ "color": [255, 0, 255] }"#).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+
+            let v: MyData = serde_json::from_str(r#"{ "color": [255, 0, 255, 128] }"#).unwrap();
+
+            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+        }
+
+        #[test]
+        fn gradient_of_channel_arrays() {
+            let v: MyData = serde_json::from_str(r##"{ "color": [[255, 0, 0], "#00f"] }"##).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![
+                Color { red: 255, green: 0, blue: 0 },
+                Color { red: 0, green: 0, blue: 255 },
+            ]));
+        }
+
+        #[test]
+        fn invalid_integer_forms() {
+            let err = serde_json::from_str::<MyData>(r#"{ "color": [255, 300, 0] }"#).unwrap_err();
+
+            assert!(err.to_string().contains("expected a channel value from 0 to 255"), "{}", err);
+
+            let err = serde_json::from_str::<MyData>(r#"{ "color": [255, 0] }"#).unwrap_err();
+
+            assert!(err.to_string().contains("expected 3 or 4 channel values"), "{}", err);
+
+            let err = serde_json::from_str::<MyData>(r##"{ "color": [255, "#fff", 0] }"##).unwrap_err();
+
+            assert!(err.to_string().contains("can't mix colors and channel values"), "{}", err);
+
+            assert!(serde_json::from_str::<MyData>(r#"{ "color": 16777216 }"#).is_err());
+            assert!(serde_json::from_str::<MyData>(r#"{ "color": -5 }"#).is_err());
+        }
+
+        #[test]
+        fn radial() {
+            let v: MyData = serde_json::from_str(r##"
+                { "color": { "type": "radial", "center": [0.25, 0.5], "stops": ["#fff", [0, 0, 0]] } }
+            "##).unwrap();
+
+            assert_eq!(v.color, Fill::Radial {
+                center: (0.25, 0.5),
+                stops: vec![Color { red: 255, green: 255, blue: 255 }, Color { red: 0, green: 0, blue: 0 }],
+            });
+
+            let v: MyData = serde_json::from_str(r##"{ "color": { "type": "radial", "stops": ["#fff"] } }"##).unwrap();
+
+            assert_eq!(v.color, Fill::Radial { center: (0.5, 0.5), stops: vec![Color { red: 255, green: 255, blue: 255 }] });
+        }
+
+        #[test]
+        fn conic() {
+            let v: MyData = serde_json::from_str(r##"
+                { "color": { "type": "conic", "angle": 90, "stops": ["#f00"] } }
+            "##).unwrap();
+
+            assert_eq!(v.color, Fill::Conic { angle: 90.0, stops: vec![Color { red: 255, green: 0, blue: 0 }] });
+
+            let v: MyData = serde_json::from_str(r##"{ "color": { "type": "conic", "stops": ["#fff"] } }"##).unwrap();
+
+            assert_eq!(v.color, Fill::Conic { angle: 0.0, stops: vec![Color { red: 255, green: 255, blue: 255 }] });
+        }
+
+        #[test]
+        fn tagged_linear() {
+            let v: MyData = serde_json::from_str(r##"{ "color": { "type": "linear", "stops": ["#f00"] } }"##).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![Color { red: 255, green: 0, blue: 0 }]));
+        }
+
+        #[test]
+        fn invalid_tagged_forms() {
+            let err = |data| serde_json::from_str::<MyData>(data).unwrap_err().to_string();
+
+            assert!(err(r#"{ "color": { "type": "conic", "center": [0, 0], "stops": [] } }"#).contains("only radial gradients have a center"));
+            assert!(err(r#"{ "color": { "type": "radial", "angle": 10, "stops": [] } }"#).contains("only conic gradients have an angle"));
+            assert!(err(r#"{ "color": { "type": "radial", "r": 0, "stops": [] } }"#).contains("can't also have color channels"));
+            assert!(err(r#"{ "color": { "type": "radial" } }"#).contains("missing field `stops`"));
+            assert!(err(r#"{ "color": { "stops": [] } }"#).contains("missing field `type`"));
+            assert!(err(r#"{ "color": { "type": "spiral", "stops": [] } }"#).contains("unknown variant `spiral`"));
+        }
+
+        #[test]
+        fn invalid_gradients() {
+            let err = |data| serde_json::from_str::<MyData>(data).unwrap_err().to_string();
+
+            assert!(err(r#"{ "color": [] }"#).contains("A gradient needs at least one stop"));
+            assert!(err(r#"{ "color": { "type": "conic", "stops": [] } }"#).contains("A gradient needs at least one stop"));
+            assert!(err(r##"{ "color": { "type": "radial", "center": [1.5, 0], "stops": ["#fff"] } }"##).contains("center (1.5, 0) is outside 0.0..=1.0"));
+
+            // the default rules allow these
+            assert!(serde_json::from_str::<MyData>(r##"{ "color": ["#fff"] }"##).is_ok());
+            assert!(serde_json::from_str::<MyData>(r##"{ "color": ["#fff", "#fff"] }"##).is_ok());
+        }
+
+        #[test]
+        #[should_panic]
+        fn out_of_range_structured_color_in_gradient_fails() {
+            let data = r##"
+                { "color": [ { "r": 300, "g": 0, "b": 0 } ] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        fn gradient_with_escaped_strings() {
+            let data = r##"
+                { "color": [ "\u0023fff", "\u0023000" ] }
+            "##;
+
+            let v: MyData = serde_json::from_str(data).unwrap();
+
+            assert_eq!(v.color, Fill::Gradient(vec![
+                Color { red: 255, green: 255, blue: 255 },
+                Color { red: 0, green: 0, blue: 0 },
+            ]));
+        }
+
+        #[test]
+        #[should_panic]
+        fn arbitrary_string_fails() {
+            let data = r##"
+                { "color": "hello" }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn short_string_fails() {
+            let data = r##"
+                { "color": "#f" }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn too_long_of_string_fails() {
+            let data = r##"
+                { "color": "#fffffffffffffff" }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn rainbow_in_gradient_fails() {
+            let data = r##"
+                { "color": ["rainbow"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn none_in_gradient_fails() {
+            let data = r##"
+                { "color": ["#fff", "none"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn transparent_in_gradient_fails() {
+            let data = r##"
+                { "color": ["transparent"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn arbitrary_string_in_gradient_fails() {
+            let data = r##"
+                { "color": ["hello"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn short_string_in_gradient_fails() {
+            let data = r##"
+                { "color": ["#f"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn long_string_in_gradient_fails() {
+            let data = r##"
+                { "color": ["#fffffffffffffff"] }
+            "##;
+
+            serde_json::from_str::<MyData>(data).unwrap();
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn keywords() {
+            assert_eq!(Fill::from_str("rainbow"), Ok(Fill::rainbow()));
+            assert_eq!(Fill::from_str("none"), Ok(Fill::None));
+            assert_eq!(Fill::from_str("transparent"), Ok(Fill::Transparent));
+        }
+
+        #[test]
+        fn color() {
+            assert_eq!(Fill::from_str("#f0f"), Ok(Fill::Color(Color { red: 255, green: 0, blue: 255 })));
+        }
+
+        #[test]
+        fn rainbow_params() {
+            let params = |period, phase| Ok(Fill::Rainbow(RainbowParams { period, phase, ..RainbowParams::DEFAULT }));
+
+            assert_eq!(Fill::from_str("rainbow(period 2, phase 0.5)"), params(2.0, 0.5));
+            assert_eq!(Fill::from_str("rainbow( phase 0.5 )"), params(1.0, 0.5));
+            assert_eq!(Fill::from_str("rainbow()"), Ok(Fill::rainbow()));
+            assert_eq!(
+                Fill::from_str("rainbow(lightness 0.25, saturation 0.5)"),
+                Ok(Fill::Rainbow(RainbowParams { saturation: 0.5, lightness: 0.25, ..RainbowParams::DEFAULT }))
+            );
+        }
+
+        #[test]
+        fn invalid_rainbow_params() {
+            let invalid = |s: &str| Err(ParseColorError::InvalidGradient(s.into()));
+
+            for s in ["rainbow(period 0)", "rainbow(speed 2)", "rainbow(period)", "rainbow(period 2 3)", "rainbow(period x)", "rainbow(phase 1"] {
+                assert_eq!(Fill::from_str(s), invalid(s));
+            }
+        }
+
+        #[test]
+        fn unknown_keyword() {
+            assert_eq!(Fill::from_str("hello"), Err(ParseColorError::UnknownKeyword("hello".into())));
+            assert_eq!(Fill::from_str("Rainbow"), Err(ParseColorError::UnknownKeyword("Rainbow".into())));
+            assert_eq!(Fill::from_str(""), Err(ParseColorError::UnknownKeyword("".into())));
+        }
+
+        #[test]
+        fn gradients() {
+            let (white, black) = (Color { red: 255, green: 255, blue: 255 }, Color { red: 0, green: 0, blue: 0 });
+
+            assert_eq!(Fill::from_str("linear-gradient(#fff, #000)"), Ok(Fill::Gradient(vec![white, black])));
+            assert_eq!(Fill::from_str("#fff,#000"), Ok(Fill::Gradient(vec![white, black])));
+            assert_eq!(Fill::from_str("#fff ,"), Ok(Fill::Gradient(vec![white])));
+            assert_eq!(Fill::from_str("radial-gradient(at 0.25 0.75, #fff)"), Ok(Fill::Radial { center: (0.25, 0.75), stops: vec![white] }));
+            assert_eq!(Fill::from_str("radial-gradient(#fff, #000)"), Ok(Fill::Radial { center: (0.5, 0.5), stops: vec![white, black] }));
+            assert_eq!(Fill::from_str("conic-gradient(from 90deg, #000)"), Ok(Fill::Conic { angle: 90.0, stops: vec![black] }));
+            assert_eq!(Fill::from_str("conic-gradient( #000 )"), Ok(Fill::Conic { angle: 0.0, stops: vec![black] }));
+        }
+
+        #[test]
+        fn invalid_gradients() {
+            let invalid = |s: &str| Err(ParseColorError::InvalidGradient(s.into()));
+
+            assert_eq!(Fill::from_str("linear-gradient(#fff"), invalid("linear-gradient(#fff"));
+            assert_eq!(Fill::from_str("radial-gradient(at 0.5, #fff)"), invalid("radial-gradient(at 0.5, #fff)"));
+            assert_eq!(Fill::from_str("radial-gradient(at x y)"), invalid("radial-gradient(at x y)"));
+            assert_eq!(Fill::from_str("conic-gradient(from 90, #fff)"), invalid("conic-gradient(from 90, #fff)"));
+            assert_eq!(Fill::from_str("linear-gradient(#fff, rainbow)"), Err(ParseColorError::MissingPrefix));
+            assert_eq!(Fill::from_str("#fff, #ff"), Err(ParseColorError::InvalidLength(3)));
+        }
+
+        #[test]
+        fn gradients_that_break_the_rules() {
+            let invalid = |s: &str| Err(ParseColorError::InvalidGradient(s.into()));
+
+            for s in [
+                "linear-gradient()",
+                "radial-gradient()",
+                "radial-gradient(at 5 5, #fff)",
+                "radial-gradient(at NaN 0.5, #fff)",
+                "conic-gradient(from NaNdeg, #fff, #000)",
+                "conic-gradient(from infdeg, #fff)",
+            ] {
+                assert_eq!(Fill::from_str(s), invalid(s));
+            }
+        }
+
+        #[test]
+        fn display_round_trips() {
+            let fills = vec![
+                Fill::rainbow(),
+                Fill::Rainbow(RainbowParams { saturation: 0.75, lightness: 0.25, period: -3.5, phase: 0.125 }),
+                Fill::None,
+                Fill::Transparent,
+                Fill::Color(Color { red: 255, green: 0, blue: 170 }),
+                Fill::Gradient(vec![Color { red: 1, green: 2, blue: 3 }]),
+                Fill::Gradient(vec![Color { red: 1, green: 2, blue: 3 }, Color { red: 4, green: 5, blue: 6 }]),
+                Fill::Radial { center: (0.1, 0.9), stops: vec![Color { red: 4, green: 5, blue: 6 }] },
+                Fill::Radial { center: (0.5, 0.5), stops: vec![Color { red: 1, green: 2, blue: 3 }] },
+                Fill::Conic { angle: -45.5, stops: vec![Color { red: 1, green: 2, blue: 3 }, Color { red: 4, green: 5, blue: 6 }] },
+            ];
+
+            for fill in fills {
+                assert_eq!(Fill::from_str(&fill.to_string()), Ok(fill));
+            }
+        }
+
+        #[test]
+        fn display() {
+            let stops = vec![Color { red: 255, green: 255, blue: 255 }, Color { red: 0, green: 0, blue: 0 }];
+
+            assert_eq!(Fill::rainbow().to_string(), "rainbow");
+            assert_eq!(
+                Fill::Rainbow(RainbowParams { period: 2.0, ..RainbowParams::DEFAULT }).to_string(),
+                "rainbow(period 2, phase 0, saturation 1, lightness 0.5)"
+            );
+            assert_eq!(Fill::Gradient(stops.clone()).to_string(), "linear-gradient(#ffffff, #000000)");
+            assert_eq!(Fill::Radial { center: (0.25, 0.5), stops: stops.clone() }.to_string(), "radial-gradient(at 0.25 0.5, #ffffff, #000000)");
+            assert_eq!(Fill::Conic { angle: 90.0, stops: vec![] }.to_string(), "conic-gradient(from 90deg)");
+            assert_eq!(
+                Formatted::new(&Fill::Gradient(stops), ColorFormat::ShortHexIfPossible).to_string(),
+                "linear-gradient(#fff, #000)",
+            );
+        }
+
+        #[test]
+        fn deserialize_stays_strict() {
+            assert!(serde_json::from_str::<Fill>(r##""linear-gradient(#fff, #000)""##).is_err());
+            assert!(serde_json::from_str::<Fill>(r##""#fff,#000""##).is_err());
+        }
+
+        #[test]
+        fn lenient() {
+            let lenient = |s: &str| Fill::parse_with_warnings(s, ParseOptions::lenient()).map(|(f, w)| (f, w.into_iter().collect::<Vec<_>>()));
+
+            assert_eq!(lenient("NONE"), Ok((Fill::None, vec![Warning::MixedCase])));
+            assert_eq!(lenient(" ff00aa "), Ok((Fill::Color(Color { red: 255, green: 0, blue: 170 }), vec![Warning::TrimmedWhitespace, Warning::MissingHash])));
+            assert_eq!(lenient("fff, #0f0f0"), Ok((
+                Fill::Gradient(vec![Color { red: 255, green: 255, blue: 255 }, Color { red: 0, green: 240, blue: 240 }]),
+                vec![Warning::MissingHash, Warning::PaddedLeadingZero],
+            )));
+            assert_eq!(lenient("hello"), Err(ParseColorError::UnknownKeyword("hello".into())));
+            assert_eq!(lenient("#ff"), Err(ParseColorError::InvalidLength(3)));
+        }
+
+        #[test]
+        fn parse_with_options() {
+            let options = ParseOptions { require_hash: false, ..ParseOptions::default() };
+
+            assert_eq!(Fill::parse_with("ff00aa", options), Ok(Fill::Color(Color { red: 255, green: 0, blue: 170 })));
+            assert_eq!(Fill::parse_with("Rainbow", options), Err(ParseColorError::UnknownKeyword("Rainbow".into())));
+            // a hex color without its `#` says so, rather than being an unknown keyword
+            assert_eq!(Fill::parse_with("ff00aa", ParseOptions::default()), Err(ParseColorError::MissingPrefix));
+            assert_eq!(Fill::parse_with("f0a", ParseOptions::default()), Err(ParseColorError::MissingPrefix));
+            assert_eq!(Fill::parse_with("ff00a", ParseOptions::default()), Err(ParseColorError::UnknownKeyword("ff00a".into())));
+            assert_eq!(Fill::parse_with("ff00ag", ParseOptions::default()), Err(ParseColorError::UnknownKeyword("ff00ag".into())));
+
+            let err = serde_json::from_str::<Fill>(r#""ff00aa""#).unwrap_err();
+
+            assert!(err.to_string().contains(&ParseColorError::MissingPrefix.to_string()), "{}", err);
+
+            let ansi = ParseOptions { allow_ansi: true, ..ParseOptions::default() };
+
+            assert_eq!(Fill::parse_with("none", ansi), Ok(Fill::None));
+            assert_eq!(Fill::parse_with("red, ansi(21)", ansi), Ok(Fill::Gradient(vec![
+                Color { red: 205, green: 0, blue: 0 },
+                Color { red: 0, green: 0, blue: 255 },
+            ])));
+        }
+
+        #[test]
+        fn color_errors_pass_through() {
+            assert_eq!(Fill::from_str("#f"), Err(ParseColorError::InvalidLength(2)));
+            assert_eq!(Fill::from_str("#ffg"), Err(ParseColorError::InvalidHexDigit { position: 3 }));
+            assert_eq!(Fill::from_str("#é"), Err(ParseColorError::NonAsciiInput));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod sample {
+        use super::*;
+
+        fn gradient() -> Fill {
+            Fill::Gradient(vec![
+                Color { red: 255, green: 0, blue: 0 },
+                Color { red: 0, green: 255, blue: 0 },
+                Color { red: 0, green: 0, blue: 255 },
+            ])
+        }
+
+        #[test]
+        fn rainbow() {
+            assert_eq!(Fill::rainbow().sample(0.0), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(Fill::rainbow().sample(1.0 / 3.0), Color { red: 0, green: 255, blue: 0 });
+            assert_eq!(Fill::rainbow().sample(0.5), Color { red: 0, green: 255, blue: 255 });
+            assert_eq!(Fill::rainbow().sample(1.0), Color { red: 255, green: 0, blue: 0 });
+        }
+
+        #[test]
+        fn rainbow_params() {
+            let twice = Fill::Rainbow(RainbowParams { period: 0.5, ..RainbowParams::DEFAULT });
+
+            assert_eq!(twice.sample(0.25), Color { red: 0, green: 255, blue: 255 });
+            assert_eq!(twice.sample(0.75), Color { red: 0, green: 255, blue: 255 });
+            // clamped, unlike `RainbowParams::sample`
+            assert_eq!(twice.sample(1.25), twice.sample(1.0));
+        }
+
+        #[test]
+        fn none_and_transparent_are_off() {
+            assert_eq!(Fill::None.sample(0.5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(Fill::Transparent.sample(0.5), Color { red: 0, green: 0, blue: 0 });
+        }
+
+        #[test]
+        fn color() {
+            let fill = Fill::Color(Color { red: 1, green: 2, blue: 3 });
+
+            assert_eq!(fill.sample(0.0), Color { red: 1, green: 2, blue: 3 });
+            assert_eq!(fill.sample(0.7), Color { red: 1, green: 2, blue: 3 });
+        }
+
+        #[test]
+        fn gradient_stops() {
+            assert_eq!(gradient().sample(0.0), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(gradient().sample(0.5), Color { red: 0, green: 255, blue: 0 });
+            assert_eq!(gradient().sample(1.0), Color { red: 0, green: 0, blue: 255 });
+        }
+
+        #[test]
+        fn gradient_between_stops() {
+            assert_eq!(gradient().sample(0.25), Color { red: 128, green: 128, blue: 0 });
+            assert_eq!(gradient().sample(0.75), Color { red: 0, green: 128, blue: 128 });
+        }
+
+        #[test]
+        fn gradient_clamps_t() {
+            assert_eq!(gradient().sample(-1.0), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(gradient().sample(5.0), Color { red: 0, green: 0, blue: 255 });
+        }
+
+        #[test]
+        fn degenerate_gradients() {
+            assert_eq!(Fill::Gradient(vec![]).sample(0.5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(Fill::Gradient(vec![Color { red: 9, green: 9, blue: 9 }]).sample(0.5), Color { red: 9, green: 9, blue: 9 });
+        }
+
+        #[test]
+        fn xy_runs_left_to_right() {
+            let fill = gradient();
+
+            assert_eq!(fill.sample_xy(0, 0, 5, 3), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(2, 0, 5, 3), Color { red: 0, green: 255, blue: 0 });
+            assert_eq!(fill.sample_xy(4, 0, 5, 3), Color { red: 0, green: 0, blue: 255 });
+            assert_eq!(fill.sample_xy(1, 0, 5, 3), fill.sample(0.25));
+        }
+
+        #[test]
+        fn xy_ignores_rows() {
+            let fill = gradient();
+
+            for y in 0..3 {
+                assert_eq!(fill.sample_xy(3, y, 5, 3), fill.sample(0.75));
+            }
+        }
+
+        #[test]
+        fn xy_degenerate_surfaces() {
+            let fill = gradient();
+
+            assert_eq!(fill.sample_xy(0, 0, 0, 0), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(7, 0, 1, 1), Color { red: 255, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(99, 0, 5, 1), Color { red: 0, green: 0, blue: 255 });
+        }
+
+        #[test]
+        fn radial_spreads_from_the_center() {
+            let fill = Fill::Radial { center: (0.5, 0.5), stops: vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 200, green: 200, blue: 200 }] };
+
+            assert_eq!(fill.sample_xy(2, 2, 5, 5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(0, 0, 5, 5), Color { red: 200, green: 200, blue: 200 });
+            assert_eq!(fill.sample_xy(4, 4, 5, 5), Color { red: 200, green: 200, blue: 200 });
+            assert_eq!(fill.sample_xy(0, 2, 5, 5), fill.sample(0.5_f32.sqrt()));
+        }
+
+        #[test]
+        fn radial_reaches_the_farthest_corner() {
+            let fill = Fill::Radial { center: (0.0, 0.0), stops: vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 200, green: 200, blue: 200 }] };
+
+            assert_eq!(fill.sample_xy(0, 0, 5, 5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(4, 4, 5, 5), Color { red: 200, green: 200, blue: 200 });
+        }
+
+        #[test]
+        fn conic_sweeps_clockwise() {
+            let fill = Fill::Conic { angle: 0.0, stops: vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 240, green: 240, blue: 240 }] };
+
+            // straight up, right, down and left of the middle
+            assert_eq!(fill.sample_xy(2, 0, 5, 5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(4, 2, 5, 5), Color { red: 60, green: 60, blue: 60 });
+            assert_eq!(fill.sample_xy(2, 4, 5, 5), Color { red: 120, green: 120, blue: 120 });
+            assert_eq!(fill.sample_xy(0, 2, 5, 5), Color { red: 180, green: 180, blue: 180 });
+        }
+
+        #[test]
+        fn conic_angle_rotates_the_start() {
+            let fill = Fill::Conic { angle: 90.0, stops: vec![Color { red: 0, green: 0, blue: 0 }, Color { red: 240, green: 240, blue: 240 }] };
+
+            assert_eq!(fill.sample_xy(4, 2, 5, 5), Color { red: 0, green: 0, blue: 0 });
+            assert_eq!(fill.sample_xy(2, 0, 5, 5), Color { red: 180, green: 180, blue: 180 });
+        }
+    }
+
+    mod validate_gradient {
+        use super::*;
+
+        const RED: Color = Color { red: 255, green: 0, blue: 0 };
+        const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+        #[test]
+        fn default_rules() {
+            let rules = GradientRules::default();
+
+            assert_eq!(Fill::Gradient(vec![]).validate_gradient(&rules), Err(GradientError::Empty));
+            assert_eq!(Fill::Gradient(vec![RED]).validate_gradient(&rules), Ok(()));
+            assert_eq!(Fill::Gradient(vec![RED, RED, BLUE]).validate_gradient(&rules), Ok(()));
+            assert_eq!(Fill::rainbow().validate_gradient(&rules), Ok(()));
+            assert_eq!(Fill::Color(RED).validate_gradient(&rules), Ok(()));
+        }
+
+        #[test]
+        fn strict_rules() {
+            let rules = GradientRules::strict();
+
+            assert_eq!(Fill::Gradient(vec![RED]).validate_gradient(&rules), Err(GradientError::SingleStop));
+            assert_eq!(Fill::Gradient(vec![RED, BLUE, BLUE]).validate_gradient(&rules), Err(GradientError::RepeatedStop { index: 2 }));
+            assert_eq!(Fill::Gradient(vec![RED, BLUE, RED]).validate_gradient(&rules), Ok(()));
+        }
+
+        #[test]
+        fn center_and_angle() {
+            let rules = GradientRules::default();
+
+            assert_eq!(Fill::Radial { center: (0.0, 1.0), stops: vec![RED] }.validate_gradient(&rules), Ok(()));
+            assert_eq!(
+                Fill::Radial { center: (0.5, -0.1), stops: vec![RED] }.validate_gradient(&rules),
+                Err(GradientError::CenterOutOfRange { x: 0.5, y: -0.1 })
+            );
+            assert!(matches!(
+                Fill::Radial { center: (f32::NAN, 0.5), stops: vec![RED] }.validate_gradient(&rules),
+                Err(GradientError::CenterOutOfRange { .. })
+            ));
+            assert_eq!(
+                Fill::Conic { angle: f32::INFINITY, stops: vec![RED] }.validate_gradient(&rules),
+                Err(GradientError::InvalidAngle(f32::INFINITY))
+            );
+        }
+
+        #[test]
+        fn every_violation() {
+            let strict = GradientRules::strict();
+
+            assert_eq!(Fill::Gradient(vec![RED, BLUE]).gradient_violations(&strict), []);
+            assert_eq!(Fill::Color(RED).gradient_violations(&strict), []);
+            assert_eq!(
+                Fill::Conic { angle: f32::NEG_INFINITY, stops: vec![] }.gradient_violations(&strict),
+                [GradientError::InvalidAngle(f32::NEG_INFINITY), GradientError::Empty]
+            );
+            assert_eq!(
+                Fill::Radial { center: (-1.0, 2.0), stops: vec![RED] }.gradient_violations(&strict),
+                [GradientError::CenterOutOfRange { x: -1.0, y: 2.0 }, GradientError::SingleStop]
+            );
+            assert_eq!(
+                Fill::Gradient(vec![RED, RED, BLUE, BLUE, BLUE]).gradient_violations(&strict),
+                [GradientError::RepeatedStop { index: 1 }, GradientError::RepeatedStop { index: 3 }, GradientError::RepeatedStop { index: 4 }]
+            );
+
+            // the default rules only care about the center, angle and having a stop
+            assert_eq!(Fill::Gradient(vec![RED, RED]).gradient_violations(&GradientRules::default()), []);
+        }
+    }
+
+    mod default {
+        use super::*;
+
+        #[test]
+        fn color_is_black() {
+            assert_eq!(Color::default(), Color { red: 0, green: 0, blue: 0 });
+        }
+
+        // the global default is shared by every test, so everything that changes it is in here
+        #[cfg(feature = "std")]
+        #[test]
+        fn global_default() {
+            #[derive(Deserialize)]
+            struct Optional {
+                #[serde(default)]
+                color: Fill,
+            }
+
+            let white = Fill::Color(Color { red: 255, green: 255, blue: 255 });
+
+            assert_eq!(Fill::default(), Fill::None);
+
+            Fill::set_global_default(white.clone());
+
+            assert_eq!(Fill::default(), white);
+            assert_eq!(std::thread::spawn(Fill::default).join().unwrap(), white);
+            assert_eq!(serde_json::from_str::<Optional>("{}").unwrap().color, white);
+            assert_eq!(serde_json::from_str::<Optional>(r#"{ "color": "rainbow" }"#).unwrap().color, Fill::rainbow());
+
+            Fill::clear_global_default();
+
+            assert_eq!(Fill::default(), Fill::None);
+        }
+    }
+
+    mod serialize {
+        use super::*;
+
+        #[test]
+        fn rainbow() {
+            let json = json!(MyData { color: Fill::rainbow() });
+
+            assert_eq!(json.to_string(), r##"{"color":"rainbow"}"##)
+        }
+
+        #[test]
+        fn rainbow_params() {
+            let fill = Fill::Rainbow(RainbowParams { period: 4.0, ..RainbowParams::DEFAULT });
+            let json = serde_json::to_string(&fill).unwrap();
+
+            assert_eq!(json, r#"{"rainbow":{"saturation":1.0,"lightness":0.5,"period":4.0,"phase":0.0}}"#);
+            assert_eq!(serde_json::from_str::<Fill>(&json).unwrap(), fill);
+        }
+
+        #[test]
+        fn none_and_transparent() {
+            let json = json!(MyData { color: Fill::None });
+
+            assert_eq!(json.to_string(), r##"{"color":"none"}"##);
+
+            let json = json!(MyData { color: Fill::Transparent });
+
+            assert_eq!(json.to_string(), r##"{"color":"transparent"}"##)
+        }
+
+        #[test]
+        fn color() {
+            let json = json!(MyData { color: Fill::Color(Color { red: 255, green: 255, blue: 255 })});
+
+            assert_eq!(json.to_string(), r##"{"color":"#ffffff"}"##);
+
+            let json = json!(MyData { color: Fill::Color(Color { red: 15, green: 0, blue: 255 })});
+
+            assert_eq!(json.to_string(), r##"{"color":"#0f00ff"}"##)
+        }
+
+        #[test]
+        fn gradient() {
+            let json = json!(MyData { color: Fill::Gradient(vec![
+                Color { red: 255, green: 255, blue: 255 },
+                Color { red: 15, green: 0, blue: 255 },
+            ])});
+
+            assert_eq!(json.to_string(), r##"{"color":["#ffffff","#0f00ff"]}"##)
+        }
+
+        #[test]
+        fn radial_and_conic() {
+            // `to_string` rather than `json!`, which would sort the keys
+            let json = serde_json::to_string(&MyData { color: Fill::Radial { center: (0.25, 0.5), stops: vec![Color { red: 255, green: 0, blue: 0 }] }}).unwrap();
+
+            assert_eq!(json, r##"{"color":{"type":"radial","center":[0.25,0.5],"stops":["#ff0000"]}}"##);
+
+            let json = serde_json::to_string(&MyData { color: Fill::Conic { angle: 90.0, stops: vec![] }}).unwrap();
+
+            assert_eq!(json, r##"{"color":{"type":"conic","angle":90.0,"stops":[]}}"##)
+        }
+
+        #[test]
+        fn round_trip() {
+            let fills = vec![
+                Fill::Gradient(vec![Color { red: 1, green: 2, blue: 3 }]),
+                Fill::Radial { center: (0.1, 0.9), stops: vec![Color { red: 1, green: 2, blue: 3 }] },
+                Fill::Conic { angle: 45.5, stops: vec![Color { red: 4, green: 5, blue: 6 }, Color { red: 7, green: 8, blue: 9 }] },
+            ];
+
+            for fill in fills {
+                let json = serde_json::to_string(&fill).unwrap();
+
+                assert_eq!(serde_json::from_str::<Fill>(&json).unwrap(), fill);
+            }
+        }
+    }
+}
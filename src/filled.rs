@@ -0,0 +1,313 @@
+//! Fill parsing for an application's own types: anything that converts to and from a `Fill`
+//! reads and writes every form a `Fill` does. Pick one of
+//!
+//!  * `Filled<T>` as the field's type,
+//!  * `#[serde(with = "led_json::filled")]` on a field of type `T`,
+//!  * `fill_serde!(T);` to implement `Serialize` and `Deserialize` for `T` itself.
+//!
+//! `T` converts from a `Fill` with `TryFrom`, whose error is reported as the deserialization
+//! error, and back with `Into`.
+//!
+//! ```
+//! use std::convert::TryFrom;
+//!
+//! use led_json::{Color, Fill, Filled};
+//! use serde::{Deserialize, Serialize};
+//!
+//! /// What a single LED can show.
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Led {
+//!     Off,
+//!     On(Color),
+//! }
+//!
+//! impl TryFrom<Fill> for Led {
+//!     type Error = &'static str;
+//!
+//!     fn try_from(fill: Fill) -> Result<Self, Self::Error> {
+//!         match fill {
+//!             Fill::None => Ok(Led::Off),
+//!             Fill::Color(color) => Ok(Led::On(color)),
+//!             _ => Err("a single LED can only be off or one color"),
+//!         }
+//!     }
+//! }
+//!
+//! impl From<Led> for Fill {
+//!     fn from(led: Led) -> Self {
+//!         match led {
+//!             Led::Off => Fill::None,
+//!             Led::On(color) => Fill::Color(color),
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! struct Status {
+//!     power: Filled<Led>,
+//!     #[serde(with = "led_json::filled")]
+//!     network: Led,
+//! }
+//!
+//! let status: Status = serde_json::from_str(r##"{ "power": "#0f0", "network": "none" }"##).unwrap();
+//!
+//! assert_eq!(*status.power, Led::On(Color::new(0, 255, 0)));
+//! assert_eq!(status.network, Led::Off);
+//! assert_eq!(serde_json::to_string(&status).unwrap(), r##"{"power":"#00ff00","network":"none"}"##);
+//!
+//! let err = serde_json::from_str::<Status>(r##"{ "power": "rainbow", "network": "none" }"##).unwrap_err();
+//!
+//! assert!(err.to_string().starts_with("a single LED can only be off or one color"));
+//! ```
+
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::fill::Fill;
+
+/// A `T` that (de)serializes as the `Fill` it converts to and from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Filled<T>(pub T);
+
+impl<T> Filled<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Filled<T> {
+    fn from(value: T) -> Self {
+        Filled(value)
+    }
+}
+
+impl<T> Deref for Filled<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Filled<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Serialize for Filled<T>
+where
+    T: Clone + Into<Fill>,
+{
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Filled<T>
+where
+    T: TryFrom<Fill>,
+    T::Error: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(Filled)
+    }
+}
+
+/// Serializes `value` as the `Fill` it converts to, for `#[serde(with = "led_json::filled")]`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    T: Clone + Into<Fill>,
+    S: Serializer,
+{
+    value.clone().into().serialize(serializer)
+}
+
+/// Deserializes a `Fill` and converts it to a `T`, for `#[serde(with = "led_json::filled")]`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> core::result::Result<T, D::Error>
+where
+    T: TryFrom<Fill>,
+    T::Error: fmt::Display,
+    D: Deserializer<'de>,
+{
+    let fill = Fill::deserialize(deserializer)?;
+
+    T::try_from(fill).map_err(de::Error::custom)
+}
+
+/// Implements `Serialize` and `Deserialize` for a type that converts to and from a `Fill`, so
+/// every field of that type reads the forms a `Fill` does. See the `filled` module.
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// use led_json::{fill_serde, Color, Fill};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Solid(Color);
+///
+/// impl TryFrom<Fill> for Solid {
+///     type Error = String;
+///
+///     fn try_from(fill: Fill) -> Result<Self, String> {
+///         match fill {
+///             Fill::Color(color) => Ok(Solid(color)),
+///             fill => Err(format!("{} isn't a solid color", fill)),
+///         }
+///     }
+/// }
+///
+/// impl From<Solid> for Fill {
+///     fn from(solid: Solid) -> Self {
+///         Fill::Color(solid.0)
+///     }
+/// }
+///
+/// fill_serde!(Solid);
+///
+/// let solids: Vec<Solid> = serde_json::from_str(r##"["#f00", { "r": 0, "g": 0, "b": 255 }]"##).unwrap();
+///
+/// assert_eq!(solids, [Solid(Color::new(255, 0, 0)), Solid(Color::new(0, 0, 255))]);
+/// assert!(serde_json::from_str::<Solid>(r#""rainbow""#).is_err());
+/// ```
+#[macro_export]
+macro_rules! fill_serde {
+    ($ty:ty) => {
+        impl $crate::__private::serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::filled::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::filled::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::color::Color;
+
+    /// The stops of a gradient, and nothing else.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Stops(Vec<Color>);
+
+    impl TryFrom<Fill> for Stops {
+        type Error = &'static str;
+
+        fn try_from(fill: Fill) -> core::result::Result<Self, Self::Error> {
+            match fill {
+                Fill::Gradient(stops) => Ok(Stops(stops)),
+                _ => Err("not a linear gradient"),
+            }
+        }
+    }
+
+    impl From<Stops> for Fill {
+        fn from(stops: Stops) -> Self {
+            Fill::Gradient(stops.0)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Macro(Stops);
+
+    impl TryFrom<Fill> for Macro {
+        type Error = &'static str;
+
+        fn try_from(fill: Fill) -> core::result::Result<Self, Self::Error> {
+            Stops::try_from(fill).map(Macro)
+        }
+    }
+
+    impl From<Macro> for Fill {
+        fn from(value: Macro) -> Self {
+            value.0.into()
+        }
+    }
+
+    fill_serde!(Macro);
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Config {
+        wrapped: Filled<Stops>,
+
+        #[serde(with = "crate::filled")]
+        with: Stops,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        optional: Option<Filled<Stops>>,
+
+        derived: Macro,
+    }
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    #[test]
+    fn every_way_reads_and_writes_fills() {
+        let json = r##"{"wrapped":["#ff0000","#0000ff"],"with":["#ff0000"],"derived":["#0000ff","#0000ff"]}"##;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config, Config {
+            wrapped: Filled(Stops(vec![RED, BLUE])),
+            with: Stops(vec![RED]),
+            optional: None,
+            derived: Macro(Stops(vec![BLUE, BLUE])),
+        });
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+    }
+
+    #[test]
+    fn every_form_of_fill() {
+        let config: Config = serde_json::from_str(r##"{
+            "wrapped": ["#F00", { "r": 0, "g": 0, "b": 255 }],
+            "with": [[255, 0, 0]],
+            "optional": ["#00f"],
+            "derived": ["#00f"]
+        }"##).unwrap();
+
+        assert_eq!(config.wrapped.into_inner(), Stops(vec![RED, BLUE]));
+        assert_eq!(config.with, Stops(vec![RED]));
+        assert_eq!(config.optional, Some(Filled(Stops(vec![BLUE]))));
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let err = serde_json::from_str::<Filled<Stops>>(r#""rainbow""#).unwrap_err().to_string();
+
+        assert!(err.starts_with("not a linear gradient"), "{}", err);
+        assert!(serde_json::from_str::<Macro>(r##""#ggg""##).is_err());
+
+        let err: String = serde_json::from_str::<Macro>(r##"{ "type": "radial", "stops": ["#fff"] }"##)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.starts_with("not a linear gradient"), "{}", err);
+    }
+}
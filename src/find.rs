@@ -0,0 +1,175 @@
+//! Finding the fills in a JSON document that isn't described by any type, for analysis tools.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::fmt;
+
+use crate::fill::Fill;
+
+/// Where a value sits in a JSON document, as an RFC 6901 pointer like `/theme/accent/0`. The
+/// whole document is the empty pointer. `serde_json::Value::pointer` takes `as_str()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct JsonPointer(String);
+
+impl JsonPointer {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The pointer to a member or element of what `self` points to, with `~` and `/` in `token`
+    /// escaped.
+    pub fn join(&self, token: &str) -> JsonPointer {
+        JsonPointer(format!("{}/{}", self.0, token.replace('~', "~0").replace('/', "~1")))
+    }
+}
+
+impl fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Every value in `document` that parses as a fill, in the order `serde_json` keeps object
+/// members in: sorted by key, unless its `preserve_order` feature is on. Nothing inside a fill is
+/// reported again, so a gradient's stops come back as the gradient.
+///
+/// Bare numbers and arrays of nothing but numbers are left out, even though they can be colors,
+/// since most of the ones in a document aren't.
+///
+/// ```
+/// use std::collections::BTreeSet;
+///
+/// use led_json::{find_fills, Color, Fill};
+///
+/// let config = serde_json::json!({
+///     "name": "night",
+///     "size": [1920, 1080, 1],
+///     "background": "#000",
+///     "zones": [
+///         { "id": 1, "fill": ["#f00", "#000"] },
+///         { "id": 2, "fill": "rainbow" },
+///     ],
+/// });
+///
+/// let fills = find_fills(&config);
+/// let pointers: Vec<&str> = fills.iter().map(|(pointer, _)| pointer.as_str()).collect();
+///
+/// assert_eq!(pointers, ["/background", "/zones/0/fill", "/zones/1/fill"]);
+///
+/// let colors: BTreeSet<Color> = fills.iter()
+///     .flat_map(|(_, fill)| match fill {
+///         Fill::Color(color) => vec![*color],
+///         fill => fill.stops().unwrap_or(&[]).to_vec(),
+///     })
+///     .collect();
+///
+/// assert_eq!(colors.len(), 2);
+/// ```
+pub fn find_fills(document: &Value) -> Vec<(JsonPointer, Fill)> {
+    let mut fills = vec![];
+    find(document, JsonPointer::default(), &mut fills);
+
+    fills
+}
+
+fn find(value: &Value, pointer: JsonPointer, fills: &mut Vec<(JsonPointer, Fill)>) {
+    let numbers = match value {
+        Value::Number(_) => true,
+        Value::Array(items) => items.iter().all(Value::is_number),
+        _ => false,
+    };
+
+    if !numbers {
+        if let Ok(fill) = Fill::deserialize(value) {
+            fills.push((pointer, fill));
+
+            return
+        }
+    }
+
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                find(item, pointer.join(&i.to_string()), fills);
+            }
+        },
+        Value::Object(members) => {
+            for (key, member) in members {
+                find(member, pointer.join(key), fills);
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::color::Color;
+
+    fn pointers(document: Value) -> Vec<String> {
+        find_fills(&document).into_iter().map(|(pointer, _)| pointer.to_string()).collect()
+    }
+
+    #[test]
+    fn every_form() {
+        let document = json!({
+            "color": "#ff00aa",
+            "keyword": "none",
+            "gradient": ["#fff", "#000"],
+            "radial": { "type": "radial", "stops": ["#fff"] },
+            "channels": { "r": 255, "g": 0, "b": 170 },
+            "rainbow": { "rainbow": { "period": 2.0 } },
+        });
+
+        assert_eq!(pointers(document), ["/channels", "/color", "/gradient", "/keyword", "/radial", "/rainbow"]);
+    }
+
+    #[test]
+    fn skips_what_isnt_a_fill() {
+        let document = json!({
+            "name": "night",
+            "enabled": true,
+            "brightness": 255,
+            "size": [1920, 1080, 1],
+            "tags": ["a", "b"],
+            "nothing": null,
+            "empty": [],
+            "zone": { "id": 3, "label": "#1" },
+        });
+
+        assert_eq!(pointers(document), Vec::<String>::new());
+    }
+
+    #[test]
+    fn nested() {
+        let document = json!([
+            { "fills": ["#f00", ["#f00", "#00f"]] },
+            "#000",
+        ]);
+
+        let fills = find_fills(&document);
+
+        assert_eq!(fills[0], (JsonPointer("/0/fills/0".into()), Fill::Color(Color::new(255, 0, 0))));
+        assert_eq!(fills[1].0.as_str(), "/0/fills/1");
+        assert_eq!(fills[2].0.as_str(), "/1");
+        assert_eq!(fills.len(), 3);
+    }
+
+    #[test]
+    fn pointers_point_at_the_fills() {
+        let document = json!({ "a/b": { "c~d": "#fff" }, "": "#000" });
+        let fills = find_fills(&document);
+
+        assert_eq!(fills[0].0.as_str(), "/");
+        assert_eq!(fills[1].0.as_str(), "/a~1b/c~0d");
+
+        for (pointer, fill) in &fills {
+            assert_eq!(&Fill::deserialize(document.pointer(pointer.as_str()).unwrap()).unwrap(), fill);
+        }
+    }
+}
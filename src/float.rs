@@ -0,0 +1,315 @@
+//! Serde for the float color types, `Hsl`, `Hsv` and `Lab`, with a policy for components that
+//! are NaN, infinite or out of range.
+//!
+//! Each serializes as a struct of its fields, like `{ "hue": 300.0, "saturation": 1.0,
+//! "lightness": 0.5 }`. `Deserialize` rejects anything `FloatPolicy::Reject` would; deserialize
+//! through `FloatPolicy::seed` to clamp or keep such values instead.
+
+use serde::de::{self, DeserializeSeed, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use std::marker::PhantomData;
+
+use crate::error::ComponentError;
+use crate::hsl::{Hsl, Hsv};
+use crate::lab::Lab;
+
+/// How far Lab's `a` and `b` can go either way from gray, past anything sRGB can show.
+const LAB_AXIS_LIMIT: f32 = 128.0;
+
+/// What to do with a component that's NaN, infinite or outside its range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatPolicy {
+    /// Fail with a `ComponentError`. What `Deserialize` does.
+    #[default]
+    Reject,
+
+    /// Pull the component into range: hues wrap around to `0.0..360.0`, everything else is
+    /// clamped to its range, and NaN becomes the bottom of the range (`0.0` for hues). Infinite
+    /// hues become `0.0` too. Lab's `a` and `b` are clamped to `-128.0..=128.0` like the rest,
+    /// but NaN becomes `0.0`, no color at all, rather than the bottom of the range.
+    Clamp,
+
+    /// Keep every value as it is, for pipelines that check them later.
+    Preserve,
+}
+
+/// A color type with float components that `FloatPolicy` applies to.
+pub trait FloatColor: Sized + private::Sealed {
+    /// Checks or fixes the components according to `policy`.
+    fn with_policy(self, policy: FloatPolicy) -> std::result::Result<Self, ComponentError>;
+}
+
+mod private {
+    pub trait Sealed {
+        /// Deserializes without looking at the values.
+        fn deserialize_unchecked<'de, D>(deserializer: D) -> std::result::Result<Self, D::Error>
+        where
+            Self: Sized,
+            D: serde::Deserializer<'de>;
+    }
+}
+
+impl FloatPolicy {
+    /// Deserializes a `T` with this policy.
+    ///
+    /// ```
+    /// use led_json::{FloatPolicy, Hsl};
+    /// use serde::de::DeserializeSeed;
+    ///
+    /// let json = r#"{ "hue": 400.0, "saturation": 1.5, "lightness": 0.5 }"#;
+    ///
+    /// assert!(serde_json::from_str::<Hsl>(json).is_err());
+    ///
+    /// let hsl: Hsl = FloatPolicy::Clamp.seed().deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+    ///
+    /// assert_eq!(hsl, Hsl { hue: 40.0, saturation: 1.0, lightness: 0.5 });
+    /// ```
+    pub fn seed<T: FloatColor>(self) -> PolicySeed<T> {
+        PolicySeed { policy: self, marker: PhantomData }
+    }
+
+    /// Applies the policy to one component that should be in `min..=max`.
+    fn apply(self, component: &'static str, value: f32, min: f32, max: f32) -> std::result::Result<f32, ComponentError> {
+        match self {
+            FloatPolicy::Preserve => Ok(value),
+            FloatPolicy::Clamp if value.is_nan() => Ok(min),
+            FloatPolicy::Clamp => Ok(value.clamp(min, max)),
+            FloatPolicy::Reject if !value.is_finite() => Err(ComponentError::NotFinite { component, value }),
+            FloatPolicy::Reject if value < min || value > max => {
+                Err(ComponentError::OutOfRange { component, value, min, max })
+            },
+            FloatPolicy::Reject => Ok(value),
+        }
+    }
+
+    /// Like `apply`, for a hue in degrees, which can wrap around instead of being clamped.
+    fn hue(self, value: f32) -> std::result::Result<f32, ComponentError> {
+        match self {
+            FloatPolicy::Clamp if !value.is_finite() => Ok(0.0),
+            // `rem_euclid` can round up to exactly 360.0 for tiny negative hues
+            FloatPolicy::Clamp => Ok(Some(value.rem_euclid(360.0)).filter(|h| *h < 360.0).unwrap_or(0.0)),
+            FloatPolicy::Reject if value.is_finite() && !(0.0..360.0).contains(&value) => {
+                Err(ComponentError::OutOfRange { component: "hue", value, min: 0.0, max: 360.0 })
+            },
+            _ => self.apply("hue", value, f32::NEG_INFINITY, f32::INFINITY),
+        }
+    }
+
+    /// Like `apply`, for Lab's `a` or `b`, which is centered on gray.
+    fn axis(self, component: &'static str, value: f32) -> std::result::Result<f32, ComponentError> {
+        match self {
+            FloatPolicy::Clamp if value.is_nan() => Ok(0.0),
+            _ => self.apply(component, value, -LAB_AXIS_LIMIT, LAB_AXIS_LIMIT),
+        }
+    }
+}
+
+/// See `FloatPolicy::seed`.
+pub struct PolicySeed<T> {
+    policy: FloatPolicy,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T: FloatColor> DeserializeSeed<'de> for PolicySeed<T> {
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_unchecked(deserializer)?.with_policy(self.policy).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Hsl", deny_unknown_fields)]
+struct HslDef {
+    hue: f32,
+    saturation: f32,
+    lightness: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Hsv", deny_unknown_fields)]
+struct HsvDef {
+    hue: f32,
+    saturation: f32,
+    value: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Lab", deny_unknown_fields)]
+struct LabDef {
+    lightness: f32,
+    a: f32,
+    b: f32,
+}
+
+impl FloatColor for Hsl {
+    fn with_policy(self, policy: FloatPolicy) -> std::result::Result<Self, ComponentError> {
+        Ok(Hsl {
+            hue: policy.hue(self.hue)?,
+            saturation: policy.apply("saturation", self.saturation, 0.0, 1.0)?,
+            lightness: policy.apply("lightness", self.lightness, 0.0, 1.0)?,
+        })
+    }
+}
+
+impl FloatColor for Hsv {
+    fn with_policy(self, policy: FloatPolicy) -> std::result::Result<Self, ComponentError> {
+        Ok(Hsv {
+            hue: policy.hue(self.hue)?,
+            saturation: policy.apply("saturation", self.saturation, 0.0, 1.0)?,
+            value: policy.apply("value", self.value, 0.0, 1.0)?,
+        })
+    }
+}
+
+/// `lightness` is in `0.0..=100.0`, and `a` and `b` in `-128.0..=128.0`.
+impl FloatColor for Lab {
+    fn with_policy(self, policy: FloatPolicy) -> std::result::Result<Self, ComponentError> {
+        Ok(Lab {
+            lightness: policy.apply("lightness", self.lightness, 0.0, 100.0)?,
+            a: policy.axis("a", self.a)?,
+            b: policy.axis("b", self.b)?,
+        })
+    }
+}
+
+macro_rules! serde_impls {
+    ($($ty:ident => $def:ident),*) => {
+        $(
+            impl private::Sealed for $ty {
+                fn deserialize_unchecked<'de, D>(deserializer: D) -> std::result::Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    $def::deserialize(deserializer)
+                }
+            }
+
+            /// Writes the components as they are, NaN included.
+            impl Serialize for $ty {
+                fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    $def::serialize(self, serializer)
+                }
+            }
+
+            /// Rejects components that are NaN, infinite or out of range.
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    FloatPolicy::Reject.seed().deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+serde_impls!(Hsl => HslDef, Hsv => HsvDef, Lab => LabDef);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with<T: FloatColor>(policy: FloatPolicy, json: &str) -> serde_json::Result<T> {
+        policy.seed().deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn round_trip() {
+        let hsl = Hsl { hue: 300.0, saturation: 1.0, lightness: 0.25 };
+        let json = serde_json::to_string(&hsl).unwrap();
+
+        assert_eq!(json, r#"{"hue":300.0,"saturation":1.0,"lightness":0.25}"#);
+        assert_eq!(serde_json::from_str::<Hsl>(&json).unwrap(), hsl);
+
+        let lab = Lab { lightness: 50.0, a: -20.5, b: 120.0 };
+
+        assert_eq!(serde_json::from_str::<Lab>(&serde_json::to_string(&lab).unwrap()).unwrap(), lab);
+    }
+
+    #[test]
+    fn rejects_by_default() {
+        let err = serde_json::from_str::<Hsv>(r#"{ "hue": 10.0, "saturation": 2.0, "value": 1.0 }"#).unwrap_err();
+
+        assert!(err.to_string().contains("saturation 2 is outside 0..=1"), "{}", err);
+
+        assert!(serde_json::from_str::<Hsl>(r#"{ "hue": 360.0, "saturation": 0.0, "lightness": 0.0 }"#).is_err());
+        assert!(serde_json::from_str::<Lab>(r#"{ "lightness": 101.0, "a": 0.0, "b": 0.0 }"#).is_err());
+        assert!(serde_json::from_str::<Lab>(r#"{ "lightness": 50.0, "a": 0.0, "b": 130.0 }"#).is_err());
+        assert!(serde_json::from_str::<Lab>(r#"{ "lightness": 50.0, "a": -128.0, "b": 128.0 }"#).is_ok());
+        assert!(serde_json::from_str::<Lab>(r#"{ "lightness": 50.0, "a": 0.0, "b": 0.0, "alpha": 1.0 }"#).is_err());
+    }
+
+    #[test]
+    fn policies_on_components() {
+        let nan = Hsl { hue: f32::NAN, saturation: f32::NAN, lightness: f32::INFINITY };
+
+        assert!(matches!(nan.with_policy(FloatPolicy::Reject), Err(ComponentError::NotFinite { component: "hue", .. })));
+        assert_eq!(nan.with_policy(FloatPolicy::Clamp), Ok(Hsl { hue: 0.0, saturation: 0.0, lightness: 1.0 }));
+
+        let preserved = nan.with_policy(FloatPolicy::Preserve).unwrap();
+
+        assert!(preserved.hue.is_nan() && preserved.saturation.is_nan() && preserved.lightness.is_infinite());
+
+        assert_eq!(
+            Hsv { hue: -30.0, saturation: -1.0, value: 0.5 }.with_policy(FloatPolicy::Clamp),
+            Ok(Hsv { hue: 330.0, saturation: 0.0, value: 0.5 })
+        );
+        assert_eq!(
+            Lab { lightness: 120.0, a: f32::NEG_INFINITY, b: 5.0 }.with_policy(FloatPolicy::Clamp),
+            Ok(Lab { lightness: 100.0, a: -128.0, b: 5.0 })
+        );
+    }
+
+    #[test]
+    fn clamped_lab_axes() {
+        let clamp = |a, b| Lab { lightness: 50.0, a, b }.with_policy(FloatPolicy::Clamp).unwrap();
+
+        assert_eq!(clamp(f32::NAN, f32::NAN), Lab { lightness: 50.0, a: 0.0, b: 0.0 });
+        assert_eq!(clamp(1e39_f64 as f32, -200.0), Lab { lightness: 50.0, a: 128.0, b: -128.0 });
+        assert_eq!(clamp(-20.5, 127.0), Lab { lightness: 50.0, a: -20.5, b: 127.0 });
+
+        let json = r#"{ "lightness": 50.0, "a": 1e39, "b": -1e39 }"#;
+
+        assert_eq!(with::<Lab>(FloatPolicy::Clamp, json).unwrap(), Lab { lightness: 50.0, a: 128.0, b: -128.0 });
+        assert!(with::<Lab>(FloatPolicy::Reject, json).is_err());
+
+        // both policies agree on the range
+        let err = Lab { lightness: 50.0, a: 0.0, b: -200.0 }.with_policy(FloatPolicy::Reject).unwrap_err();
+
+        assert_eq!(err, ComponentError::OutOfRange { component: "b", value: -200.0, min: -128.0, max: 128.0 });
+    }
+
+    #[test]
+    fn nan_from_binary_formats() {
+        // JSON can't hold a NaN, but binary formats can
+        let fields = || {
+            let entries = vec![("hue", f32::NAN), ("saturation", 0.5), ("value", 0.5)];
+
+            de::value::MapDeserializer::<_, de::value::Error>::new(entries.into_iter())
+        };
+
+        let err = Hsv::deserialize(fields()).unwrap_err();
+
+        assert_eq!(err.to_string(), "hue NaN isn't a finite number");
+        assert_eq!(FloatPolicy::Clamp.seed().deserialize(fields()), Ok(Hsv { hue: 0.0, saturation: 0.5, value: 0.5 }));
+    }
+
+    #[test]
+    fn policy_seeds() {
+        let json = r#"{ "lightness": 150.0, "a": 0.0, "b": 0.0 }"#;
+
+        assert!(with::<Lab>(FloatPolicy::Reject, json).is_err());
+        assert_eq!(with::<Lab>(FloatPolicy::Clamp, json).unwrap(), Lab { lightness: 100.0, a: 0.0, b: 0.0 });
+        assert_eq!(with::<Lab>(FloatPolicy::Preserve, json).unwrap(), Lab { lightness: 150.0, a: 0.0, b: 0.0 });
+    }
+}
@@ -0,0 +1,306 @@
+use serde::{Serialize, Serializer};
+use serde::ser::{self, SerializeSeq, SerializeStruct};
+
+use alloc::string::{String, ToString};
+
+use core::fmt::{self, Write};
+
+use crate::binary;
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::rainbow::RainbowParams;
+
+/// Output style used when turning a `Color` back into a string.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorFormat {
+    /// `#ff00aa`. This is what `Display` and the default `Serialize` impls use.
+    #[default]
+    LongHex,
+
+    /// `#f0a` when every channel is a repeated digit, otherwise `#ff00ab`.
+    ShortHexIfPossible,
+
+    /// `#FF00AA`
+    UpperHex,
+
+    /// `#F0A` when every channel is a repeated digit, otherwise `#FF00AB`.
+    ShortUpperHexIfPossible,
+
+    /// `rgb(255, 0, 170)`
+    RgbFunction,
+}
+
+impl Color {
+    pub fn format(&self, format: ColorFormat) -> String {
+        Formatted::new(self, format).to_string()
+    }
+
+    fn is_short(&self) -> bool {
+        [self.red, self.green, self.blue].iter().all(|c| c % 17 == 0)
+    }
+}
+
+/// Wraps a `Color` or `Fill` so that it serializes using the given `ColorFormat`.
+///
+/// ```
+/// use led_json::{Color, ColorFormat, Fill, Formatted};
+///
+/// let fill = Fill::Color(Color { red: 255, green: 0, blue: 255 });
+/// let json = serde_json::to_string(&Formatted::new(&fill, ColorFormat::ShortHexIfPossible)).unwrap();
+///
+/// assert_eq!(json, r##""#f0f""##);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Formatted<'a, T> {
+    pub value: &'a T,
+    pub format: ColorFormat,
+}
+
+impl<'a, T> Formatted<'a, T> {
+    pub fn new(value: &'a T, format: ColorFormat) -> Self {
+        Formatted { value, format }
+    }
+}
+
+impl fmt::Display for Formatted<'_, Color> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = self.value;
+
+        match self.format {
+            ColorFormat::LongHex => write!(f, "#{:02x}{:02x}{:02x}", c.red, c.green, c.blue),
+            ColorFormat::UpperHex => write!(f, "#{:02X}{:02X}{:02X}", c.red, c.green, c.blue),
+            ColorFormat::ShortHexIfPossible if c.is_short() => {
+                write!(f, "#{:x}{:x}{:x}", c.red / 17, c.green / 17, c.blue / 17)
+            },
+            ColorFormat::ShortHexIfPossible => write!(f, "#{:02x}{:02x}{:02x}", c.red, c.green, c.blue),
+            ColorFormat::ShortUpperHexIfPossible if c.is_short() => {
+                write!(f, "#{:X}{:X}{:X}", c.red / 17, c.green / 17, c.blue / 17)
+            },
+            ColorFormat::ShortUpperHexIfPossible => write!(f, "#{:02X}{:02X}{:02X}", c.red, c.green, c.blue),
+            ColorFormat::RgbFunction => write!(f, "rgb({}, {}, {})", c.red, c.green, c.blue),
+        }
+    }
+}
+
+/// Fixed-size buffer for formatting a single color without allocating. Big enough for the
+/// longest format, `rgb(255, 255, 255)`.
+struct Buffer {
+    bytes: [u8; 18],
+    len: usize,
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Buffer { bytes: [0; 18], len: 0 }
+    }
+
+    fn as_str(&self) -> &str {
+        // only ever written to through `write_str`, so this is always a whole `str`
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+
+        Ok(())
+    }
+}
+
+impl Serialize for Formatted<'_, Color> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return binary::serialize_color(self.value, serializer)
+        }
+
+        // `collect_str` defaults to going through a `String` for most serializers
+        let mut buffer = Buffer::new();
+        write!(buffer, "{}", self).map_err(ser::Error::custom)?;
+
+        serializer.serialize_str(buffer.as_str())
+    }
+}
+
+impl Serialize for Formatted<'_, Fill> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return binary::serialize_fill(self.value, serializer)
+        }
+
+        match self.value {
+            Fill::Rainbow(params) if *params == RainbowParams::DEFAULT => serializer.serialize_str("rainbow"),
+            Fill::Rainbow(params) => {
+                let mut s = serializer.serialize_struct("Fill", 1)?;
+                s.serialize_field("rainbow", params)?;
+
+                s.end()
+            },
+            Fill::None => serializer.serialize_str("none"),
+            Fill::Transparent => serializer.serialize_str("transparent"),
+            Fill::Color(color) => Formatted::new(color, self.format).serialize(serializer),
+            Fill::Gradient(colors) => Stops(colors, self.format).serialize(serializer),
+            Fill::Radial { center, stops } => {
+                let mut s = serializer.serialize_struct("Fill", 3)?;
+                s.serialize_field("type", "radial")?;
+                s.serialize_field("center", center)?;
+                s.serialize_field("stops", &Stops(stops, self.format))?;
+
+                s.end()
+            },
+            Fill::Conic { angle, stops } => {
+                let mut s = serializer.serialize_struct("Fill", 3)?;
+                s.serialize_field("type", "conic")?;
+                s.serialize_field("angle", angle)?;
+                s.serialize_field("stops", &Stops(stops, self.format))?;
+
+                s.end()
+            },
+            Fill::Raw(raw) => serializer.serialize_str(raw),
+        }
+    }
+}
+
+/// `linear-gradient(#fff, #000)` and friends, which `Fill::from_str` reads back.
+impl fmt::Display for Formatted<'_, Fill> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops = |f: &mut fmt::Formatter<'_>, stops: &[Color], mut separator: &str| {
+            for c in stops {
+                write!(f, "{}{}", separator, Formatted::new(c, self.format))?;
+                separator = ", ";
+            }
+
+            f.write_str(")")
+        };
+
+        match self.value {
+            Fill::Rainbow(params) if *params == RainbowParams::DEFAULT => f.write_str("rainbow"),
+            Fill::Rainbow(params) => write!(
+                f,
+                "rainbow(period {}, phase {}, saturation {}, lightness {})",
+                params.period, params.phase, params.saturation, params.lightness
+            ),
+            Fill::None => f.write_str("none"),
+            Fill::Transparent => f.write_str("transparent"),
+            Fill::Color(color) => Formatted::new(color, self.format).fmt(f),
+            Fill::Gradient(colors) => {
+                f.write_str("linear-gradient(")?;
+                stops(f, colors, "")
+            },
+            Fill::Radial { center: (x, y), stops: colors } => {
+                write!(f, "radial-gradient(at {} {}", x, y)?;
+                stops(f, colors, ", ")
+            },
+            Fill::Conic { angle, stops: colors } => {
+                write!(f, "conic-gradient(from {}deg", angle)?;
+                stops(f, colors, ", ")
+            },
+            Fill::Raw(raw) => f.write_str(raw),
+        }
+    }
+}
+
+/// Gradient stops, each serialized with the same format.
+struct Stops<'a>(&'a [Color], ColorFormat);
+
+impl Serialize for Stops<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_seq(Some(self.0.len()))?;
+        for c in self.0 {
+            s.serialize_element(&Formatted::new(c, self.1))?;
+        }
+
+        s.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::{format, vec};
+
+    const MAGENTA: Color = Color { red: 255, green: 0, blue: 255 };
+    const ODD: Color = Color { red: 15, green: 0, blue: 171 };
+
+    #[test]
+    fn long_hex() {
+        assert_eq!(MAGENTA.format(ColorFormat::LongHex), "#ff00ff");
+        assert_eq!(ODD.format(ColorFormat::LongHex), "#0f00ab");
+    }
+
+    #[test]
+    fn short_hex_if_possible() {
+        assert_eq!(MAGENTA.format(ColorFormat::ShortHexIfPossible), "#f0f");
+        assert_eq!(ODD.format(ColorFormat::ShortHexIfPossible), "#0f00ab");
+    }
+
+    #[test]
+    fn upper_hex() {
+        assert_eq!(ODD.format(ColorFormat::UpperHex), "#0F00AB");
+    }
+
+    #[test]
+    fn short_upper_hex_if_possible() {
+        assert_eq!(MAGENTA.format(ColorFormat::ShortUpperHexIfPossible), "#F0F");
+        assert_eq!(ODD.format(ColorFormat::ShortUpperHexIfPossible), "#0F00AB");
+    }
+
+    #[test]
+    fn rgb_function() {
+        assert_eq!(ODD.format(ColorFormat::RgbFunction), "rgb(15, 0, 171)");
+    }
+
+    #[test]
+    fn buffer_fits_every_format() {
+        let white = Color { red: 255, green: 255, blue: 255 };
+        let formats = [
+            ColorFormat::LongHex,
+            ColorFormat::ShortHexIfPossible,
+            ColorFormat::UpperHex,
+            ColorFormat::ShortUpperHexIfPossible,
+            ColorFormat::RgbFunction,
+        ];
+
+        for format in formats.iter() {
+            for color in [&white, &ODD].iter() {
+                let json = serde_json::to_string(&Formatted::new(*color, *format)).unwrap();
+
+                assert_eq!(json, format!("\"{}\"", color.format(*format)));
+            }
+        }
+    }
+
+    #[test]
+    fn buffer_overflow_is_an_error() {
+        let mut buffer = Buffer::new();
+
+        assert!(buffer.write_str("rgb(255, 255, 255)").is_ok());
+        assert!(buffer.write_str(" ").is_err());
+        assert_eq!(buffer.as_str(), "rgb(255, 255, 255)");
+    }
+
+    #[test]
+    fn serialize_fill() {
+        let fill = Fill::Gradient(vec![MAGENTA, ODD]);
+        let json = serde_json::to_string(&Formatted::new(&fill, ColorFormat::ShortHexIfPossible)).unwrap();
+
+        assert_eq!(json, r##"["#f0f","#0f00ab"]"##);
+
+        let json = serde_json::to_string(&Formatted::new(&Fill::rainbow(), ColorFormat::UpperHex)).unwrap();
+
+        assert_eq!(json, r##""rainbow""##);
+    }
+}
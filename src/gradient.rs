@@ -0,0 +1,253 @@
+//! Gradients built from stops at positions of their own, like CSS's
+//! `linear-gradient(#fff 0%, #000 25%, #fff 100%)`.
+
+use alloc::vec::Vec;
+
+use crate::color::Color;
+use crate::error::{BuildError, GradientError};
+use crate::fill::{Fill, GradientRules};
+
+/// The most evenly spaced stops `GradientBuilder::build` spreads the given ones over.
+const MAX_STOPS: usize = 256;
+
+/// How far a position can be from an evenly spaced stop and still land on it.
+const TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Linear,
+    Radial((f32, f32)),
+    Conic(f32),
+}
+
+impl Shape {
+    fn fill(self, stops: Vec<Color>) -> Fill {
+        match self {
+            Shape::Linear => Fill::Gradient(stops),
+            Shape::Radial(center) => Fill::Radial { center, stops },
+            Shape::Conic(angle) => Fill::Conic { angle, stops },
+        }
+    }
+}
+
+/// Builds a gradient `Fill` from stops at positions from `0.0` to `1.0`, reporting everything
+/// wrong with it at once rather than the first problem, for editors that show them all inline.
+///
+/// A `Fill`'s stops are evenly spaced, so the given ones are spread over the fewest evenly spaced
+/// stops that put each of them on one, up to 256, with the colors in between interpolated. Before
+/// the first stop and after the last one, the gradient is that stop's color.
+///
+/// ```
+/// use led_json::{BuildError, Color, Fill, GradientBuilder};
+///
+/// let (white, black) = (Color::new(255, 255, 255), Color::new(0, 0, 0));
+/// let fill = GradientBuilder::linear().stop(0.0, white).stop(0.5, black).stop(1.0, white).build();
+///
+/// assert_eq!(fill, Ok(Fill::Gradient(vec![white, black, white])));
+///
+/// let errors = GradientBuilder::conic(400.0)
+///     .stop(0.0, white)
+///     .stop(0.5, black)
+///     .stop(0.5, white)
+///     .stop(0.25, black)
+///     .stop(1.5, white)
+///     .build()
+///     .unwrap_err();
+///
+/// assert_eq!(errors, [
+///     BuildError::DuplicatePosition { index: 2, position: 0.5 },
+///     BuildError::UnorderedStop { index: 3, position: 0.25, previous: 0.5 },
+///     BuildError::PositionOutOfRange { index: 4, position: 1.5 },
+///     BuildError::AngleOutOfRange(400.0),
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientBuilder {
+    shape: Shape,
+    stops: Vec<(f32, Color)>,
+    rules: GradientRules,
+}
+
+impl GradientBuilder {
+    /// A `Fill::Gradient`.
+    pub fn linear() -> Self {
+        GradientBuilder::new(Shape::Linear)
+    }
+
+    /// A `Fill::Radial` around `center`, given as fractions of the surface.
+    pub fn radial(center: (f32, f32)) -> Self {
+        GradientBuilder::new(Shape::Radial(center))
+    }
+
+    /// A `Fill::Conic` starting `angle` degrees clockwise from straight up, from `0.0` up to
+    /// `360.0`.
+    pub fn conic(angle: f32) -> Self {
+        GradientBuilder::new(Shape::Conic(angle))
+    }
+
+    fn new(shape: Shape) -> Self {
+        GradientBuilder { shape, stops: Vec::new(), rules: GradientRules::default() }
+    }
+
+    /// The same builder, with a stop of `color` at `position`. Stops go in order of position.
+    pub fn stop(mut self, position: f32, color: Color) -> Self {
+        self.stops.push((position, color));
+        self
+    }
+
+    /// The same builder, checking the stops against `rules` rather than the default ones.
+    pub fn rules(self, rules: GradientRules) -> Self {
+        GradientBuilder { rules, ..self }
+    }
+
+    /// Every problem `build` would report, in order: the stops' positions, the angle, then the
+    /// `GradientRules`, whose stop indexes are the given stops'. Empty if it would succeed.
+    pub fn violations(&self) -> Vec<BuildError> {
+        let mut violations = Vec::new();
+        let mut furthest: Option<f32> = None;
+
+        for (index, &(position, _)) in self.stops.iter().enumerate() {
+            if !(0.0..=1.0).contains(&position) {
+                violations.push(BuildError::PositionOutOfRange { index, position });
+                continue
+            }
+
+            match furthest {
+                Some(previous) if position < previous => violations.push(BuildError::UnorderedStop { index, position, previous }),
+                Some(previous) if position == previous => violations.push(BuildError::DuplicatePosition { index, position }),
+                _ => furthest = Some(position),
+            }
+        }
+
+        if let Shape::Conic(angle) = self.shape {
+            if !(0.0..360.0).contains(&angle) {
+                violations.push(BuildError::AngleOutOfRange(angle));
+            }
+        }
+
+        let given = self.shape.fill(self.stops.iter().map(|&(_, color)| color).collect());
+        let broken = given.gradient_violations(&self.rules).into_iter();
+
+        // a bad angle has already been reported, with its range
+        violations.extend(broken.filter(|e| !matches!(e, GradientError::InvalidAngle(_))).map(BuildError::from));
+
+        violations
+    }
+
+    /// The gradient, or every problem with it.
+    pub fn build(&self) -> core::result::Result<Fill, Vec<BuildError>> {
+        let violations = self.violations();
+
+        if !violations.is_empty() {
+            return Err(violations)
+        }
+
+        let stops = match self.stops.as_slice() {
+            [(_, color)] => vec![*color],
+            stops => {
+                let lands = |count: usize| {
+                    let steps = (count - 1) as f32;
+
+                    stops.iter().all(|&(position, _)| ((position * steps).round() - position * steps).abs() < TOLERANCE * steps)
+                };
+                let count = (2..=MAX_STOPS).find(|&count| lands(count)).unwrap_or(MAX_STOPS);
+
+                (0..count).map(|i| color_at(stops, i as f32 / (count - 1) as f32)).collect()
+            },
+        };
+
+        Ok(self.shape.fill(stops))
+    }
+}
+
+/// The color at `t` between positioned `stops`, which are in order.
+fn color_at(stops: &[(f32, Color)], t: f32) -> Color {
+    match stops.iter().position(|&(position, _)| position + TOLERANCE >= t) {
+        Some(0) => stops[0].1,
+        Some(index) => {
+            let ((from, start), (to, end)) = (stops[index - 1], stops[index]);
+
+            if (to - t).abs() < TOLERANCE {
+                end
+            } else {
+                start.lerp(&end, (t - from) / (to - from))
+            }
+        },
+        None => stops[stops.len() - 1].1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+    const GRAY: Color = Color { red: 128, green: 128, blue: 128 };
+
+    #[test]
+    fn evenly_spaced_stops() {
+        assert_eq!(GradientBuilder::linear().stop(0.0, WHITE).stop(1.0, BLACK).build(), Ok(Fill::Gradient(vec![WHITE, BLACK])));
+        assert_eq!(
+            GradientBuilder::radial((0.25, 0.5)).stop(0.0, WHITE).stop(0.5, BLACK).stop(1.0, WHITE).build(),
+            Ok(Fill::Radial { center: (0.25, 0.5), stops: vec![WHITE, BLACK, WHITE] })
+        );
+    }
+
+    #[test]
+    fn positions_in_between() {
+        // 0.25 needs five stops, with the two between it and 1.0 a third and two thirds of the way
+        assert_eq!(
+            GradientBuilder::conic(90.0).stop(0.0, WHITE).stop(0.25, BLACK).stop(1.0, WHITE).build(),
+            Ok(Fill::Conic { angle: 90.0, stops: vec![WHITE, BLACK, Color { red: 85, green: 85, blue: 85 }, Color { red: 170, green: 170, blue: 170 }, WHITE] })
+        );
+
+        // before the first stop and after the last is their color
+        assert_eq!(GradientBuilder::linear().stop(0.5, GRAY).stop(1.0, WHITE).build(), Ok(Fill::Gradient(vec![GRAY, GRAY, WHITE])));
+        assert_eq!(GradientBuilder::linear().stop(0.3, GRAY).build(), Ok(Fill::Gradient(vec![GRAY])));
+    }
+
+    #[test]
+    fn positions_that_never_land() {
+        let fill = GradientBuilder::linear().stop(0.0, WHITE).stop(0.5 + 1.0 / 600.0, BLACK).stop(1.0, WHITE).build().unwrap();
+
+        assert_eq!(fill.stops().map(<[_]>::len), Some(MAX_STOPS));
+    }
+
+    #[test]
+    fn every_problem_at_once() {
+        let builder = GradientBuilder::radial((1.5, 0.5))
+            .stop(-0.5, WHITE)
+            .stop(0.5, WHITE)
+            .stop(0.5, WHITE)
+            .rules(GradientRules::strict());
+
+        assert_eq!(builder.violations(), [
+            BuildError::PositionOutOfRange { index: 0, position: -0.5 },
+            BuildError::DuplicatePosition { index: 2, position: 0.5 },
+            BuildError::Gradient(GradientError::CenterOutOfRange { x: 1.5, y: 0.5 }),
+            BuildError::Gradient(GradientError::RepeatedStop { index: 1 }),
+            BuildError::Gradient(GradientError::RepeatedStop { index: 2 }),
+        ]);
+        assert_eq!(builder.build(), Err(builder.violations()));
+
+        let nan = GradientBuilder::linear().stop(f32::NAN, WHITE).violations();
+
+        assert!(matches!(nan.as_slice(), [BuildError::PositionOutOfRange { index: 0, position }] if position.is_nan()));
+    }
+
+    #[test]
+    fn angles() {
+        for angle in [-1.0, 360.0, f32::INFINITY] {
+            assert_eq!(GradientBuilder::conic(angle).stop(0.0, WHITE).violations(), [BuildError::AngleOutOfRange(angle)]);
+        }
+
+        assert!(matches!(GradientBuilder::conic(f32::NAN).stop(0.0, WHITE).violations().as_slice(), [BuildError::AngleOutOfRange(_)]));
+        assert!(GradientBuilder::conic(359.5).stop(0.0, WHITE).build().is_ok());
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(GradientBuilder::linear().build(), Err(vec![BuildError::Gradient(GradientError::Empty)]));
+    }
+}
@@ -0,0 +1,158 @@
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::fill::Fill;
+
+/// Exposed as a hex string like `"#ff00ff"`. Both short and long hex are accepted as input.
+#[Scalar(name = "Color")]
+impl ScalarType for Color {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(s) => Color::from_str(s).map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::String(_))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// Exposed the same way as in JSON: a keyword like `"rainbow"` or a hex color string, a list of
+/// colors in any of their forms for a gradient, or an object for a color, a radial or conic
+/// gradient or rainbow parameters. Strings are also read in the textual forms of `FromStr`.
+#[Scalar(name = "Fill")]
+impl ScalarType for Fill {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(s) => Fill::from_str(s).map_err(InputValueError::custom),
+            // through `Deserialize`, so stops take every form they do in JSON and are checked the same way
+            Value::List(_) | Value::Object(_) => async_graphql::from_value(value).map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::String(_) | Value::List(_) | Value::Object(_))
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Fill::None => Value::String("none".to_string()),
+            Fill::Transparent => Value::String("transparent".to_string()),
+            Fill::Raw(raw) => Value::String(raw.clone()),
+            Fill::Color(color) => color.to_value(),
+            Fill::Gradient(colors) => Value::List(colors.iter().map(|c| c.to_value()).collect()),
+            // these only hold floats, strings and lists, which always serialize
+            Fill::Rainbow(_) | Fill::Radial { .. } | Fill::Conic { .. } => async_graphql::to_value(self).unwrap_or(Value::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rainbow::RainbowParams;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    fn color_round_trip() {
+        let color = <Color as ScalarType>::parse(string("#f0f")).unwrap();
+
+        assert_eq!(color, Color { red: 255, green: 0, blue: 255 });
+        assert_eq!(color.to_value(), string("#ff00ff"));
+    }
+
+    #[test]
+    fn color_rejects_non_string() {
+        assert!(<Color as ScalarType>::parse(Value::Boolean(true)).is_err());
+        assert!(<Color as ScalarType>::parse(string("hello")).is_err());
+    }
+
+    #[test]
+    fn fill_rainbow() {
+        let fill = <Fill as ScalarType>::parse(string("rainbow")).unwrap();
+
+        assert_eq!(fill, Fill::rainbow());
+        assert_eq!(fill.to_value(), string("rainbow"));
+    }
+
+    #[test]
+    fn fill_none_and_transparent() {
+        assert_eq!(<Fill as ScalarType>::parse(string("none")).unwrap(), Fill::None);
+        assert_eq!(Fill::Transparent.to_value(), string("transparent"));
+    }
+
+    #[test]
+    fn fill_gradient() {
+        let value = Value::List(vec![string("#fff"), string("#00ff00")]);
+        let fill = <Fill as ScalarType>::parse(value).unwrap();
+
+        assert_eq!(fill, Fill::Gradient(vec![
+            Color { red: 255, green: 255, blue: 255 },
+            Color { red: 0, green: 255, blue: 0 },
+        ]));
+        assert_eq!(fill.to_value(), Value::List(vec![string("#ffffff"), string("#00ff00")]));
+    }
+
+    #[test]
+    fn fill_radial() {
+        let fill = Fill::Radial { center: (0.25, 0.5), stops: vec![Color { red: 255, green: 0, blue: 0 }] };
+        let value = fill.to_value();
+
+        assert_eq!(value.to_string(), r##"{type: "radial", center: [0.25, 0.5], stops: ["#ff0000"]}"##);
+        assert_eq!(<Fill as ScalarType>::parse(value).unwrap(), fill);
+    }
+
+    #[test]
+    fn fill_rainbow_params() {
+        let fill = Fill::Rainbow(RainbowParams { period: 2.0, ..RainbowParams::DEFAULT });
+        let value = fill.to_value();
+
+        assert_eq!(value.to_string(), "{rainbow: {saturation: 1.0, lightness: 0.5, period: 2.0, phase: 0.0}}");
+        assert_eq!(<Fill as ScalarType>::parse(value).unwrap(), fill);
+    }
+
+    #[test]
+    fn fill_gradient_rejects_rainbow() {
+        let value = Value::List(vec![string("rainbow")]);
+
+        assert!(<Fill as ScalarType>::parse(value).is_err());
+    }
+
+    #[test]
+    fn fill_structured_stops() {
+        let value = async_graphql::to_value(serde_json::json!([{ "r": 255, "g": 0, "b": 0 }, [0, 0, 255]])).unwrap();
+
+        assert_eq!(
+            <Fill as ScalarType>::parse(value).unwrap(),
+            Fill::Gradient(vec![Color { red: 255, green: 0, blue: 0 }, Color { red: 0, green: 0, blue: 255 }])
+        );
+
+        let value = async_graphql::to_value(serde_json::json!({ "r": 1, "g": 2, "b": 3 })).unwrap();
+
+        assert_eq!(<Fill as ScalarType>::parse(value).unwrap(), Fill::Color(Color { red: 1, green: 2, blue: 3 }));
+    }
+
+    #[test]
+    fn fill_is_validated() {
+        let parse = |json| <Fill as ScalarType>::parse(async_graphql::to_value(json).unwrap());
+
+        assert!(parse(serde_json::json!([])).is_err());
+        assert!(parse(serde_json::json!({ "type": "radial", "center": [5.0, 5.0], "stops": ["#fff"] })).is_err());
+        assert!(parse(serde_json::json!({ "rainbow": { "period": 0.0 } })).is_err());
+
+        for s in ["linear-gradient()", "radial-gradient(at 5 5, #fff)", "conic-gradient(from NaNdeg, #fff)", "rainbow(period 0)"] {
+            assert!(<Fill as ScalarType>::parse(string(s)).is_err(), "{}", s);
+        }
+    }
+}
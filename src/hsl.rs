@@ -0,0 +1,244 @@
+use crate::color::Color;
+
+/// Hue in degrees (`0.0..360.0`), saturation and lightness in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+/// Hue in degrees (`0.0..360.0`), saturation and value in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+fn to_unit(c: u8) -> f32 {
+    f32::from(c) / 255.0
+}
+
+/// Rounds half away from zero, so `0.5 / 255` lands on 1 rather than truncating to 0.
+fn from_unit(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn normalize_hue(hue: f32) -> f32 {
+    let hue = hue.rem_euclid(360.0);
+
+    // rem_euclid can return exactly 360.0 for tiny negative inputs
+    if hue >= 360.0 { 0.0 } else { hue }
+}
+
+/// Returns (hue, min, max) for the color, with hue in degrees.
+fn hue_min_max(color: &Color) -> (f32, f32, f32) {
+    let (r, g, b) = (to_unit(color.red), to_unit(color.green), to_unit(color.blue));
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (normalize_hue(hue), min, max)
+}
+
+/// Builds a color from chroma, the hue in degrees and the amount to add to every channel.
+fn from_chroma(chroma: f32, hue: f32, m: f32) -> Color {
+    let h = normalize_hue(hue) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match h as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    Color {
+        red: from_unit(r + m),
+        green: from_unit(g + m),
+        blue: from_unit(b + m),
+    }
+}
+
+impl Color {
+    pub fn to_hsl(&self) -> Hsl {
+        let (hue, min, max) = hue_min_max(self);
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Hsl { hue, saturation, lightness }
+    }
+
+    /// Saturation and lightness are clamped to `0.0..=1.0`; hue wraps around.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        let s = saturation.clamp(0.0, 1.0);
+        let l = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+
+        from_chroma(chroma, hue, l - chroma / 2.0)
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let (hue, min, max) = hue_min_max(self);
+
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        Hsv { hue, saturation, value: max }
+    }
+
+    /// Saturation and value are clamped to `0.0..=1.0`; hue wraps around.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+
+        let chroma = v * s;
+
+        from_chroma(chroma, hue, v - chroma)
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Self {
+        Color::from_hsl(hsl.hue, hsl.saturation, hsl.lightness)
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Self {
+        Color::from_hsv(hsv.hue, hsv.saturation, hsv.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.005, "expected {}, got {}", expected, actual);
+    }
+
+    fn assert_hsl(color: Color, hue: f32, saturation: f32, lightness: f32) {
+        let hsl = color.to_hsl();
+
+        assert_close(hsl.hue, hue);
+        assert_close(hsl.saturation, saturation);
+        assert_close(hsl.lightness, lightness);
+    }
+
+    fn assert_hsv(color: Color, hue: f32, saturation: f32, value: f32) {
+        let hsv = color.to_hsv();
+
+        assert_close(hsv.hue, hue);
+        assert_close(hsv.saturation, saturation);
+        assert_close(hsv.value, value);
+    }
+
+    mod hsl {
+        use super::*;
+
+        #[test]
+        fn primaries() {
+            assert_hsl(rgb(255, 0, 0), 0.0, 1.0, 0.5);
+            assert_hsl(rgb(0, 255, 0), 120.0, 1.0, 0.5);
+            assert_hsl(rgb(0, 0, 255), 240.0, 1.0, 0.5);
+        }
+
+        #[test]
+        fn grays() {
+            assert_hsl(rgb(0, 0, 0), 0.0, 0.0, 0.0);
+            assert_hsl(rgb(255, 255, 255), 0.0, 0.0, 1.0);
+            assert_hsl(rgb(128, 128, 128), 0.0, 0.0, 0.502);
+        }
+
+        #[test]
+        fn reference_values() {
+            // https://www.w3.org/TR/css-color-3/#hsl-color
+            assert_hsl(rgb(255, 0, 255), 300.0, 1.0, 0.5);
+            assert_hsl(rgb(0, 128, 128), 180.0, 1.0, 0.251);
+            assert_hsl(rgb(255, 165, 0), 38.824, 1.0, 0.5);
+            assert_hsl(rgb(70, 130, 180), 207.273, 0.44, 0.490);
+        }
+
+        #[test]
+        fn from_hsl() {
+            assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), rgb(255, 0, 0));
+            assert_eq!(Color::from_hsl(300.0, 1.0, 0.5), rgb(255, 0, 255));
+            assert_eq!(Color::from_hsl(180.0, 1.0, 0.25), rgb(0, 128, 128));
+            assert_eq!(Color::from_hsl(0.0, 0.0, 0.5), rgb(128, 128, 128));
+        }
+
+        #[test]
+        fn hue_wraps() {
+            assert_eq!(Color::from_hsl(360.0, 1.0, 0.5), rgb(255, 0, 0));
+            assert_eq!(Color::from_hsl(-120.0, 1.0, 0.5), rgb(0, 0, 255));
+        }
+
+        #[test]
+        fn clamps_out_of_range() {
+            assert_eq!(Color::from_hsl(0.0, 2.0, 1.5), rgb(255, 255, 255));
+            assert_eq!(Color::from_hsl(0.0, -1.0, -0.5), rgb(0, 0, 0));
+        }
+
+        #[test]
+        fn round_trips_every_gray_and_primary_ramp() {
+            for c in 0..=255 {
+                for color in [rgb(c, c, c), rgb(c, 0, 0), rgb(0, c, 0), rgb(0, 0, c)].iter() {
+                    assert_eq!(&Color::from(color.to_hsl()), color);
+                }
+            }
+        }
+    }
+
+    mod hsv {
+        use super::*;
+
+        #[test]
+        fn reference_values() {
+            assert_hsv(rgb(255, 0, 0), 0.0, 1.0, 1.0);
+            assert_hsv(rgb(0, 0, 0), 0.0, 0.0, 0.0);
+            assert_hsv(rgb(128, 128, 128), 0.0, 0.0, 0.502);
+            assert_hsv(rgb(255, 165, 0), 38.824, 1.0, 1.0);
+            assert_hsv(rgb(70, 130, 180), 207.273, 0.611, 0.706);
+        }
+
+        #[test]
+        fn from_hsv() {
+            assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), rgb(0, 255, 0));
+            assert_eq!(Color::from_hsv(240.0, 1.0, 0.5), rgb(0, 0, 128));
+            assert_eq!(Color::from_hsv(0.0, 0.0, 1.0), rgb(255, 255, 255));
+        }
+
+        #[test]
+        fn round_trips_every_gray_and_primary_ramp() {
+            for c in 0..=255 {
+                for color in [rgb(c, c, c), rgb(c, 0, 0), rgb(0, c, 0), rgb(0, 0, c)].iter() {
+                    assert_eq!(&Color::from(color.to_hsv()), color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::color::Color;
+use crate::consts::{
+    BRADFORD, BRADFORD_INVERSE, WHITE_A, WHITE_D50, WHITE_D55, WHITE_D65 as WHITE, WHITE_D75, WHITE_F11, WHITE_F2,
+    XYZ_TO_SRGB,
+};
+use crate::fill::Fill;
+use crate::lab::{linear_to_srgb, multiply, xyz_to_lab, Lab};
+
+/// Standard CIE illuminants, for judging how colors look under light other than the D65 an sRGB
+/// display assumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Illuminant {
+    /// Incandescent / tungsten light, about 2856 K.
+    A,
+
+    /// Horizon daylight, about 5000 K. The usual viewing condition for print proofs.
+    D50,
+
+    /// Mid-morning daylight, about 5500 K.
+    D55,
+
+    /// Noon daylight, about 6500 K. What sRGB colors are defined under.
+    D65,
+
+    /// North sky daylight, about 7500 K.
+    D75,
+
+    /// Cool white fluorescent.
+    F2,
+
+    /// Narrow band "TL84" fluorescent, common in shops.
+    F11,
+}
+
+/// Largest CIEDE2000 difference `appears_same_under` still counts as the same color. 2.3 is the
+/// usual figure for a just noticeable difference.
+const MAX_DELTA_E: f32 = 2.3;
+
+impl Illuminant {
+    /// XYZ of the white point for the CIE 1931 2° observer, normalized to Y = 1.
+    pub(crate) fn white_point(self) -> [f64; 3] {
+        match self {
+            Illuminant::A => WHITE_A,
+            Illuminant::D50 => WHITE_D50,
+            Illuminant::D55 => WHITE_D55,
+            Illuminant::D65 => WHITE,
+            Illuminant::D75 => WHITE_D75,
+            Illuminant::F2 => WHITE_F2,
+            Illuminant::F11 => WHITE_F11,
+        }
+    }
+}
+
+/// The temperature `Color::white_balance` leaves colors alone at: about that of D65, sRGB's white.
+const NEUTRAL_KELVIN: f32 = 6500.0;
+
+/// XYZ, normalized to Y = 1, of a black body at `kelvin`, clamped to `1667.0..=25000.0`. Uses Kim
+/// et al.'s cubic fit of the Planckian locus.
+fn planckian_white(kelvin: f32) -> [f64; 3] {
+    let t = f64::from(kelvin.clamp(1667.0, 25000.0));
+    let (t2, t3) = (t * t, t * t * t);
+
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+    };
+
+    let (x2, x3) = (x * x, x * x * x);
+
+    let y = if t <= 2222.0 {
+        -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+    };
+
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+/// Bradford chromatic adaptation of a D65 XYZ color to the given white point.
+fn adapt(xyz: [f64; 3], white: [f64; 3]) -> [f64; 3] {
+    adapt_between(xyz, WHITE, white)
+}
+
+/// Bradford chromatic adaptation of an XYZ color from one white point to another.
+fn adapt_between(xyz: [f64; 3], from: [f64; 3], to: [f64; 3]) -> [f64; 3] {
+    let source = multiply(&BRADFORD, from);
+    let destination = multiply(&BRADFORD, to);
+
+    let cone = multiply(&BRADFORD, xyz);
+    let scaled = [
+        cone[0] * destination[0] / source[0],
+        cone[1] * destination[1] / source[1],
+        cone[2] * destination[2] / source[2],
+    ];
+
+    multiply(&BRADFORD_INVERSE, scaled)
+}
+
+impl Color {
+    /// L\*a\*b\* of the color as seen by a viewer adapted to `illuminant`, relative to that
+    /// illuminant's white. Under `D65` this is the same as `to_lab`.
+    pub fn to_lab_under(&self, illuminant: Illuminant) -> Lab {
+        let white = illuminant.white_point();
+
+        xyz_to_lab(adapt(self.to_xyz(), white), white)
+    }
+
+    /// Whether the two colors are indistinguishable under `illuminant`: after Bradford chromatic
+    /// adaptation their CIEDE2000 difference is at most 2.3, about one just noticeable difference.
+    ///
+    /// The colors are only known as sRGB values, so this can't catch metamerism between two
+    /// physical materials; it answers whether the adapted colors are still close enough to pass as
+    /// the same.
+    pub fn appears_same_under(&self, other: &Color, illuminant: Illuminant) -> bool {
+        self.to_lab_under(illuminant).delta_e(&other.to_lab_under(illuminant)) <= MAX_DELTA_E
+    }
+
+    /// Shifts the color toward the white of a light at `kelvin`, the way it would look lit by it.
+    /// `6500.0` is neutral, lower is warmer and higher is cooler; temperatures outside
+    /// `1667.0..=25000.0` are clamped.
+    ///
+    /// The shift is a Bradford chromatic adaptation in linear light, between the black body
+    /// whites at 6500 K and at `kelvin`. Colors pushed out of gamut are clamped channel by
+    /// channel.
+    ///
+    /// ```
+    /// use led_json::Color;
+    ///
+    /// let white = Color::new(255, 255, 255);
+    /// let warm = white.white_balance(2700.0);
+    ///
+    /// assert_eq!(white.white_balance(6500.0), white);
+    /// assert!(warm.red > warm.green && warm.green > warm.blue);
+    /// ```
+    pub fn white_balance(&self, kelvin: f32) -> Color {
+        white_balance_with(self, planckian_white(NEUTRAL_KELVIN), planckian_white(kelvin))
+    }
+}
+
+fn white_balance_with(color: &Color, from: [f64; 3], to: [f64; 3]) -> Color {
+    let [r, g, b] = multiply(&XYZ_TO_SRGB, adapt_between(color.to_xyz(), from, to));
+
+    Color {
+        red: linear_to_srgb(r),
+        green: linear_to_srgb(g),
+        blue: linear_to_srgb(b),
+    }
+}
+
+impl Fill {
+    /// `Color::white_balance` on a solid color or every stop of a gradient, for a scene whose
+    /// light temperature is configured separately from its palette.
+    ///
+    /// The keyword fills are returned as they are; `Rainbow` is defined by its hue sweep rather
+    /// than by colors. A `Raw` string is another application's value, so it isn't rebalanced
+    /// either.
+    pub fn white_balance(&self, kelvin: f32) -> Fill {
+        let (from, to) = (planckian_white(NEUTRAL_KELVIN), planckian_white(kelvin));
+        let apply = |stops: &[Color]| stops.iter().map(|c| white_balance_with(c, from, to)).collect();
+
+        match self {
+            Fill::Rainbow(params) => Fill::Rainbow(*params),
+            Fill::None => Fill::None,
+            Fill::Transparent => Fill::Transparent,
+            Fill::Raw(raw) => Fill::Raw(raw.clone()),
+            Fill::Color(color) => Fill::Color(white_balance_with(color, from, to)),
+            Fill::Gradient(stops) => Fill::Gradient(apply(stops)),
+            Fill::Radial { center, stops } => Fill::Radial { center: *center, stops: apply(stops) },
+            Fill::Conic { angle, stops } => Fill::Conic { angle: *angle, stops: apply(stops) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Illuminant; 7] = [
+        Illuminant::A,
+        Illuminant::D50,
+        Illuminant::D55,
+        Illuminant::D65,
+        Illuminant::D75,
+        Illuminant::F2,
+        Illuminant::F11,
+    ];
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!((actual - expected).abs() < tolerance, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn d65_is_unchanged() {
+        let color = Color { red: 200, green: 30, blue: 90 };
+
+        let adapted = color.to_lab_under(Illuminant::D65);
+        let lab = color.to_lab();
+
+        assert_close(adapted.lightness, lab.lightness, 0.001);
+        assert_close(adapted.a, lab.a, 0.001);
+        assert_close(adapted.b, lab.b, 0.001);
+    }
+
+    #[test]
+    fn white_stays_white() {
+        let white = Color { red: 255, green: 255, blue: 255 };
+
+        for illuminant in ALL.iter() {
+            let lab = white.to_lab_under(*illuminant);
+
+            assert_close(lab.lightness, 100.0, 0.01);
+            assert_close(lab.a, 0.0, 0.01);
+            assert_close(lab.b, 0.0, 0.01);
+        }
+    }
+
+    #[test]
+    fn same_color() {
+        let color = Color { red: 0, green: 120, blue: 200 };
+
+        for illuminant in ALL.iter() {
+            assert!(color.appears_same_under(&color, *illuminant));
+        }
+    }
+
+    #[test]
+    fn nearly_the_same_color() {
+        let a = Color { red: 0, green: 120, blue: 200 };
+        let b = Color { red: 1, green: 121, blue: 200 };
+
+        for illuminant in ALL.iter() {
+            assert!(a.appears_same_under(&b, *illuminant));
+        }
+    }
+
+    mod white_balance {
+        use super::*;
+
+        const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+        const TEAL: Color = Color { red: 0, green: 128, blue: 128 };
+
+        #[test]
+        fn neutral_is_unchanged() {
+            assert_eq!(WHITE.white_balance(6500.0), WHITE);
+            assert_eq!(TEAL.white_balance(6500.0), TEAL);
+        }
+
+        #[test]
+        fn warm_and_cool() {
+            let warm = WHITE.white_balance(3000.0);
+            let cool = WHITE.white_balance(10000.0);
+
+            assert!(warm.red > warm.green && warm.green > warm.blue, "{:?}", warm);
+            assert!(cool.red < cool.green.min(cool.blue), "{:?}", cool);
+            assert!(TEAL.white_balance(3000.0).blue < TEAL.blue);
+        }
+
+        #[test]
+        fn daylight_is_close_to_neutral() {
+            let d50 = WHITE.white_balance(5000.0);
+
+            assert!(d50.red == 255 && d50.blue < 255 && d50.blue > 200, "{:?}", d50);
+        }
+
+        #[test]
+        fn clamps_temperature() {
+            assert_eq!(TEAL.white_balance(500.0), TEAL.white_balance(1667.0));
+            assert_eq!(TEAL.white_balance(100_000.0), TEAL.white_balance(25000.0));
+        }
+
+        #[test]
+        fn black_stays_black() {
+            assert_eq!(Color { red: 0, green: 0, blue: 0 }.white_balance(2000.0), Color { red: 0, green: 0, blue: 0 });
+        }
+
+        #[test]
+        fn fills() {
+            let gradient = Fill::Gradient(vec![WHITE, TEAL]);
+
+            assert_eq!(gradient.white_balance(2700.0), Fill::Gradient(vec![WHITE.white_balance(2700.0), TEAL.white_balance(2700.0)]));
+            assert_eq!(
+                Fill::Radial { center: (0.25, 0.5), stops: vec![TEAL] }.white_balance(9000.0),
+                Fill::Radial { center: (0.25, 0.5), stops: vec![TEAL.white_balance(9000.0)] }
+            );
+            assert_eq!(Fill::Color(WHITE).white_balance(6500.0), Fill::Color(WHITE));
+            assert_eq!(Fill::rainbow().white_balance(2700.0), Fill::rainbow());
+            assert_eq!(Fill::None.white_balance(2700.0), Fill::None);
+        }
+    }
+
+    #[test]
+    fn different_colors() {
+        let a = Color { red: 0, green: 120, blue: 200 };
+        let b = Color { red: 0, green: 140, blue: 200 };
+
+        for illuminant in ALL.iter() {
+            assert!(!a.appears_same_under(&b, *illuminant));
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::color::Color;
+use crate::consts::{
+    LAB_EPSILON as EPSILON, LAB_KAPPA as KAPPA, SRGB_DECODE_THRESHOLD, SRGB_ENCODE_THRESHOLD, SRGB_GAMMA, SRGB_OFFSET,
+    SRGB_SLOPE, SRGB_TO_XYZ, WHITE_D65 as WHITE, XYZ_TO_SRGB,
+};
+
+/// CIE L\*a\*b\* relative to the D65 white point. `lightness` is `0.0..=100.0`; `a` and `b` are
+/// roughly `-128.0..=128.0` for colors in the sRGB gamut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// Removes the sRGB transfer function, giving linear light in `0.0..=1.0`.
+pub(crate) fn srgb_to_linear(c: u8) -> f64 {
+    let c = f64::from(c) / 255.0;
+
+    if c <= SRGB_DECODE_THRESHOLD { c / SRGB_SLOPE } else { ((c + SRGB_OFFSET) / (1.0 + SRGB_OFFSET)).powf(SRGB_GAMMA) }
+}
+
+/// Applies the sRGB transfer function and rounds to the nearest channel value.
+pub(crate) fn linear_to_srgb(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= SRGB_ENCODE_THRESHOLD {
+        c * SRGB_SLOPE
+    } else {
+        (1.0 + SRGB_OFFSET) * c.powf(1.0 / SRGB_GAMMA) - SRGB_OFFSET
+    };
+
+    (c * 255.0).round() as u8
+}
+
+pub(crate) fn multiply(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+/// L\*a\*b\* of an XYZ color relative to the given white point.
+pub(crate) fn xyz_to_lab(xyz: [f64; 3], white: [f64; 3]) -> Lab {
+    let f = |t: f64| if t > EPSILON { t.cbrt() } else { (KAPPA * t + 16.0) / 116.0 };
+    let (fx, fy, fz) = (f(xyz[0] / white[0]), f(xyz[1] / white[1]), f(xyz[2] / white[2]));
+
+    Lab {
+        lightness: (116.0 * fy - 16.0) as f32,
+        a: (500.0 * (fx - fy)) as f32,
+        b: (200.0 * (fy - fz)) as f32,
+    }
+}
+
+impl Color {
+    /// CIE XYZ under D65, normalized so that white has Y = 1.
+    pub(crate) fn to_xyz(self) -> [f64; 3] {
+        let linear = [srgb_to_linear(self.red), srgb_to_linear(self.green), srgb_to_linear(self.blue)];
+
+        multiply(&SRGB_TO_XYZ, linear)
+    }
+
+    pub fn to_lab(&self) -> Lab {
+        xyz_to_lab(self.to_xyz(), WHITE)
+    }
+
+    /// Out of gamut values are clamped to the nearest sRGB color, channel by channel.
+    pub fn from_lab(lab: Lab) -> Color {
+        let fy = (f64::from(lab.lightness) + 16.0) / 116.0;
+        let fx = fy + f64::from(lab.a) / 500.0;
+        let fz = fy - f64::from(lab.b) / 200.0;
+
+        let f_inv = |f: f64| {
+            let cubed = f * f * f;
+
+            if cubed > EPSILON { cubed } else { (116.0 * f - 16.0) / KAPPA }
+        };
+
+        let xyz = [f_inv(fx) * WHITE[0], f_inv(fy) * WHITE[1], f_inv(fz) * WHITE[2]];
+        let [r, g, b] = multiply(&XYZ_TO_SRGB, xyz);
+
+        Color {
+            red: linear_to_srgb(r),
+            green: linear_to_srgb(g),
+            blue: linear_to_srgb(b),
+        }
+    }
+
+    /// CIEDE2000 color difference. Around 1.0 is the smallest difference most people can see.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        self.to_lab().delta_e(&other.to_lab())
+    }
+}
+
+impl From<Lab> for Color {
+    fn from(lab: Lab) -> Self {
+        Color::from_lab(lab)
+    }
+}
+
+impl Lab {
+    /// CIEDE2000 color difference, following Sharma, Wu and Dalal's implementation notes.
+    pub fn delta_e(&self, other: &Lab) -> f32 {
+        let (l1, a1, b1) = (f64::from(self.lightness), f64::from(self.a), f64::from(self.b));
+        let (l2, a2, b2) = (f64::from(other.lightness), f64::from(other.a), f64::from(other.b));
+
+        let pow7 = |x: f64| x.powi(7);
+        let twenty_five_7 = pow7(25.0);
+
+        let c_bar = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+        let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + twenty_five_7)).sqrt());
+
+        let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+
+        let hue = |b: f64, a: f64| {
+            if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) }
+        };
+        let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+        let delta_l = l2 - l1;
+        let delta_c = c2 - c1;
+
+        let delta_h = if c1 * c2 == 0.0 {
+            0.0
+        } else {
+            match h2 - h1 {
+                d if d > 180.0 => d - 360.0,
+                d if d < -180.0 => d + 360.0,
+                d => d,
+            }
+        };
+        let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h.to_radians() / 2.0).sin();
+
+        let l_bar = (l1 + l2) / 2.0;
+        let c_bar = (c1 + c2) / 2.0;
+
+        let h_bar = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let cos = |degrees: f64| degrees.to_radians().cos();
+        let t = 1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar)
+            + 0.32 * cos(3.0 * h_bar + 6.0) - 0.20 * cos(4.0 * h_bar - 63.0);
+
+        let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + twenty_five_7)).sqrt();
+
+        let s_l = 1.0 + (0.015 * (l_bar - 50.0).powi(2)) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar;
+        let s_h = 1.0 + 0.015 * c_bar * t;
+        let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+        let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+
+        (l * l + c * c + h * h + r_t * c * h).sqrt() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab(lightness: f32, a: f32, b: f32) -> Lab {
+        Lab { lightness, a, b }
+    }
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!((actual - expected).abs() < tolerance, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn to_lab() {
+        let white = Color { red: 255, green: 255, blue: 255 }.to_lab();
+
+        assert_close(white.lightness, 100.0, 0.01);
+        assert_close(white.a, 0.0, 0.01);
+        assert_close(white.b, 0.0, 0.01);
+
+        let red = Color { red: 255, green: 0, blue: 0 }.to_lab();
+
+        assert_close(red.lightness, 53.24, 0.01);
+        assert_close(red.a, 80.09, 0.01);
+        assert_close(red.b, 67.20, 0.01);
+    }
+
+    #[test]
+    fn round_trip() {
+        for c in (0..=255).step_by(5) {
+            let color = Color { red: c, green: 255 - c, blue: c / 3 };
+
+            assert_eq!(Color::from_lab(color.to_lab()), color);
+        }
+    }
+
+    #[test]
+    fn ciede2000_reference_pairs() {
+        // from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula", table 1
+        let pairs = [
+            (lab(50.0, 2.6772, -79.7751), lab(50.0, 0.0, -82.7485), 2.0425),
+            (lab(50.0, 3.1571, -77.2803), lab(50.0, 0.0, -82.7485), 2.8615),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.49, -0.001), lab(50.0, -2.49, 0.0011), 7.2195),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(50.0, 2.5, 0.0), lab(56.0, -27.0, -3.0), 31.9030),
+            (lab(60.2574, -34.0099, 36.2677), lab(60.4626, -34.1751, 39.4387), 1.2644),
+            (lab(22.7233, 20.0904, -46.6940), lab(23.0331, 14.9730, -42.5619), 2.0373),
+            (lab(2.0776, 0.0795, -1.1350), lab(0.9033, -0.0636, -0.5514), 0.9082),
+        ];
+
+        for (a, b, expected) in pairs.iter() {
+            assert_close(a.delta_e(b), *expected, 0.0005);
+            assert_close(b.delta_e(a), *expected, 0.0005);
+        }
+    }
+
+    #[test]
+    fn identical_colors() {
+        let color = Color { red: 12, green: 200, blue: 99 };
+
+        assert_eq!(color.delta_e(&color), 0.0);
+    }
+}
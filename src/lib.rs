@@ -0,0 +1,193 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod ansi;
+mod binary;
+mod color;
+mod custom;
+mod error;
+mod extension;
+mod fill;
+mod format;
+mod policy;
+mod rainbow;
+mod resolver;
+mod warning;
+
+pub mod consts;
+pub mod defaults;
+pub mod filled;
+
+// for macros, which can't name `alloc` in `no_std` crates that haven't declared it
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec;
+    pub use serde;
+}
+
+#[cfg(feature = "std")]
+mod adjust;
+
+#[cfg(feature = "std")]
+mod banding;
+
+#[cfg(feature = "std")]
+pub mod batch;
+
+#[cfg(feature = "std")]
+mod cache;
+
+#[cfg(feature = "std")]
+mod contrast;
+
+#[cfg(feature = "std")]
+mod curve;
+
+#[cfg(feature = "std")]
+mod distance;
+
+#[cfg(feature = "std")]
+mod dither;
+
+#[cfg(feature = "std")]
+mod find;
+
+#[cfg(feature = "std")]
+mod float;
+
+#[cfg(feature = "std")]
+mod gradient;
+
+#[cfg(feature = "std")]
+mod hsl;
+
+#[cfg(feature = "std")]
+mod illuminant;
+
+#[cfg(feature = "std")]
+mod lab;
+
+#[cfg(feature = "std")]
+mod oklab;
+
+#[cfg(feature = "std")]
+mod palette;
+
+#[cfg(feature = "std")]
+mod rng;
+
+#[cfg(feature = "std")]
+mod theme;
+
+#[cfg(feature = "std")]
+pub mod export;
+
+#[cfg(feature = "std")]
+pub mod spectral;
+
+#[cfg(feature = "async-graphql")]
+mod graphql;
+
+#[cfg(feature = "csscolorparser")]
+mod css;
+
+#[cfg(feature = "palette")]
+mod palette_interop;
+
+#[cfg(feature = "colorgrad")]
+mod colorgrad_interop;
+
+#[cfg(feature = "schemars")]
+mod schema;
+
+#[cfg(feature = "tokio")]
+mod load;
+
+#[cfg(feature = "notify")]
+mod watch;
+
+#[cfg(feature = "prost")]
+pub mod proto;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use ansi::{AnsiDepth, AnsiEscape, RESET};
+pub use color::{Color, ParseOptions};
+pub use custom::{CustomFill, FillKeyword};
+pub use error::{GradientError, ParseColorError, PolicyError, RainbowError};
+pub use extension::{FillInputExtension, InputExtensions, RawPrefix};
+
+#[deprecated(note = "renamed to `ParseColorError`")]
+pub type ColorParser = ParseColorError;
+pub use fill::{Fill, GradientRules};
+pub use filled::Filled;
+pub use format::{ColorFormat, Formatted};
+pub use policy::{Gamut, Policy, Validated};
+pub use rainbow::RainbowParams;
+pub use resolver::{ColorResolver, FillParser};
+pub use warning::{Warning, Warnings};
+
+#[cfg(feature = "std")]
+pub use adjust::ColorAdjustments;
+
+#[cfg(feature = "std")]
+pub use banding::Band;
+
+#[cfg(feature = "std")]
+pub use rainbow::RainbowFrames;
+
+#[cfg(feature = "std")]
+pub use cache::{ColorSeed, FillSeed, ParserCache};
+
+#[cfg(feature = "std")]
+pub use curve::{Channel, Curve};
+
+#[cfg(feature = "std")]
+pub use dither::Noise;
+
+#[cfg(feature = "std")]
+pub use error::{BuildError, ComponentError, CurveError, PaletteError, ThemeError};
+
+#[cfg(feature = "tokio")]
+pub use error::LoadError;
+
+#[cfg(feature = "std")]
+pub use export::{ExportFormat, Exporters};
+
+#[cfg(feature = "std")]
+pub use find::{find_fills, JsonPointer};
+
+#[cfg(feature = "std")]
+pub use float::{FloatColor, FloatPolicy, PolicySeed};
+
+#[cfg(feature = "std")]
+pub use gradient::GradientBuilder;
+
+#[cfg(feature = "std")]
+pub use hsl::{Hsl, Hsv};
+
+#[cfg(feature = "std")]
+pub use illuminant::Illuminant;
+
+#[cfg(feature = "std")]
+pub use lab::Lab;
+
+#[cfg(feature = "std")]
+pub use oklab::{Oklab, Oklch};
+
+#[cfg(feature = "std")]
+pub use palette::{Palette, PaletteFormat, Swatch};
+
+#[cfg(feature = "notify")]
+pub use watch::ThemeWatcher;
+
+#[cfg(feature = "colorgrad")]
+pub use colorgrad_interop::ColorgradPreset;
+
+#[cfg(feature = "std")]
+pub use theme::{Adjustment, ContrastConstraint, NameOrder, Ordered, Role, SemanticTheme, SlotValue, Solution, Style, Theme, UnresolvedTheme};
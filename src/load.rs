@@ -0,0 +1,127 @@
+//! Async helpers for reading themes and fills from files, for services that load them at startup.
+
+use std::path::Path;
+
+use crate::error::LoadError;
+use crate::fill::Fill;
+use crate::theme::Theme;
+
+fn invalid(format: &'static str, message: impl ToString) -> LoadError {
+    LoadError::Invalid { format, message: message.to_string() }
+}
+
+/// Parses a theme in whichever format the extension of `path` names.
+pub(crate) fn parse_theme(path: &Path, contents: &str) -> std::result::Result<Theme, LoadError> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("json") => serde_json::from_str(contents).map_err(|e| invalid("JSON", e)),
+
+        #[cfg(feature = "toml")]
+        Some("toml") => toml::from_str(contents).map_err(|e| invalid("TOML", e)),
+
+        #[cfg(feature = "yaml")]
+        Some("yaml") | Some("yml") => serde_yaml::from_str(contents).map_err(|e| invalid("YAML", e)),
+
+        _ => Err(LoadError::UnknownExtension(path.to_path_buf())),
+    }
+}
+
+impl Theme {
+    /// Reads a theme file. `.json` is always understood, `.toml` with the `toml` feature and
+    /// `.yaml` or `.yml` with the `yaml` feature.
+    pub async fn load(path: impl AsRef<Path>) -> std::result::Result<Theme, LoadError> {
+        let path = path.as_ref();
+        let contents = tokio::fs::read_to_string(path).await?;
+
+        parse_theme(path, &contents)
+    }
+}
+
+impl Fill {
+    /// Reads a file holding a single fill as JSON, whatever its extension.
+    pub async fn load_json(path: impl AsRef<Path>) -> std::result::Result<Fill, LoadError> {
+        let contents = tokio::fs::read_to_string(path).await?;
+
+        serde_json::from_str(&contents).map_err(|e| invalid("JSON", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    use crate::color::Color;
+
+    /// A file in the temp directory holding `contents`, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("led-json-{}-{}", std::process::id(), name));
+            std::fs::write(&path, contents).unwrap();
+
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn theme() -> Theme {
+        let mut theme = Theme::new();
+        theme.set("background", Color { red: 0, green: 0, blue: 0 });
+
+        theme
+    }
+
+    #[tokio::test]
+    async fn load_json_theme() {
+        let file = TempFile::new("theme.JSON", r##"{ "colors": { "background": "#000" } }"##);
+
+        assert_eq!(Theme::load(&file.0).await.unwrap(), theme());
+    }
+
+    #[cfg(feature = "toml")]
+    #[tokio::test]
+    async fn load_toml_theme() {
+        let file = TempFile::new("theme.toml", "[colors]\nbackground = \"#000\"\n");
+
+        assert_eq!(Theme::load(&file.0).await.unwrap(), theme());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[tokio::test]
+    async fn load_yaml_theme() {
+        let file = TempFile::new("theme.yml", "colors:\n  background: \"#000\"\n");
+
+        assert_eq!(Theme::load(&file.0).await.unwrap(), theme());
+    }
+
+    #[tokio::test]
+    async fn load_errors() {
+        let file = TempFile::new("theme.txt", "");
+
+        assert!(matches!(Theme::load(&file.0).await, Err(LoadError::UnknownExtension(p)) if p == file.0));
+
+        let file = TempFile::new("bad.json", r#"{ "colors": { "background": "black" } }"#);
+
+        assert!(matches!(Theme::load(&file.0).await, Err(LoadError::Invalid { format: "JSON", .. })));
+        assert!(matches!(Theme::load("/does/not/exist.json").await, Err(LoadError::Io(_))));
+    }
+
+    #[tokio::test]
+    async fn load_fill() {
+        let file = TempFile::new("fill.conf", r##"["#fff", "#000"]"##);
+
+        assert_eq!(Fill::load_json(&file.0).await.unwrap(), Fill::Gradient(vec![
+            Color { red: 255, green: 255, blue: 255 },
+            Color { red: 0, green: 0, blue: 0 },
+        ]));
+    }
+}
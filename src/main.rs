@@ -1,357 +1,416 @@
-use serde::{Serialize, Serializer, Deserialize, Deserializer};
-use serde_json::Result;
-use serde_json::json;
-use serde::de::{self, Visitor, SeqAccess};
-use serde::ser::SerializeSeq;
-
-use std::str::FromStr;
-use std::marker::PhantomData;
+//! `color-tool`: checks and rewrites config files full of fills, for linting them in CI.
+//!
+//! Every value in a file is taken to be a fill, or an object (a table in TOML) of more of them.
+//! Files are JSON, YAML or TOML, going by their extension or `--from`; without a file, standard
+//! input is read as JSON. `export` reads a `Theme` instead and writes it with one of the
+//! `Exporters::builtin()` formats.
+
 use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use led_json::export::Exporters;
+use led_json::{Color, ColorFormat, Fill, Formatted, JsonPointer, Theme};
+
+#[derive(Parser)]
+#[command(name = "color-tool", version, about = "Validates and normalizes config files full of fills")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-use thiserror::Error;
+#[derive(Subcommand)]
+enum Command {
+    /// Checks that every fill parses, printing each error with its location.
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
-#[derive(Deserialize, Serialize, Debug)]
-struct MyData {
-    pub color: Fill,
-}
+        #[arg(long, value_enum)]
+        from: Option<Syntax>,
+    },
 
-#[derive(Error, Debug, PartialEq)]
-enum ColorParser {
-    #[error("Missing leading '#' descriptor")]
-    MissingPrefix,
+    /// Prints the file with every fill rewritten in canonical form.
+    Normalize {
+        file: Option<PathBuf>,
 
-    #[error("Invalid length")]
-    InvalidLength(usize),
-}
+        #[arg(long, value_enum)]
+        from: Option<Syntax>,
 
-#[derive(Debug, PartialEq)]
-struct Color {
-    pub red: u8,
-    pub green: u8,
-    pub blue: u8,
+        #[arg(long, value_enum, default_value_t)]
+        format: Style,
+    },
+
+    /// Prints the file in another syntax, with its fills normalized.
+    Convert {
+        file: Option<PathBuf>,
+
+        #[arg(long, value_enum)]
+        from: Option<Syntax>,
+
+        #[arg(long, value_enum)]
+        to: Syntax,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: Style,
+    },
+
+    /// Prints a theme file in an export format, picked by name or file extension, like `css` or
+    /// `gpl`.
+    Export {
+        file: Option<PathBuf>,
+
+        #[arg(long, value_enum)]
+        from: Option<Syntax>,
+
+        #[arg(long)]
+        format: String,
+    },
 }
 
-impl fmt::Display for Color {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Syntax {
+    Json,
+    Yaml,
+    Toml,
 }
 
+/// The `ColorFormat` colors are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+enum Style {
+    /// `#ff00aa`
+    #[default]
+    LongHex,
+
+    /// `#f0a` where possible
+    ShortHex,
+
+    /// `#FF00AA`
+    UpperHex,
 
-impl FromStr for Color {
-    type Err = Box<dyn std::error::Error>;
+    /// `#F0A` where possible
+    ShortUpperHex,
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let len = s.len();
+    /// `rgb(255, 0, 170)`
+    Rgb,
+}
 
-        if s.chars().nth(0) != Some('#') {
-            return Err(ColorParser::MissingPrefix.into())
+impl From<Style> for ColorFormat {
+    fn from(style: Style) -> Self {
+        match style {
+            Style::LongHex => ColorFormat::LongHex,
+            Style::ShortHex => ColorFormat::ShortHexIfPossible,
+            Style::UpperHex => ColorFormat::UpperHex,
+            Style::ShortUpperHex => ColorFormat::ShortUpperHexIfPossible,
+            Style::Rgb => ColorFormat::RgbFunction,
         }
+    }
+}
 
-        let s = &s[1..];
-
-        let (red, green, blue) =
-            match len {
-                4 => {
-                    // 17 * c
-                    let red = 17 * u8::from_str_radix(&s[0..1], 16)?;
-                    let green = 17 * u8::from_str_radix(&s[1..2], 16)?;
-                    let blue = 17 * u8::from_str_radix(&s[2..3], 16)?;
-
-                    (red, green, blue)
-                },
-                7 => {
-                    // parse the double-digit hex value
-                    let red = u8::from_str_radix(&s[0..=1], 16)?;
-                    let green = u8::from_str_radix(&s[2..=3], 16)?;
-                    let blue = u8::from_str_radix(&s[4..=5], 16)?;
-
-                    (red, green, blue)
-                },
-                len => {
-                    return Err(ColorParser::InvalidLength(len).into())
-                }
-            };
+impl Syntax {
+    fn detect(path: &Path) -> Option<Syntax> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Syntax::Json),
+            "yaml" | "yml" => Some(Syntax::Yaml),
+            "toml" => Some(Syntax::Toml),
+            _ => None,
+        }
+    }
 
-        Ok(Color {
-            red,
-            green,
-            blue,
-        })
+    fn read(self, text: &str) -> Result<Value, String> {
+        match self {
+            Syntax::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Syntax::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+            Syntax::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn write(self, value: &Value) -> Result<String, String> {
+        match self {
+            Syntax::Json => serde_json::to_string_pretty(value).map(|json| json + "\n").map_err(|e| e.to_string()),
+            Syntax::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            Syntax::Toml => toml::to_string(value).map_err(|e| e.to_string()),
+        }
     }
 }
 
+/// A fill that didn't parse, at its JSON pointer like `/theme/accent/1`, as `find_fills` reports
+/// fills.
 #[derive(Debug, PartialEq)]
-// #[serde(untagged)]
-enum Fill {
-    Rainbow,
-    Color(Color),
-    Gradient(Vec<Color>),
+struct Problem {
+    path: JsonPointer,
+    message: String,
 }
 
-impl FromStr for Fill {
-    type Err = Box<dyn std::error::Error>;
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let res = match s {
-            "rainbow" => Fill::Rainbow,
-            s => Fill::Color(Color::from_str(s)?),
-        };
+/// Whether an object that isn't a valid fill was meant to be one, as opposed to an object of
+/// fills.
+fn meant_as_fill(map: &Map<String, Value>) -> bool {
+    const FIELDS: [&str; 5] = ["type", "stops", "center", "angle", "rainbow"];
+    const CHANNELS: [&str; 6] = ["r", "g", "b", "red", "green", "blue"];
 
-        Ok(res)
-    }
+    map.iter().any(|(key, value)| {
+        FIELDS.contains(&key.as_str()) || (CHANNELS.contains(&key.as_str()) && value.is_number())
+    })
 }
 
-impl<'de> Deserialize<'de> for Fill {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        // This is a Visitor that forwards string types to T's `FromStr` impl and
-        // forwards map types to T's `Deserialize` impl. The `PhantomData` is to
-        // keep the compiler from complaining about T being an unused generic type
-        // parameter. We need T in order to know the Value type for the Visitor
-        // impl.
-        struct StringOrVec<Fill>(PhantomData<fn() -> Fill>);
-
-        impl<'de> Visitor<'de> for StringOrVec<Fill>
-        {
-            type Value = Fill;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("string or array")
+/// `value` with each fill in it written in `format`. What doesn't parse is left as it is and
+/// added to `problems`.
+fn normalize(value: &Value, path: &JsonPointer, format: ColorFormat, problems: &mut Vec<Problem>) -> Value {
+    let err = match Fill::deserialize(value) {
+        Ok(fill) => return serde_json::to_value(Formatted::new(&fill, format)).expect("fills serialize to JSON"),
+        Err(err) => err,
+    };
+
+    match value {
+        Value::Object(map) if !meant_as_fill(map) => {
+            return Value::Object(map.iter()
+                .map(|(key, value)| (key.clone(), normalize(value, &path.join(key), format, problems)))
+                .collect())
+        },
+        Value::Array(stops) => {
+            // point at the stops that are wrong, if it's them
+            let before = problems.len();
+
+            for (i, stop) in stops.iter().enumerate() {
+                if let Err(err) = Color::deserialize(stop) {
+                    problems.push(Problem { path: path.join(&i.to_string()), message: err.to_string() });
+                }
             }
 
-            fn visit_str<E>(self, value: &str) -> std::result::Result<Fill, E>
-            where
-                E: de::Error,
-            {
-                Ok(FromStr::from_str(value).unwrap())
+            if problems.len() == before {
+                problems.push(Problem { path: path.clone(), message: err.to_string() });
             }
+        },
+        _ => problems.push(Problem { path: path.clone(), message: err.to_string() }),
+    }
 
-            fn visit_seq<S>(self, mut seq: S) -> std::result::Result<Fill, S::Error>
-            where
-                S: SeqAccess<'de>,
-            {
-                // `MapAccessDeserializer` is a wrapper that turns a `MapAccess`
-                // into a `Deserializer`, allowing it to be used as the input to T's
-                // `Deserialize` implementation. T then deserializes itself using
-                // the entries from the map visitor.
+    value.clone()
+}
 
-                let mut colors: Vec<Color> = vec![];
+/// The file, or standard input, and the syntax it's in.
+fn read(file: Option<&Path>, from: Option<Syntax>) -> Result<(Value, Syntax), String> {
+    let (text, detected) = match file {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
 
-                while let Some(c) = seq.next_element()? {
-                    colors.push(FromStr::from_str(c).unwrap());
-                }
+            (text, Syntax::detect(path))
+        },
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map_err(|e| e.to_string())?;
 
-                Ok(Fill::Gradient(colors))
+            (text, None)
+        },
+    };
 
-                // Deserialize::deserialize(de::value::SeqAccessDeserializer::new(seq))
-            }
-        }
+    let syntax = from.or(detected).unwrap_or(Syntax::Json);
 
-        deserializer.deserialize_any(StringOrVec(PhantomData))
-    }
+    Ok((syntax.read(&text)?, syntax))
 }
 
-impl Serialize for Fill
-{
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        // serializer.serialize_str("foo")
-        
-        match self {
-            Fill::Rainbow => serializer.serialize_str("rainbow"),
-            Fill::Color(color) => {
-                serializer.serialize_str(&format!{"{}", color})
-            },
-            Fill::Gradient(colors) => {
-                let mut s = serializer.serialize_seq(Some(colors.len()))?;
-                for c in colors {
-                    s.serialize_element(&format!("{}", c))?;
-                }
-
-                s.end()
-            }
-        }
-    }
+fn name(file: Option<&Path>) -> String {
+    file.map_or_else(|| "<stdin>".into(), |path| path.display().to_string())
 }
 
-fn main() -> Result<()> {
-    let data = r##"
-        { "color": "rainbow" }
-    "##;
+/// Normalizes the file and prints it in `to`, or the syntax it was in.
+fn rewrite(file: Option<&Path>, from: Option<Syntax>, to: Option<Syntax>, style: Style) -> bool {
+    let (value, syntax) = match read(file, from) {
+        Ok(read) => read,
+        Err(err) => {
+            eprintln!("{}: {}", name(file), err);
 
-    // Parse the string of data into serde_json::Value.
-    let v: MyData = serde_json::from_str(data)?;
+            return false
+        },
+    };
 
-    // Access parts of the data by indexing with square brackets.
-    println!("color: {:?}", v.color);
+    let mut problems = vec![];
+    let normalized = normalize(&value, &JsonPointer::default(), style.into(), &mut problems);
 
-    let json = json!(v);
+    if !problems.is_empty() {
+        for problem in problems {
+            eprintln!("{}: {}", name(file), problem);
+        }
 
-    println!("json: {}", json);
+        return false
+    }
 
-    Ok(())
-}
+    match to.unwrap_or(syntax).write(&normalized) {
+        Ok(text) => {
+            print!("{}", text);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            true
+        },
+        Err(err) => {
+            eprintln!("{}: {}", name(file), err);
 
-    mod deserialize {
-        use super::*;
+            false
+        },
+    }
+}
 
-        #[test]
-        fn rainbow() {
-            let data = r##"
-                { "color": "rainbow" }
-            "##;
+/// The theme in `value`, written in the export format called `format`, or with that extension.
+fn export_theme(value: &Value, format: &str) -> Result<Vec<u8>, String> {
+    let exporters = Exporters::builtin();
 
-            let v: MyData = serde_json::from_str(data).unwrap();
+    let exporter = exporters.get(format).or_else(|| exporters.by_extension(format)).ok_or_else(|| {
+        format!("unknown export format {:?}, expected one of {}", format, exporters.names().collect::<Vec<_>>().join(", "))
+    })?;
 
-            assert_eq!(v.color, Fill::Rainbow);
-        }
+    let theme = Theme::deserialize(value).map_err(|e| e.to_string())?;
 
-        #[test]
-        fn short_color() {
-            let data = r##"
-                { "color": "#f0f" }
-            "##;
+    let mut out = vec![];
+    exporter.write_theme(&theme, &mut out).map_err(|e| e.to_string())?;
 
-            let v: MyData = serde_json::from_str(data).unwrap();
+    Ok(out)
+}
 
-            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
-        }
+fn export(file: Option<&Path>, from: Option<Syntax>, format: &str) -> bool {
+    let exported = read(file, from).and_then(|(value, _)| export_theme(&value, format));
 
-        #[test]
-        fn long_color() {
-            let data = r##"
-                { "color": "#ff00ff" }
-            "##;
+    match exported.and_then(|out| io::stdout().write_all(&out).map_err(|e| e.to_string())) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("{}: {}", name(file), err);
 
-            let v: MyData = serde_json::from_str(data).unwrap();
+            false
+        },
+    }
+}
 
-            assert_eq!(v.color, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
-        }
+fn validate(files: &[PathBuf], from: Option<Syntax>) -> bool {
+    let mut valid = true;
 
-        #[test]
-        fn gradient() {
-            let data = r##"
-                { "color": [ "#fff", "#00ff00", "#00f" ] }
-            "##;
+    for file in files {
+        let problems = match read(Some(file), from) {
+            Ok((value, _)) => {
+                let mut problems = vec![];
+                normalize(&value, &JsonPointer::default(), ColorFormat::LongHex, &mut problems);
 
-            let v: MyData = serde_json::from_str(data).unwrap();
+                problems.iter().map(Problem::to_string).collect()
+            },
+            Err(err) => vec![err],
+        };
 
-            assert_eq!(v.color, Fill::Gradient(vec![
-                Color { red: 255, green: 255, blue: 255 },
-                Color { red: 0, green: 255, blue: 0 },
-                Color { red: 0, green: 0, blue: 255 },
-            ]));
+        for problem in &problems {
+            println!("{}: {}", file.display(), problem);
         }
 
-        #[test]
-        #[should_panic]
-        fn arbitrary_string_fails() {
-            let data = r##"
-                { "color": "hello" }
-            "##;
+        valid &= problems.is_empty();
+    }
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    valid
+}
 
-        #[test]
-        #[should_panic]
-        fn short_string_fails() {
-            let data = r##"
-                { "color": "#f" }
-            "##;
+fn main() -> ExitCode {
+    let ok = match Cli::parse().command {
+        Command::Validate { files, from } => validate(&files, from),
+        Command::Normalize { file, from, format } => rewrite(file.as_deref(), from, None, format),
+        Command::Convert { file, from, to, format } => rewrite(file.as_deref(), from, Some(to), format),
+        Command::Export { file, from, format } => export(file.as_deref(), from, &format),
+    };
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
 
-        #[test]
-        #[should_panic]
-        fn too_long_of_string_fails() {
-            let data = r##"
-                { "color": "#fffffffffffffff" }
-            "##;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    use serde_json::json;
 
-        #[test]
-        #[should_panic]
-        fn rainbow_in_gradient_fails() {
-            let data = r##"
-                { "color": ["rainbow"] }
-            "##;
+    use led_json::find_fills;
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    fn problems(value: Value) -> Vec<String> {
+        let mut problems = vec![];
+        normalize(&value, &JsonPointer::default(), ColorFormat::LongHex, &mut problems);
 
-        #[test]
-        #[should_panic]
-        fn arbitrary_string_in_gradient_fails() {
-            let data = r##"
-                { "color": ["hello"] }
-            "##;
+        problems.into_iter().map(|p| p.path.to_string()).collect()
+    }
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    #[test]
+    fn paths() {
+        let value = json!({ "two words": { "a/b": ["#fff", "#ggg"] }, "1st": "#ggg" });
 
-        #[test]
-        #[should_panic]
-        fn short_string_in_gradient_fails() {
-            let data = r##"
-                { "color": ["#f"] }
-            "##;
+        assert_eq!(problems(value.clone()), vec!["/1st", "/two words/a~1b/1"]);
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+        // the same pointers `find_fills` gives, and that `Value::pointer` follows
+        let found: Vec<String> = find_fills(&value).into_iter().map(|(pointer, _)| pointer.to_string()).collect();
 
-        #[test]
-        #[should_panic]
-        fn long_string_in_gradient_fails() {
-            let data = r##"
-                { "color": ["#fffffffffffffff"] }
-            "##;
+        assert_eq!(found, ["/two words/a~1b/0"]);
+        assert!(value.pointer("/two words/a~1b/1").is_some());
+    }
 
-            serde_json::from_str::<MyData>(data).unwrap();
-        }
+    #[test]
+    fn normalizes_nested_fills() {
+        let value = json!({
+            "background": "#FFF",
+            "theme": { "accent": ["#f00", "#00F"], "glow": { "r": 255, "g": 0, "b": 170 } },
+            "idle": "rainbow",
+        });
+
+        let mut problems = vec![];
+        let normalized = normalize(&value, &JsonPointer::default(), ColorFormat::ShortHexIfPossible, &mut problems);
+
+        assert_eq!(problems, vec![]);
+        assert_eq!(normalized, json!({
+            "background": "#fff",
+            "theme": { "accent": ["#f00", "#00f"], "glow": "#f0a" },
+            "idle": "rainbow",
+        }));
     }
 
-    mod serialize {
-        use super::*;
+    #[test]
+    fn locates_problems() {
+        let value = json!({
+            "background": "#ggg",
+            "theme": { "accent": ["#f00", "red", "#00f"], "glow": { "type": "radial", "stops": [] } },
+            "ok": "#000",
+        });
 
-        #[test]
-        fn rainbow() {
-            let json = json!(MyData { color: Fill::Rainbow });
+        assert_eq!(problems(value), vec!["/background", "/theme/accent/1", "/theme/glow"]);
+    }
 
-            assert_eq!(json.to_string(), r##"{"color":"rainbow"}"##)
-        }
+    #[test]
+    fn objects_of_colors_by_channel_name() {
+        // a theme that happens to have a `red` entry isn't a color object
+        assert_eq!(problems(json!({ "red": "#f00", "blue": ["#00f", "#00e"] })), Vec::<String>::new());
+        assert_eq!(problems(json!({ "red": 300, "green": 0, "blue": 0 })), vec![""]);
+    }
 
-        #[test]
-        fn color() {
-            let json = json!(MyData { color: Fill::Color(Color { red: 255, green: 255, blue: 255 })});
+    #[test]
+    fn syntaxes() {
+        let value = Syntax::Toml.read("[theme]\naccent = \"#FF00AA\"\n").unwrap();
+        let normalized = normalize(&value, &JsonPointer::default(), ColorFormat::LongHex, &mut vec![]);
 
-            assert_eq!(json.to_string(), r##"{"color":"#ffffff"}"##);
+        assert_eq!(Syntax::Yaml.write(&normalized).unwrap(), "theme:\n  accent: '#ff00aa'\n");
+        assert_eq!(Syntax::Json.read(&Syntax::Json.write(&normalized).unwrap()).unwrap(), normalized);
+        assert_eq!(Syntax::detect(Path::new("config.yml")), Some(Syntax::Yaml));
+        assert_eq!(Syntax::detect(Path::new("config")), None);
+    }
 
-            let json = json!(MyData { color: Fill::Color(Color { red: 15, green: 0, blue: 255 })});
+    #[test]
+    fn exports_themes() {
+        let theme = json!({ "name": "Night", "colors": { "accent": "$background", "background": "#000" } });
+        let css = "/* Night */\n:root {\n  --accent: #000000;\n  --background: #000000;\n}\n";
 
-            assert_eq!(json.to_string(), r##"{"color":"#0f00ff"}"##)
-        }
+        assert_eq!(String::from_utf8(export_theme(&theme, "css").unwrap()).unwrap(), css);
+        assert_eq!(String::from_utf8(export_theme(&theme, ".scss").unwrap()).unwrap(), "// Night\n$accent: #000000;\n$background: #000000;\n");
 
-        #[test]
-        fn gradient() {
-            let json = json!(MyData { color: Fill::Gradient(vec![
-                Color { red: 255, green: 255, blue: 255 },
-                Color { red: 15, green: 0, blue: 255 },
-            ])});
+        let err = export_theme(&theme, "pdf").unwrap_err();
 
-            assert_eq!(json.to_string(), r##"{"color":["#ffffff","#0f00ff"]}"##)
-        }
+        assert!(err.contains("css, scss, gpl"), "{}", err);
+        assert!(export_theme(&json!({ "colors": { "accent": "rainbow" } }), "css").is_err());
     }
 }
@@ -0,0 +1,272 @@
+//! Oklab, and its polar form Oklch, where lightness can change without the hue drifting the way
+//! it does in HSL. Conversions follow Björn Ottosson's reference implementation.
+
+use crate::color::Color;
+use crate::consts::{LMS_TO_OKLAB, LMS_TO_SRGB, OKLAB_TO_LMS, SRGB_TO_LMS};
+use crate::fill::Fill;
+use crate::lab::{linear_to_srgb, multiply, srgb_to_linear};
+
+/// Lightness in `0.0..=1.0`; `a` (green to red) and `b` (blue to yellow) are within about
+/// `-0.4..=0.4` for colors in the sRGB gamut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// Lightness in `0.0..=1.0`, chroma from `0.0` up to about `0.37` for colors in the sRGB gamut,
+/// and hue in degrees (`0.0..360.0`). Grays have a chroma of `0.0` and a hue of `0.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub lightness: f32,
+    pub chroma: f32,
+    pub hue: f32,
+}
+
+// below this, a color is a gray and its hue is noise
+const ACHROMATIC: f64 = 1e-4;
+
+/// Oklab of a linear sRGB color.
+pub(crate) fn from_linear(linear: [f64; 3]) -> Oklab {
+    let [l, m, s] = multiply(&SRGB_TO_LMS, linear);
+    let [lightness, a, b] = multiply(&LMS_TO_OKLAB, [l.cbrt(), m.cbrt(), s.cbrt()]);
+
+    Oklab { lightness: lightness as f32, a: a as f32, b: b as f32 }
+}
+
+/// Linear sRGB for the Oklab color, which may be outside `0.0..=1.0`.
+pub(crate) fn to_linear(oklab: &Oklab) -> [f64; 3] {
+    let lab = [f64::from(oklab.lightness), f64::from(oklab.a), f64::from(oklab.b)];
+    let [l, m, s] = multiply(&OKLAB_TO_LMS, lab);
+
+    multiply(&LMS_TO_SRGB, [l * l * l, m * m * m, s * s * s])
+}
+
+/// The nearest sRGB color to a linear one, clamped channel by channel.
+pub(crate) fn clamp_linear([r, g, b]: [f64; 3]) -> Color {
+    Color {
+        red: linear_to_srgb(r),
+        green: linear_to_srgb(g),
+        blue: linear_to_srgb(b),
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(oklab: Oklab) -> Self {
+        let (a, b) = (f64::from(oklab.a), f64::from(oklab.b));
+        let chroma = a.hypot(b);
+        let hue = if chroma < ACHROMATIC { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+
+        Oklch { lightness: oklab.lightness, chroma: chroma as f32, hue: hue as f32 }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(oklch: Oklch) -> Self {
+        let (chroma, hue) = (f64::from(oklch.chroma), f64::from(oklch.hue).to_radians());
+
+        Oklab { lightness: oklch.lightness, a: (chroma * hue.cos()) as f32, b: (chroma * hue.sin()) as f32 }
+    }
+}
+
+impl Color {
+    pub fn to_oklab(&self) -> Oklab {
+        from_linear([srgb_to_linear(self.red), srgb_to_linear(self.green), srgb_to_linear(self.blue)])
+    }
+
+    /// Out of gamut values are clamped to the nearest sRGB color, channel by channel.
+    pub fn from_oklab(oklab: Oklab) -> Color {
+        clamp_linear(to_linear(&oklab))
+    }
+
+    pub fn to_oklch(&self) -> Oklch {
+        self.to_oklab().into()
+    }
+
+    /// Out of gamut values are clamped to the nearest sRGB color, channel by channel.
+    pub fn from_oklch(oklch: Oklch) -> Color {
+        Color::from_oklab(oklch.into())
+    }
+
+    /// The color with its Oklch lightness flipped, `1.0 - L`, and the same hue and chroma, so
+    /// dark blue becomes light blue rather than the orange `invert` gives. Where the flipped
+    /// color is outside the sRGB gamut, it loses as little chroma as it takes to fit.
+    pub fn invert_lightness(&self) -> Color {
+        let oklch = self.to_oklch();
+        let flipped = Oklch { lightness: (1.0 - oklch.lightness).clamp(0.0, 1.0), ..oklch };
+
+        let fits = |chroma: f32| {
+            to_linear(&Oklch { chroma, ..flipped }.into()).iter().all(|c| (-1e-4..=1.0 + 1e-4).contains(c))
+        };
+
+        if fits(flipped.chroma) {
+            return Color::from_oklch(flipped)
+        }
+
+        // the largest chroma that fits, which is all of the gamut there is at this hue and lightness
+        let (mut low, mut high) = (0.0, flipped.chroma);
+
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+
+            if fits(mid) { low = mid } else { high = mid }
+        }
+
+        Color::from_oklch(Oklch { chroma: low, ..flipped })
+    }
+}
+
+impl From<Oklab> for Color {
+    fn from(oklab: Oklab) -> Self {
+        Color::from_oklab(oklab)
+    }
+}
+
+impl From<Oklch> for Color {
+    fn from(oklch: Oklch) -> Self {
+        Color::from_oklch(oklch)
+    }
+}
+
+impl Fill {
+    /// `Color::invert_lightness` on a solid color or every stop of a gradient, keeping the stops in
+    /// order, for deriving a dark theme from a light one.
+    ///
+    /// A `Rainbow` has no stops, so its HSL lightness is flipped instead. The other keyword fills
+    /// are returned as they are. A `Raw` string is too: it may not be a color at all.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill};
+    ///
+    /// let fill = Fill::Gradient(vec![Color::new(255, 255, 255), Color::new(0, 0, 0)]);
+    ///
+    /// assert_eq!(fill.invert_lightness(), Fill::Gradient(vec![Color::new(0, 0, 0), Color::new(255, 255, 255)]));
+    /// ```
+    pub fn invert_lightness(&self) -> Fill {
+        let apply = |stops: &[Color]| stops.iter().map(Color::invert_lightness).collect();
+
+        match self {
+            Fill::Rainbow(params) => {
+                let mut params = *params;
+                params.lightness = 1.0 - params.lightness;
+
+                Fill::Rainbow(params)
+            },
+            Fill::None => Fill::None,
+            Fill::Transparent => Fill::Transparent,
+            Fill::Raw(raw) => Fill::Raw(raw.clone()),
+            Fill::Color(color) => Fill::Color(color.invert_lightness()),
+            Fill::Gradient(stops) => Fill::Gradient(apply(stops)),
+            Fill::Radial { center, stops } => Fill::Radial { center: *center, stops: apply(stops) },
+            Fill::Conic { angle, stops } => Fill::Conic { angle: *angle, stops: apply(stops) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::rainbow::RainbowParams;
+
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+    const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+
+    /// A spread of colors across the whole cube.
+    fn samples() -> impl Iterator<Item = Color> {
+        (0..=255).step_by(51).flat_map(|r| {
+            (0..=255).step_by(51).flat_map(move |g| (0..=255).step_by(51).map(move |b| Color::new(r, g, b)))
+        })
+    }
+
+    fn hue_difference(a: f32, b: f32) -> f32 {
+        let d = (a - b).rem_euclid(360.0);
+
+        d.min(360.0 - d)
+    }
+
+    mod conversion {
+        use super::*;
+
+        #[test]
+        fn reference_values() {
+            let white = WHITE.to_oklch();
+            let red = Color::new(255, 0, 0).to_oklch();
+
+            assert!((white.lightness - 1.0).abs() < 1e-3 && white.chroma < 1e-3, "{:?}", white);
+            assert_eq!(BLACK.to_oklch(), Oklch { lightness: 0.0, chroma: 0.0, hue: 0.0 });
+
+            // from the Oklab reference: sRGB red is L 0.628, C 0.258, h 29.2
+            assert!((red.lightness - 0.628).abs() < 1e-3, "{:?}", red);
+            assert!((red.chroma - 0.258).abs() < 1e-3, "{:?}", red);
+            assert!((red.hue - 29.23).abs() < 0.1, "{:?}", red);
+
+            // and a 0.225, b 0.126 in Oklab
+            let red = Color::new(255, 0, 0).to_oklab();
+
+            assert!((red.a - 0.2249).abs() < 1e-3 && (red.b - 0.1258).abs() < 1e-3, "{:?}", red);
+        }
+
+        #[test]
+        fn round_trips() {
+            for color in samples() {
+                assert_eq!(Color::from_oklch(color.to_oklch()), color);
+                assert_eq!(Color::from_oklab(color.to_oklab()), color);
+            }
+        }
+    }
+
+    mod invert_lightness {
+        use super::*;
+
+        #[test]
+        fn black_and_white() {
+            assert_eq!(WHITE.invert_lightness(), BLACK);
+            assert_eq!(BLACK.invert_lightness(), WHITE);
+            // the middle gray, at L 0.5
+            assert_eq!(Color::new(99, 99, 99).invert_lightness(), Color::new(99, 99, 99));
+        }
+
+        #[test]
+        fn flips_lightness_and_keeps_hue() {
+            for color in samples() {
+                let before = color.to_oklch();
+                let after = color.invert_lightness().to_oklch();
+
+                // 8-bit channels are coarse near black, where the first step up is L 0.07
+                assert!((after.lightness - (1.0 - before.lightness)).abs() < 0.04, "{:?} -> {:?}", before, after);
+
+                if before.chroma > 0.05 && after.chroma > 0.05 {
+                    assert!(hue_difference(before.hue, after.hue) < 4.0, "{:?} -> {:?}", before, after);
+                }
+            }
+        }
+
+        #[test]
+        fn loses_chroma_only_to_fit() {
+            let navy = Color::new(20, 30, 90);
+            let yellow = Color::new(255, 255, 0);
+
+            assert!((navy.invert_lightness().to_oklch().chroma - navy.to_oklch().chroma).abs() < 0.01);
+            assert!(yellow.invert_lightness().to_oklch().chroma < yellow.to_oklch().chroma);
+        }
+
+        #[test]
+        fn fills() {
+            let navy = Color::new(20, 30, 90);
+            let gradient = Fill::Conic { angle: 45.0, stops: vec![WHITE, navy] };
+
+            assert_eq!(
+                gradient.invert_lightness(),
+                Fill::Conic { angle: 45.0, stops: vec![BLACK, navy.invert_lightness()] }
+            );
+            assert_eq!(Fill::Color(WHITE).invert_lightness(), Fill::Color(BLACK));
+            assert_eq!(Fill::Transparent.invert_lightness(), Fill::Transparent);
+            assert_eq!(
+                Fill::Rainbow(RainbowParams { lightness: 0.25, ..RainbowParams::DEFAULT }).invert_lightness(),
+                Fill::Rainbow(RainbowParams { lightness: 0.75, ..RainbowParams::DEFAULT })
+            );
+        }
+    }
+}
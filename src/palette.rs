@@ -0,0 +1,185 @@
+mod ase;
+mod gpl;
+mod jasc;
+mod naming;
+mod json;
+mod raw;
+mod table;
+
+use std::io::Read;
+
+use crate::color::Color;
+use crate::error::PaletteError;
+
+/// A single color in a `Palette`, optionally named.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Swatch {
+    pub name: Option<String>,
+    pub color: Color,
+}
+
+/// An ordered list of colors, like the ones stored in palette files.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Palette {
+    pub name: Option<String>,
+    pub swatches: Vec<Swatch>,
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Palette::default()
+    }
+
+    pub fn push(&mut self, color: Color) {
+        self.swatches.push(Swatch { name: None, color });
+    }
+
+    pub fn push_named(&mut self, name: impl Into<String>, color: Color) {
+        self.swatches.push(Swatch { name: Some(name.into()), color });
+    }
+
+    pub fn colors(&self) -> impl Iterator<Item = &Color> {
+        self.swatches.iter().map(|s| &s.color)
+    }
+
+    pub fn len(&self) -> usize {
+        self.swatches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.swatches.is_empty()
+    }
+}
+
+impl From<Vec<Color>> for Palette {
+    fn from(colors: Vec<Color>) -> Self {
+        Palette {
+            name: None,
+            swatches: colors.into_iter().map(|color| Swatch { name: None, color }).collect(),
+        }
+    }
+}
+
+/// The palette file formats `Palette::from_reader_autodetect` can tell apart. Raw `.pal` files
+/// have no header to recognize, so they aren't included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// Adobe Swatch Exchange, see `Palette::read_ase`.
+    Ase,
+
+    /// GIMP palette, see `Palette::read_gpl`.
+    Gpl,
+
+    /// Paint Shop Pro, see `Palette::read_jasc`.
+    Jasc,
+
+    /// See `Palette::read_json`.
+    Json,
+}
+
+/// `bytes` without a leading UTF-8 byte order mark or whitespace.
+fn skip_preamble(bytes: &[u8]) -> &[u8] {
+    let text = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(text.len());
+
+    &text[start..]
+}
+
+impl PaletteFormat {
+    /// Recognizes a format from the start of a file: the `ASEF` magic bytes, the `GIMP Palette`
+    /// or `JASC-PAL` header line, or a JSON object or array. A UTF-8 byte order mark and leading
+    /// whitespace are skipped for the text formats.
+    pub fn detect(bytes: &[u8]) -> Option<PaletteFormat> {
+        if bytes.starts_with(ase::SIGNATURE) {
+            return Some(PaletteFormat::Ase)
+        }
+
+        let text = skip_preamble(bytes);
+
+        if text.starts_with(b"GIMP Palette") {
+            Some(PaletteFormat::Gpl)
+        } else if text.starts_with(b"JASC-PAL") {
+            Some(PaletteFormat::Jasc)
+        } else if text.starts_with(b"{") || text.starts_with(b"[") {
+            Some(PaletteFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+impl Palette {
+    /// Reads whichever format `PaletteFormat::detect` recognizes, for imports where the user
+    /// doesn't say what they're handing over.
+    ///
+    /// ```
+    /// use led_json::{Color, Palette};
+    ///
+    /// let gpl = Palette::from_reader_autodetect("GIMP Palette\n255 0 255\n".as_bytes()).unwrap();
+    /// let json = Palette::from_reader_autodetect(r##"["#ff00ff"]"##.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(gpl, Palette::from(vec![Color { red: 255, green: 0, blue: 255 }]));
+    /// assert_eq!(gpl, json);
+    /// ```
+    pub fn from_reader_autodetect<R: Read>(mut reader: R) -> std::result::Result<Palette, PaletteError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+
+        let text = skip_preamble(&bytes);
+
+        match PaletteFormat::detect(&bytes) {
+            Some(PaletteFormat::Ase) => Palette::read_ase(bytes.as_slice()),
+            Some(PaletteFormat::Gpl) => Palette::read_gpl(text),
+            Some(PaletteFormat::Jasc) => Palette::read_jasc(text),
+            Some(PaletteFormat::Json) => Palette::read_json(text),
+            None => Err(PaletteError::UnknownFormat),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGENTA: Color = Color { red: 255, green: 0, blue: 255 };
+
+    #[test]
+    fn detect() {
+        assert_eq!(PaletteFormat::detect(b"ASEF\x00\x01"), Some(PaletteFormat::Ase));
+        assert_eq!(PaletteFormat::detect(b"\xef\xbb\xbfGIMP Palette\r\n"), Some(PaletteFormat::Gpl));
+        assert_eq!(PaletteFormat::detect(b"JASC-PAL\r\n"), Some(PaletteFormat::Jasc));
+        assert_eq!(PaletteFormat::detect(b"\n  { \"colors\": [] }"), Some(PaletteFormat::Json));
+        assert_eq!(PaletteFormat::detect(b"[]"), Some(PaletteFormat::Json));
+        assert_eq!(PaletteFormat::detect(&[255, 0, 255]), None);
+        assert_eq!(PaletteFormat::detect(b""), None);
+    }
+
+    #[test]
+    fn autodetect_reads_every_format() {
+        let palette = Palette::from(vec![MAGENTA, Color { red: 1, green: 2, blue: 3 }]);
+
+        let mut files = vec![vec![], vec![], vec![], vec![]];
+        palette.write_ase(&mut files[0]).unwrap();
+        palette.write_gpl(&mut files[1]).unwrap();
+        palette.write_jasc(&mut files[2]).unwrap();
+        palette.write_json(&mut files[3]).unwrap();
+
+        for file in files {
+            assert_eq!(Palette::from_reader_autodetect(file.as_slice()).unwrap(), palette);
+        }
+    }
+
+    #[test]
+    fn autodetect_skips_a_byte_order_mark() {
+        let palette = Palette::from_reader_autodetect("\u{feff}JASC-PAL\r\n0100\r\n1\r\n255 0 255\r\n".as_bytes()).unwrap();
+
+        assert_eq!(palette, Palette::from(vec![MAGENTA]));
+    }
+
+    #[test]
+    fn autodetect_unknown() {
+        let result = Palette::from_reader_autodetect(&[255u8, 0, 255][..]);
+
+        assert!(matches!(result, Err(PaletteError::UnknownFormat)));
+    }
+}
@@ -0,0 +1,246 @@
+//! Adobe Swatch Exchange (`.ase`), the binary palette format shared by Photoshop, Illustrator
+//! and InDesign. Everything is big-endian:
+//!
+//! ```text
+//! "ASEF" | version 1.0 | block count
+//! per block: type (0x0001 color, 0xc001 group start, 0xc002 group end) | byte length | body
+//! color body: UTF-16 name with a trailing NUL | "RGB ", "CMYK", "Gray" or "LAB " | f32 channels | swatch type
+//! ```
+//!
+//! Groups are flattened into one list.
+
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use crate::color::Color;
+use crate::error::PaletteError;
+use crate::palette::Palette;
+
+pub(crate) const SIGNATURE: &[u8; 4] = b"ASEF";
+
+const COLOR: u16 = 0x0001;
+
+/// Swatch type for writing: a normal, process color.
+const NORMAL: u16 = 2;
+
+fn read_u16<R: Read>(reader: &mut R) -> std::io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u16::from_be_bytes(bytes))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn channel(value: f32) -> u8 {
+    (value * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// A color block's name and color. `body` is everything after the block header.
+fn read_color(mut body: &[u8]) -> std::result::Result<(Option<String>, Color), PaletteError> {
+    let units = read_u16(&mut body)?;
+    let name = (0..units).map(|_| read_u16(&mut body)).collect::<std::io::Result<Vec<u16>>>()?;
+    let name = String::from_utf16_lossy(&name).trim_end_matches('\0').to_string();
+
+    let mut model = [0; 4];
+    body.read_exact(&mut model)?;
+
+    let mut values = |n: usize| -> std::io::Result<Vec<f32>> {
+        (0..n).map(|_| read_u32(&mut body).map(f32::from_bits)).collect()
+    };
+
+    let color = match &model {
+        b"RGB " => {
+            let rgb = values(3)?;
+
+            Color { red: channel(rgb[0]), green: channel(rgb[1]), blue: channel(rgb[2]) }
+        },
+        b"Gray" => {
+            let gray = channel(values(1)?[0]);
+
+            Color { red: gray, green: gray, blue: gray }
+        },
+        b"CMYK" => {
+            // naive, without a color profile
+            let cmyk = values(4)?;
+            let ink = |c: f32| channel((1.0 - c) * (1.0 - cmyk[3]));
+
+            Color { red: ink(cmyk[0]), green: ink(cmyk[1]), blue: ink(cmyk[2]) }
+        },
+        _ => return Err(PaletteError::UnsupportedColorModel(String::from_utf8_lossy(&model).trim().to_string())),
+    };
+
+    Ok((Some(name).filter(|name| !name.is_empty()), color))
+}
+
+impl Palette {
+    /// Reads RGB, grayscale and CMYK swatches. CMYK is converted without a color profile, so it's
+    /// only approximate, and Lab swatches are an error.
+    pub fn read_ase<R: Read>(mut reader: R) -> std::result::Result<Palette, PaletteError> {
+        let mut signature = [0; 4];
+        reader.read_exact(&mut signature)?;
+
+        if &signature != SIGNATURE {
+            return Err(PaletteError::InvalidHeader)
+        }
+
+        let (major, minor) = (read_u16(&mut reader)?, read_u16(&mut reader)?);
+
+        if major != 1 {
+            return Err(PaletteError::UnsupportedVersion(format!("{}.{}", major, minor)))
+        }
+
+        let blocks = read_u32(&mut reader)?;
+        let mut palette = Palette::new();
+
+        for _ in 0..blocks {
+            let kind = read_u16(&mut reader)?;
+            let length = read_u32(&mut reader)?;
+
+            let mut body = vec![];
+            (&mut reader).take(u64::from(length)).read_to_end(&mut body)?;
+
+            if body.len() != length as usize {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into())
+            }
+
+            if kind == COLOR {
+                match read_color(&body)? {
+                    (Some(name), color) => palette.push_named(name, color),
+                    (None, color) => palette.push(color),
+                }
+            }
+        }
+
+        Ok(palette)
+    }
+
+    /// Writes every swatch as an RGB color. The palette's own name is not stored.
+    pub fn write_ase<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let too_many = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "too many swatches for an ASE file");
+
+        writer.write_all(SIGNATURE)?;
+        writer.write_all(&1u16.to_be_bytes())?;
+        writer.write_all(&0u16.to_be_bytes())?;
+        writer.write_all(&u32::try_from(self.len()).map_err(|_| too_many())?.to_be_bytes())?;
+
+        for swatch in &self.swatches {
+            let name = swatch.name.as_deref().unwrap_or("").encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+            let units = u16::try_from(name.len()).map_err(|_| too_many())?;
+            let c = &swatch.color;
+
+            writer.write_all(&COLOR.to_be_bytes())?;
+            writer.write_all(&(2 + 2 * u32::from(units) + 4 + 12 + 2).to_be_bytes())?;
+            writer.write_all(&units.to_be_bytes())?;
+
+            for unit in name {
+                writer.write_all(&unit.to_be_bytes())?;
+            }
+
+            writer.write_all(b"RGB ")?;
+
+            for channel in [c.red, c.green, c.blue].iter() {
+                writer.write_all(&(f32::from(*channel) / 255.0).to_be_bytes())?;
+            }
+
+            writer.write_all(&NORMAL.to_be_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = kind.to_be_bytes().to_vec();
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        out.extend_from_slice(body);
+
+        out
+    }
+
+    fn color_body(model: &[u8; 4], values: &[f32]) -> Vec<u8> {
+        // the name "a"
+        let mut body = vec![0, 2, 0, b'a', 0, 0];
+        body.extend_from_slice(model);
+
+        for v in values {
+            body.extend_from_slice(&v.to_be_bytes());
+        }
+
+        body.extend_from_slice(&NORMAL.to_be_bytes());
+
+        body
+    }
+
+    fn file(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = b"ASEF\x00\x01\x00\x00".to_vec();
+        out.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+
+        for b in blocks {
+            out.extend_from_slice(b);
+        }
+
+        out
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut palette = Palette::new();
+        palette.push_named("magenta ✨", Color { red: 255, green: 0, blue: 170 });
+        palette.push(Color { red: 1, green: 2, blue: 3 });
+
+        let mut out = vec![];
+        palette.write_ase(&mut out).unwrap();
+
+        assert_eq!(&out[..4], b"ASEF");
+        assert_eq!(Palette::read_ase(out.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn other_models_and_groups() {
+        let data = file(&[
+            block(0xc001, &[0, 1, 0, 0]),
+            block(COLOR, &color_body(b"Gray", &[0.5])),
+            block(COLOR, &color_body(b"CMYK", &[0.0, 1.0, 1.0, 0.0])),
+            block(0xc002, &[]),
+        ]);
+
+        let palette = Palette::read_ase(data.as_slice()).unwrap();
+
+        assert_eq!(palette.colors().cloned().collect::<Vec<_>>(), vec![
+            Color { red: 128, green: 128, blue: 128 },
+            Color { red: 255, green: 0, blue: 0 },
+        ]);
+        assert_eq!(palette.swatches[0].name.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn lab_is_unsupported() {
+        let data = file(&[block(COLOR, &color_body(b"LAB ", &[0.5, 0.0, 0.0]))]);
+
+        assert!(matches!(Palette::read_ase(data.as_slice()), Err(PaletteError::UnsupportedColorModel(m)) if m == "LAB"));
+    }
+
+    #[test]
+    fn bad_header() {
+        assert!(matches!(Palette::read_ase(&b"ASEX\x00\x01\x00\x00\x00\x00\x00\x00"[..]), Err(PaletteError::InvalidHeader)));
+        assert!(matches!(Palette::read_ase(&b"ASEF\x00\x02\x00\x00\x00\x00\x00\x00"[..]), Err(PaletteError::UnsupportedVersion(v)) if v == "2.0"));
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = file(&[block(COLOR, &color_body(b"RGB ", &[1.0, 0.0, 0.0]))]);
+        data.truncate(data.len() - 3);
+
+        assert!(matches!(Palette::read_ase(data.as_slice()), Err(PaletteError::Io(_))));
+    }
+}
@@ -0,0 +1,137 @@
+//! GIMP palettes, also read by Inkscape, Krita and Aseprite:
+//!
+//! ```text
+//! GIMP Palette
+//! Name: Night
+//! #
+//! 255   0 255 accent
+//!   0   0   0 background
+//! ```
+
+use std::io::{BufRead, Write};
+
+use crate::color::Color;
+use crate::error::PaletteError;
+use crate::palette::Palette;
+
+const HEADER: &str = "GIMP Palette";
+
+impl Palette {
+    /// `Name:` becomes the palette's name and the text after each entry's channels its swatch
+    /// name. `Columns:` and `#` comments are skipped.
+    pub fn read_gpl<R: BufRead>(reader: R) -> std::result::Result<Palette, PaletteError> {
+        let mut lines = reader.lines();
+
+        match lines.next().transpose()? {
+            Some(line) if line.trim_start_matches('\u{feff}').trim() == HEADER => {},
+            _ => return Err(PaletteError::InvalidHeader),
+        }
+
+        let mut palette = Palette::new();
+
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            let entry = line.trim();
+
+            if entry.is_empty() || entry.starts_with('#') || entry.starts_with("Columns:") {
+                continue
+            }
+
+            if let Some(name) = entry.strip_prefix("Name:") {
+                palette.name = Some(name.trim().to_string());
+                continue
+            }
+
+            // the channels, then everything after them is the name
+            let mut rest = entry;
+            let mut channel = || {
+                let trimmed = rest.trim_start();
+                let (channel, tail) = trimmed.split_at(trimmed.find(char::is_whitespace).unwrap_or(trimmed.len()));
+
+                rest = tail;
+                channel.parse::<u8>().ok()
+            };
+
+            let color = match (channel(), channel(), channel()) {
+                (Some(red), Some(green), Some(blue)) => Color { red, green, blue },
+                _ => return Err(PaletteError::InvalidEntry { line: i + 2, entry: entry.to_string() }),
+            };
+
+            let name = Some(rest.trim()).filter(|name| !name.is_empty());
+
+            match name {
+                Some(name) => palette.push_named(name, color),
+                None => palette.push(color),
+            }
+        }
+
+        Ok(palette)
+    }
+
+    pub fn write_gpl<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+
+        if let Some(name) = &self.name {
+            writeln!(writer, "Name: {}", name)?;
+        }
+
+        writeln!(writer, "#")?;
+
+        for swatch in &self.swatches {
+            let c = &swatch.color;
+
+            write!(writer, "{:3} {:3} {:3}", c.red, c.green, c.blue)?;
+
+            match &swatch.name {
+                Some(name) => writeln!(writer, "\t{}", name)?,
+                None => writeln!(writer)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        let data = "GIMP Palette\nName: Night\nColumns: 4\n#\n# a comment\n255   0 255\taccent color\n  0   0   0\n";
+        let palette = Palette::read_gpl(data.as_bytes()).unwrap();
+
+        let mut expected = Palette::new();
+        expected.name = Some("Night".to_string());
+        expected.push_named("accent color", Color { red: 255, green: 0, blue: 255 });
+        expected.push(Color { red: 0, green: 0, blue: 0 });
+
+        assert_eq!(palette, expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut palette = Palette::new();
+        palette.name = Some("Mixed".to_string());
+        palette.push_named("one", Color { red: 1, green: 2, blue: 3 });
+        palette.push(Color { red: 200, green: 100, blue: 0 });
+
+        let mut out = vec![];
+        palette.write_gpl(&mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), "GIMP Palette\nName: Mixed\n#\n  1   2   3\tone\n200 100   0\n");
+        assert_eq!(Palette::read_gpl(out.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn bad_header() {
+        assert!(matches!(Palette::read_gpl("JASC-PAL\n".as_bytes()), Err(PaletteError::InvalidHeader)));
+    }
+
+    #[test]
+    fn bad_entry() {
+        let result = Palette::read_gpl("GIMP Palette\n#\n255 0\n".as_bytes());
+
+        assert!(matches!(result, Err(PaletteError::InvalidEntry { line: 3, .. })));
+    }
+}
@@ -0,0 +1,158 @@
+//! JASC-PAL, the text palette format written by Paint Shop Pro and read by most sprite editors:
+//!
+//! ```text
+//! JASC-PAL
+//! 0100
+//! 2
+//! 255 0 255
+//! 0 0 0
+//! ```
+
+use std::io::{BufRead, Write};
+
+use crate::color::Color;
+use crate::error::PaletteError;
+use crate::palette::Palette;
+
+const HEADER: &str = "JASC-PAL";
+const VERSION: &str = "0100";
+
+fn parse_channel(s: Option<&str>) -> Option<u8> {
+    s?.parse().ok()
+}
+
+impl Palette {
+    pub fn read_jasc<R: BufRead>(reader: R) -> std::result::Result<Palette, PaletteError> {
+        let mut lines = reader.lines();
+
+        let mut next_line = || -> std::result::Result<Option<String>, PaletteError> {
+            match lines.next() {
+                Some(line) => Ok(Some(line?.trim().to_string())),
+                None => Ok(None),
+            }
+        };
+
+        if next_line()?.as_deref() != Some(HEADER) {
+            return Err(PaletteError::InvalidHeader)
+        }
+
+        match next_line()? {
+            Some(v) if v == VERSION => {},
+            v => return Err(PaletteError::UnsupportedVersion(v.unwrap_or_default())),
+        }
+
+        let expected = match next_line()? {
+            Some(count) => count.parse::<usize>().map_err(|_| PaletteError::InvalidCount(count))?,
+            None => return Err(PaletteError::InvalidCount(String::new())),
+        };
+
+        let mut palette = Palette::new();
+        let mut line_number = 3;
+
+        while let Some(entry) = next_line()? {
+            line_number += 1;
+
+            if entry.is_empty() {
+                continue
+            }
+
+            let mut parts = entry.split_whitespace();
+
+            let color = match (parse_channel(parts.next()), parse_channel(parts.next()), parse_channel(parts.next()), parts.next()) {
+                (Some(red), Some(green), Some(blue), None) => Color { red, green, blue },
+                _ => return Err(PaletteError::InvalidEntry { line: line_number, entry }),
+            };
+
+            palette.push(color);
+        }
+
+        if palette.len() != expected {
+            return Err(PaletteError::CountMismatch { expected, actual: palette.len() })
+        }
+
+        Ok(palette)
+    }
+
+    /// Writes with CRLF line endings, like Paint Shop Pro does. Swatch names are not stored.
+    pub fn write_jasc<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        write!(writer, "{}\r\n{}\r\n{}\r\n", HEADER, VERSION, self.len())?;
+
+        for c in self.colors() {
+            write!(writer, "{} {} {}\r\n", c.red, c.green, c.blue)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        let data = "JASC-PAL\r\n0100\r\n2\r\n255 0 255\r\n0 0 0\r\n";
+        let palette = Palette::read_jasc(data.as_bytes()).unwrap();
+
+        assert_eq!(palette, Palette::from(vec![
+            Color { red: 255, green: 0, blue: 255 },
+            Color { red: 0, green: 0, blue: 0 },
+        ]));
+    }
+
+    #[test]
+    fn read_unix_line_endings() {
+        let data = "JASC-PAL\n0100\n1\n1 2 3\n";
+        let palette = Palette::read_jasc(data.as_bytes()).unwrap();
+
+        assert_eq!(palette, Palette::from(vec![Color { red: 1, green: 2, blue: 3 }]));
+    }
+
+    #[test]
+    fn write() {
+        let palette = Palette::from(vec![Color { red: 1, green: 2, blue: 3 }]);
+        let mut out = vec![];
+
+        palette.write_jasc(&mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "JASC-PAL\r\n0100\r\n1\r\n1 2 3\r\n");
+    }
+
+    #[test]
+    fn round_trip() {
+        let palette = Palette::from((0..=255).map(|c| Color { red: c, green: 255 - c, blue: c / 2 }).collect::<Vec<_>>());
+        let mut out = vec![];
+
+        palette.write_jasc(&mut out).unwrap();
+
+        assert_eq!(Palette::read_jasc(out.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn bad_header() {
+        let result = Palette::read_jasc("GIMP Palette\n".as_bytes());
+
+        assert!(matches!(result, Err(PaletteError::InvalidHeader)));
+    }
+
+    #[test]
+    fn bad_version() {
+        let result = Palette::read_jasc("JASC-PAL\n0200\n0\n".as_bytes());
+
+        assert!(matches!(result, Err(PaletteError::UnsupportedVersion(v)) if v == "0200"));
+    }
+
+    #[test]
+    fn bad_entry() {
+        let result = Palette::read_jasc("JASC-PAL\n0100\n1\n256 0 0\n".as_bytes());
+
+        assert!(matches!(result, Err(PaletteError::InvalidEntry { line: 4, .. })));
+    }
+
+    #[test]
+    fn count_mismatch() {
+        let result = Palette::read_jasc("JASC-PAL\n0100\n2\n0 0 0\n".as_bytes());
+
+        assert!(matches!(result, Err(PaletteError::CountMismatch { expected: 2, actual: 1 })));
+    }
+}
@@ -0,0 +1,152 @@
+//! JSON palettes: an object with an optional name and a list of colors, or just the list. Each
+//! entry is any form `Color` deserializes from, or an object naming it:
+//!
+//! ```json
+//! { "name": "Night", "colors": ["#000", { "name": "accent", "color": "#ff00ff" }] }
+//! ```
+
+use serde::{Serialize, Deserialize};
+
+use std::io::{Read, Write};
+
+use crate::color::Color;
+use crate::error::PaletteError;
+use crate::palette::{Palette, Swatch};
+use crate::theme::NameOrder;
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum Entry {
+    Color(Color),
+    Named { name: String, color: Color },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Document {
+    Colors(Vec<Entry>),
+    Palette {
+        #[serde(default)]
+        name: Option<String>,
+        colors: Vec<Entry>,
+    },
+}
+
+#[derive(Serialize)]
+struct Written<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: &'a Option<String>,
+    colors: Vec<Entry>,
+}
+
+impl Palette {
+    pub fn read_json<R: Read>(reader: R) -> std::result::Result<Palette, PaletteError> {
+        let document = serde_json::from_reader(reader).map_err(|e| PaletteError::InvalidJson(e.to_string()))?;
+
+        let (name, entries) = match document {
+            Document::Colors(entries) => (None, entries),
+            Document::Palette { name, colors } => (name, colors),
+        };
+
+        let swatches = entries.into_iter()
+            .map(|entry| match entry {
+                Entry::Color(color) => Swatch { name: None, color },
+                Entry::Named { name, color } => Swatch { name: Some(name), color },
+            })
+            .collect();
+
+        Ok(Palette { name, swatches })
+    }
+
+    /// Always writes the object form, so the name survives. Swatches are written in their order.
+    pub fn write_json<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.write_json_ordered(writer, NameOrder::Insertion)
+    }
+
+    /// `write_json`, with the swatches in `order`. Sorting alphabetically puts the unnamed
+    /// swatches first, in their order.
+    pub fn write_json_ordered<W: Write>(&self, writer: W, order: NameOrder) -> std::io::Result<()> {
+        let mut swatches: Vec<&Swatch> = self.swatches.iter().collect();
+
+        if order == NameOrder::Alphabetical {
+            swatches.sort_by_key(|swatch| &swatch.name);
+        }
+
+        let colors = swatches.into_iter()
+            .map(|swatch| match &swatch.name {
+                Some(name) => Entry::Named { name: name.clone(), color: swatch.color },
+                None => Entry::Color(swatch.color),
+            })
+            .collect();
+
+        serde_json::to_writer_pretty(writer, &Written { name: &self.name, colors })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        let data = r##"{ "name": "Night", "colors": ["#000", { "name": "accent", "color": [255, 0, 255] }] }"##;
+
+        let mut expected = Palette::new();
+        expected.name = Some("Night".to_string());
+        expected.push(Color { red: 0, green: 0, blue: 0 });
+        expected.push_named("accent", Color { red: 255, green: 0, blue: 255 });
+
+        assert_eq!(Palette::read_json(data.as_bytes()).unwrap(), expected);
+    }
+
+    #[test]
+    fn read_bare_list() {
+        let palette = Palette::read_json(r##"["#fff", { "r": 1, "g": 2, "b": 3 }]"##.as_bytes()).unwrap();
+
+        assert_eq!(palette, Palette::from(vec![
+            Color { red: 255, green: 255, blue: 255 },
+            Color { red: 1, green: 2, blue: 3 },
+        ]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut palette = Palette::new();
+        palette.name = Some("Mixed".to_string());
+        palette.push_named("one", Color { red: 1, green: 2, blue: 3 });
+        palette.push(Color { red: 200, green: 100, blue: 0 });
+
+        let mut out = vec![];
+        palette.write_json(&mut out).unwrap();
+
+        assert_eq!(Palette::read_json(out.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn ordered() {
+        let mut palette = Palette::new();
+        palette.push_named("b", Color { red: 0, green: 0, blue: 0 });
+        palette.push(Color { red: 255, green: 255, blue: 255 });
+        palette.push_named("a", Color { red: 255, green: 0, blue: 0 });
+
+        let write = |order| {
+            let mut out = vec![];
+            palette.write_json_ordered(&mut out, order).unwrap();
+
+            Palette::read_json(out.as_slice()).unwrap()
+        };
+
+        assert_eq!(write(NameOrder::Insertion), palette);
+
+        let names: Vec<_> = write(NameOrder::Alphabetical).swatches.into_iter().map(|swatch| swatch.name).collect();
+
+        assert_eq!(names, [None, Some("a".to_string()), Some("b".to_string())]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(Palette::read_json(r#"{ "colors": ["red"] }"#.as_bytes()), Err(PaletteError::InvalidJson(_))));
+    }
+}
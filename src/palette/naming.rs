@@ -0,0 +1,118 @@
+//! Names for the colors of a palette that doesn't have any, like one extracted from an image.
+
+use std::collections::HashSet;
+
+use crate::color::Color;
+use crate::palette::Palette;
+
+/// Where each hue name starts, in degrees. Red wraps around from 345.
+const HUES: [(f32, &str); 8] = [
+    (15.0, "orange"),
+    (45.0, "yellow"),
+    (70.0, "green"),
+    (165.0, "cyan"),
+    (195.0, "blue"),
+    (255.0, "purple"),
+    (290.0, "magenta"),
+    (345.0, "red"),
+];
+
+/// A name like `light-blue` or `dark-gray` from the color's hue and lightness.
+fn describe(color: &Color) -> String {
+    let hsl = color.to_hsl();
+
+    let hue = match hsl.saturation {
+        s if s < 0.15 || hsl.lightness < 0.05 || hsl.lightness > 0.95 => match hsl.lightness {
+            l if l < 0.1 => return "black".into(),
+            l if l > 0.9 => return "white".into(),
+            _ => "gray",
+        },
+        _ => HUES.iter().rev().find(|(start, _)| hsl.hue >= *start).map_or("red", |(_, name)| name),
+    };
+
+    match hsl.lightness {
+        l if l < 0.3 => format!("dark-{}", hue),
+        l if l > 0.7 => format!("light-{}", hue),
+        _ => hue.into(),
+    }
+}
+
+impl Palette {
+    /// Names every unnamed swatch after its hue and lightness, like `light-blue` or `dark-gray`,
+    /// for formats that need names, like SCSS variables. Names already in the palette are kept,
+    /// and repeats get a number: the second `red` is `red-2`.
+    ///
+    /// ```
+    /// use led_json::{Color, Palette};
+    ///
+    /// let mut palette = Palette::from(vec![Color::new(255, 0, 0), Color::new(200, 10, 10), Color::new(20, 20, 90)]);
+    /// palette.auto_name();
+    ///
+    /// let names: Vec<_> = palette.swatches.iter().map(|s| s.name.as_deref().unwrap()).collect();
+    ///
+    /// assert_eq!(names, ["red", "red-2", "dark-blue"]);
+    /// ```
+    pub fn auto_name(&mut self) {
+        let mut taken: HashSet<String> = self.swatches.iter().filter_map(|s| s.name.clone()).collect();
+
+        for swatch in self.swatches.iter_mut().filter(|s| s.name.is_none()) {
+            let base = describe(&swatch.color);
+            let name = (1..)
+                .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+                .find(|name| !taken.contains(name))
+                .expect("there's always an unused number");
+
+            taken.insert(name.clone());
+            swatch.name = Some(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hues() {
+        let name = |r, g, b| describe(&Color::new(r, g, b));
+
+        assert_eq!(name(255, 0, 0), "red");
+        assert_eq!(name(255, 0, 40), "red");
+        assert_eq!(name(255, 128, 0), "orange");
+        assert_eq!(name(255, 255, 0), "yellow");
+        assert_eq!(name(0, 200, 0), "green");
+        assert_eq!(name(0, 200, 200), "cyan");
+        assert_eq!(name(0, 0, 255), "blue");
+        assert_eq!(name(128, 0, 255), "purple");
+        assert_eq!(name(255, 0, 200), "magenta");
+    }
+
+    #[test]
+    fn lightness_and_grays() {
+        let name = |r, g, b| describe(&Color::new(r, g, b));
+
+        assert_eq!(name(100, 0, 0), "dark-red");
+        assert_eq!(name(160, 200, 255), "light-blue");
+        assert_eq!(name(0, 0, 0), "black");
+        assert_eq!(name(255, 255, 255), "white");
+        assert_eq!(name(128, 128, 128), "gray");
+        assert_eq!(name(40, 40, 45), "dark-gray");
+        assert_eq!(name(220, 215, 215), "light-gray");
+    }
+
+    #[test]
+    fn keeps_names_and_avoids_collisions() {
+        let mut palette = Palette::new();
+        palette.push_named("red", Color::new(0, 0, 255));
+        palette.push(Color::new(255, 0, 0));
+        palette.push_named("red-3", Color::new(0, 255, 0));
+        palette.push(Color::new(250, 0, 0));
+        palette.push(Color::new(245, 0, 0));
+
+        palette.auto_name();
+
+        let names: Vec<_> = palette.swatches.iter().map(|s| s.name.as_deref().unwrap()).collect();
+
+        assert_eq!(names, ["red", "red-2", "red-3", "red-4", "red-5"]);
+    }
+}
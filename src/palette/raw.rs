@@ -0,0 +1,59 @@
+//! Raw `.pal` files: nothing but packed `red green blue` bytes, usually 256 colors (768 bytes).
+
+use std::io::{Read, Write};
+
+use crate::color::Color;
+use crate::error::PaletteError;
+use crate::palette::Palette;
+
+impl Palette {
+    pub fn read_raw_pal<R: Read>(mut reader: R) -> std::result::Result<Palette, PaletteError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.len() % 3 != 0 {
+            return Err(PaletteError::InvalidLength(bytes.len()))
+        }
+
+        let colors = bytes.chunks_exact(3)
+            .map(|c| Color { red: c[0], green: c[1], blue: c[2] })
+            .collect::<Vec<Color>>();
+
+        Ok(colors.into())
+    }
+
+    /// Swatch names are not stored.
+    pub fn write_raw_pal<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for c in self.colors() {
+            writer.write_all(&[c.red, c.green, c.blue])?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let palette = Palette::from(vec![
+            Color { red: 255, green: 0, blue: 255 },
+            Color { red: 1, green: 2, blue: 3 },
+        ]);
+        let mut out = vec![];
+
+        palette.write_raw_pal(&mut out).unwrap();
+
+        assert_eq!(out, vec![255, 0, 255, 1, 2, 3]);
+        assert_eq!(Palette::read_raw_pal(out.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn invalid_length() {
+        let result = Palette::read_raw_pal(&[0u8, 1, 2, 3][..]);
+
+        assert!(matches!(result, Err(PaletteError::InvalidLength(4))));
+    }
+}
@@ -0,0 +1,121 @@
+//! A palette as a text table, for a quick look at it in a terminal:
+//!
+//! ```text
+//!      name    hex        hue   sat  light  vs white  vs black
+//! ▐██▌ accent  #ff00aa   320°  100%    50%      3.60      5.83
+//! ```
+
+use std::fmt::Write;
+
+use crate::ansi::{AnsiDepth, RESET};
+use crate::color::Color;
+use crate::palette::Palette;
+
+const WHITE: Color = Color::new(255, 255, 255);
+const BLACK: Color = Color::new(0, 0, 0);
+
+impl Palette {
+    /// One line per swatch under a header: the color itself, drawn with `depth`'s escape
+    /// sequences, then its name, hex code, HSL and contrast ratios against white and black.
+    /// Unnamed swatches have a blank name.
+    ///
+    /// ```
+    /// use led_json::{AnsiDepth, Color, Palette};
+    ///
+    /// let mut palette = Palette::new();
+    /// palette.push_named("accent", Color::new(255, 0, 170));
+    ///
+    /// let table = palette.render_table(AnsiDepth::TrueColor);
+    ///
+    /// assert!(table.lines().nth(1).unwrap().ends_with("accent  #ff00aa   320°  100%    50%      3.60      5.83"));
+    /// ```
+    pub fn render_table(&self, depth: AnsiDepth) -> String {
+        let width = self.swatches.iter()
+            .filter_map(|s| s.name.as_deref())
+            .map(|name| name.chars().count())
+            .fold("name".len(), usize::max);
+
+        let mut out = String::new();
+
+        // writing to a String can't fail
+        let _ = writeln!(
+            out,
+            "     {:<width$}  {:<7}  {:>5}  {:>4}  {:>5}  {:>8}  {:>8}",
+            "name", "hex", "hue", "sat", "light", "vs white", "vs black",
+            width = width,
+        );
+
+        for swatch in &self.swatches {
+            let color = &swatch.color;
+            let hsl = color.to_hsl();
+
+            let _ = writeln!(
+                out,
+                "{}▐██▌{} {:<width$}  {}  {:>4.0}°  {:>3.0}%  {:>4.0}%  {:>8.2}  {:>8.2}",
+                color.ansi_fg(depth),
+                RESET,
+                swatch.name.as_deref().unwrap_or(""),
+                color,
+                hsl.hue,
+                hsl.saturation * 100.0,
+                hsl.lightness * 100.0,
+                color.contrast_ratio(&WHITE),
+                color.contrast_ratio(&BLACK),
+                width = width,
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_escapes(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+
+        out
+    }
+
+    #[test]
+    fn columns() {
+        let mut palette = Palette::new();
+        palette.push_named("background", Color::new(0, 0, 0));
+        palette.push(Color::new(255, 255, 255));
+        palette.push_named("red", Color::new(255, 0, 0));
+
+        let table = palette.render_table(AnsiDepth::Ansi256);
+
+        assert_eq!(strip_escapes(&table), [
+            "     name        hex        hue   sat  light  vs white  vs black",
+            "▐██▌ background  #000000     0°    0%     0%     21.00      1.00",
+            "▐██▌             #ffffff     0°    0%   100%      1.00     21.00",
+            "▐██▌ red         #ff0000     0°  100%    50%      4.00      5.25",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn swatch_escapes() {
+        let palette = Palette::from(vec![Color::new(255, 0, 0)]);
+        let line = palette.render_table(AnsiDepth::Ansi16).lines().nth(1).unwrap().to_string();
+
+        assert!(line.starts_with("\x1b[91m▐██▌\x1b[0m "), "{:?}", line);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Palette::new().render_table(AnsiDepth::TrueColor).lines().count(), 1);
+    }
+}
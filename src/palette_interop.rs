@@ -0,0 +1,90 @@
+//! Conversions to and from the `palette` crate's types.
+//!
+//! Within this file `palette` is always the external crate, never `crate::palette`.
+
+use palette::{Gradient, LinSrgb, Srgb};
+
+use crate::color::Color;
+use crate::fill::Fill;
+
+impl From<&Color> for Srgb<u8> {
+    fn from(color: &Color) -> Self {
+        Srgb::new(color.red, color.green, color.blue)
+    }
+}
+
+impl From<Color> for Srgb<u8> {
+    fn from(color: Color) -> Self {
+        Srgb::from(&color)
+    }
+}
+
+impl From<&Srgb<u8>> for Color {
+    fn from(srgb: &Srgb<u8>) -> Self {
+        Color { red: srgb.red, green: srgb.green, blue: srgb.blue }
+    }
+}
+
+impl From<Srgb<u8>> for Color {
+    fn from(srgb: Srgb<u8>) -> Self {
+        Color::from(&srgb)
+    }
+}
+
+impl Fill {
+    /// The stops of any kind of gradient as an evenly spaced `palette::Gradient` over `0.0..=1.0`.
+    ///
+    /// The stops are converted to linear sRGB, so the result blends in linear light rather than
+    /// channel by channel like `Fill::sample`. Anything other than a non-empty gradient gives
+    /// `None`.
+    pub fn to_palette_gradient(&self) -> Option<Gradient<LinSrgb>> {
+        match self.stops() {
+            Some(colors) if !colors.is_empty() => {
+                let stops = colors.iter().map(|c| Srgb::from(c).into_format::<f32>().into_linear());
+
+                Some(Gradient::new(stops))
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGENTA: Color = Color { red: 255, green: 0, blue: 170 };
+
+    #[test]
+    fn srgb_round_trip() {
+        let srgb = Srgb::from(&MAGENTA);
+
+        assert_eq!(srgb, Srgb::new(255, 0, 170));
+        assert_eq!(Color::from(srgb), MAGENTA);
+    }
+
+    #[test]
+    fn gradient_endpoints() {
+        let fill = Fill::Gradient(vec![
+            Color { red: 255, green: 0, blue: 0 },
+            Color { red: 0, green: 0, blue: 255 },
+        ]);
+
+        let gradient = fill.to_palette_gradient().unwrap();
+        let at = |t: f32| Color::from(Srgb::from_linear(gradient.get(t)).into_format::<u8>());
+
+        assert_eq!(at(0.0), Color { red: 255, green: 0, blue: 0 });
+        assert_eq!(at(1.0), Color { red: 0, green: 0, blue: 255 });
+
+        // blending in linear light is brighter in the middle than channel by channel
+        assert_eq!(at(0.5), Color { red: 188, green: 0, blue: 188 });
+    }
+
+    #[test]
+    fn only_gradients() {
+        assert!(Fill::rainbow().to_palette_gradient().is_none());
+        assert!(Fill::Color(MAGENTA).to_palette_gradient().is_none());
+        assert!(Fill::Gradient(vec![]).to_palette_gradient().is_none());
+        assert!(Fill::Radial { center: (0.5, 0.5), stops: vec![MAGENTA] }.to_palette_gradient().is_some());
+    }
+}
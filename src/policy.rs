@@ -0,0 +1,252 @@
+//! Rules a service can hold fills to before it stores them, stricter than what parses.
+
+use serde::ser::{self, Serialize, Serializer};
+
+use crate::color::Color;
+use crate::error::PolicyError;
+use crate::fill::{Fill, GradientRules};
+
+/// What `Fill::validate` checks. The default allows everything that deserializes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Policy {
+    /// The rules gradients have to follow.
+    pub gradient: GradientRules,
+
+    /// Reject `Fill::Transparent`, for outputs without an alpha channel, like LED strips.
+    pub no_alpha: bool,
+
+    /// Reject gradients with more stops than this.
+    pub max_stops: Option<usize>,
+
+    /// Reject colors outside this gamut. A rainbow's colors aren't checked.
+    pub gamut: Gamut,
+}
+
+/// A set of colors that a `Policy` can restrict fills to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gamut {
+    /// Every color.
+    #[default]
+    Full,
+
+    /// Colors with no channel above the limit, for hardware that can't take full power.
+    MaxChannel(u8),
+
+    /// The 216 web-safe colors, whose channels are all multiples of `0x33`.
+    WebSafe,
+
+    /// The exact colors of xterm's 256-color palette, which terminals show as they are.
+    Ansi256,
+}
+
+impl Gamut {
+    pub fn contains(&self, color: &Color) -> bool {
+        let channels = [color.red, color.green, color.blue];
+
+        match self {
+            Gamut::Full => true,
+            Gamut::MaxChannel(max) => channels.iter().all(|c| c <= max),
+            Gamut::WebSafe => channels.iter().all(|c| c % 0x33 == 0),
+            Gamut::Ansi256 => (0..=255).any(|i| Color::from_ansi256(i) == *color),
+        }
+    }
+}
+
+impl Fill {
+    /// Checks the fill against `policy`: its rainbow parameters, the gradient rules, then
+    /// transparency, the number of stops and the gamut of every color.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill, Gamut, Policy, PolicyError};
+    ///
+    /// let policy = Policy { max_stops: Some(2), gamut: Gamut::MaxChannel(200), ..Policy::default() };
+    ///
+    /// assert_eq!(Fill::Color(Color::new(200, 0, 0)).validate(&policy), Ok(()));
+    /// assert_eq!(
+    ///     Fill::Color(Color::new(255, 0, 0)).validate(&policy),
+    ///     Err(PolicyError::OutOfGamut { color: Color::new(255, 0, 0), gamut: Gamut::MaxChannel(200) })
+    /// );
+    /// ```
+    pub fn validate(&self, policy: &Policy) -> core::result::Result<(), PolicyError> {
+        if let Fill::Rainbow(params) = self {
+            params.validate()?;
+        }
+
+        self.validate_gradient(&policy.gradient)?;
+
+        if policy.no_alpha && *self == Fill::Transparent {
+            return Err(PolicyError::Transparent)
+        }
+
+        let colors = match self {
+            Fill::Color(color) => core::slice::from_ref(color),
+            _ => self.stops().unwrap_or(&[]),
+        };
+
+        match policy.max_stops {
+            Some(max) if self.stops().is_some() && colors.len() > max => {
+                return Err(PolicyError::TooManyStops { count: colors.len(), max })
+            },
+            _ => {},
+        }
+
+        match colors.iter().find(|color| !policy.gamut.contains(color)) {
+            Some(color) => Err(PolicyError::OutOfGamut { color: *color, gamut: policy.gamut }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Wraps a `Fill` so that serializing it checks it against `policy` first, and fails with the
+/// `PolicyError` if it breaks it. Wrap what's about to be stored, so invalid fills never are.
+///
+/// ```
+/// use led_json::{Fill, Policy, Validated};
+///
+/// let policy = Policy { no_alpha: true, ..Policy::default() };
+///
+/// assert_eq!(serde_json::to_string(&Validated::new(&Fill::rainbow(), &policy)).unwrap(), r#""rainbow""#);
+///
+/// let err = serde_json::to_string(&Validated::new(&Fill::Transparent, &policy)).unwrap_err();
+///
+/// assert_eq!(err.to_string(), "transparent breaks the policy: Transparency isn't allowed");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Validated<'a> {
+    pub value: &'a Fill,
+    pub policy: &'a Policy,
+}
+
+impl<'a> Validated<'a> {
+    pub fn new(value: &'a Fill, policy: &'a Policy) -> Self {
+        Validated { value, policy }
+    }
+}
+
+impl Serialize for Validated<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if let Err(err) = self.value.validate(self.policy) {
+            return Err(ser::Error::custom(format_args!("{} breaks the policy: {}", self.value, err)))
+        }
+
+        self.value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use crate::error::GradientError;
+    use crate::rainbow::RainbowParams;
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
+
+    mod gamut {
+        use super::*;
+
+        #[test]
+        fn contains() {
+            assert!(Gamut::Full.contains(&Color::new(1, 2, 3)));
+
+            assert!(Gamut::MaxChannel(128).contains(&Color::new(128, 0, 64)));
+            assert!(!Gamut::MaxChannel(128).contains(&Color::new(0, 129, 0)));
+
+            assert!(Gamut::WebSafe.contains(&Color::new(0x33, 0x99, 0xff)));
+            assert!(!Gamut::WebSafe.contains(&Color::new(0x34, 0x99, 0xff)));
+
+            assert!(Gamut::Ansi256.contains(&Color::new(95, 135, 175)));
+            assert!(Gamut::Ansi256.contains(&Color::new(8, 8, 8)));
+            assert!(!Gamut::Ansi256.contains(&Color::new(9, 9, 9)));
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn default_allows_everything_that_parses() {
+            for fill in [Fill::None, Fill::Transparent, Fill::rainbow(), Fill::Color(RED), Fill::Gradient(vec![RED; 20])] {
+                assert_eq!(fill.validate(&Policy::default()), Ok(()));
+            }
+        }
+
+        #[test]
+        fn no_alpha() {
+            let policy = Policy { no_alpha: true, ..Policy::default() };
+
+            assert_eq!(Fill::Transparent.validate(&policy), Err(PolicyError::Transparent));
+            assert_eq!(Fill::None.validate(&policy), Ok(()));
+        }
+
+        #[test]
+        fn max_stops() {
+            let policy = Policy { max_stops: Some(2), ..Policy::default() };
+
+            assert_eq!(Fill::Gradient(vec![RED, BLUE]).validate(&policy), Ok(()));
+            assert_eq!(
+                Fill::Conic { angle: 0.0, stops: vec![RED, BLUE, RED] }.validate(&policy),
+                Err(PolicyError::TooManyStops { count: 3, max: 2 })
+            );
+            assert_eq!(Fill::Color(RED).validate(&Policy { max_stops: Some(0), ..policy }), Ok(()));
+        }
+
+        #[test]
+        fn gamut() {
+            let policy = Policy { gamut: Gamut::WebSafe, ..Policy::default() };
+            let off = Color::new(0x10, 0, 0);
+
+            assert_eq!(Fill::Gradient(vec![RED, BLUE]).validate(&policy), Ok(()));
+            assert_eq!(
+                Fill::Radial { center: (0.5, 0.5), stops: vec![RED, off] }.validate(&policy),
+                Err(PolicyError::OutOfGamut { color: off, gamut: Gamut::WebSafe })
+            );
+            assert_eq!(Fill::rainbow().validate(&policy), Ok(()));
+        }
+
+        #[test]
+        fn gradient_and_rainbow_rules() {
+            let policy = Policy { gradient: GradientRules::strict(), ..Policy::default() };
+
+            assert_eq!(
+                Fill::Gradient(vec![RED, RED]).validate(&policy),
+                Err(PolicyError::Gradient(GradientError::RepeatedStop { index: 1 }))
+            );
+            assert!(matches!(
+                Fill::Rainbow(RainbowParams { period: 0.0, ..RainbowParams::DEFAULT }).validate(&policy),
+                Err(PolicyError::Rainbow(_))
+            ));
+        }
+    }
+
+    mod validated {
+        use super::*;
+
+        #[test]
+        fn serializes_valid_fills() {
+            let policy = Policy { max_stops: Some(2), ..Policy::default() };
+            let fill = Fill::Gradient(vec![RED, BLUE]);
+
+            assert_eq!(serde_json::to_string(&Validated::new(&fill, &policy)).unwrap(), r##"["#ff0000","#0000ff"]"##);
+        }
+
+        #[test]
+        fn fails_on_invalid_fills() {
+            let policy = Policy { max_stops: Some(1), ..Policy::default() };
+            let fill = Fill::Gradient(vec![RED, BLUE]);
+            let err = serde_json::to_string(&Validated::new(&fill, &policy)).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "linear-gradient(#ff0000, #0000ff) breaks the policy: A gradient can have at most 1 stops, not 2"
+            );
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Mirror types for the messages in `proto/led.proto`.
+//!
+//! These are written out by hand rather than generated so that no `protoc` is needed at build time.
+//! Keep them in sync with the `.proto` file.
+
+use thiserror::Error;
+
+use std::convert::TryFrom;
+
+use crate::color::Color;
+use crate::error::PolicyError;
+use crate::fill::{self, Fill};
+use crate::rainbow::RainbowParams;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProtoError {
+    #[error("Color channel out of range: {0}")]
+    ChannelOutOfRange(u32),
+
+    #[error("Fill is missing its kind")]
+    MissingKind,
+
+    /// The fill breaks the default `GradientRules` or its rainbow fails `RainbowParams::validate`,
+    /// the same as it would when deserializing.
+    #[error(transparent)]
+    Invalid(#[from] PolicyError),
+}
+
+/// `led.Color`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ColorProto {
+    #[prost(uint32, tag = "1")]
+    pub red: u32,
+    #[prost(uint32, tag = "2")]
+    pub green: u32,
+    #[prost(uint32, tag = "3")]
+    pub blue: u32,
+}
+
+/// `led.Rainbow`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RainbowProto {
+    #[prost(float, optional, tag = "1")]
+    pub saturation: Option<f32>,
+    #[prost(float, optional, tag = "2")]
+    pub lightness: Option<f32>,
+    #[prost(float, optional, tag = "3")]
+    pub period: Option<f32>,
+    #[prost(float, optional, tag = "4")]
+    pub phase: Option<f32>,
+}
+
+/// `led.None`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NoneProto {}
+
+/// `led.Transparent`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransparentProto {}
+
+/// `led.Gradient`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GradientProto {
+    #[prost(message, repeated, tag = "1")]
+    pub colors: Vec<ColorProto>,
+}
+
+/// `led.Radial`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RadialProto {
+    #[prost(float, tag = "1")]
+    pub center_x: f32,
+    #[prost(float, tag = "2")]
+    pub center_y: f32,
+    #[prost(message, repeated, tag = "3")]
+    pub stops: Vec<ColorProto>,
+}
+
+/// `led.Conic`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConicProto {
+    #[prost(float, tag = "1")]
+    pub angle: f32,
+    #[prost(message, repeated, tag = "2")]
+    pub stops: Vec<ColorProto>,
+}
+
+/// `led.Fill`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FillProto {
+    #[prost(oneof = "fill_proto::Kind", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    pub kind: Option<fill_proto::Kind>,
+}
+
+pub mod fill_proto {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Rainbow(super::RainbowProto),
+        #[prost(message, tag = "2")]
+        Color(super::ColorProto),
+        #[prost(message, tag = "3")]
+        Gradient(super::GradientProto),
+        #[prost(message, tag = "4")]
+        None(super::NoneProto),
+        #[prost(message, tag = "5")]
+        Transparent(super::TransparentProto),
+        #[prost(message, tag = "6")]
+        Radial(super::RadialProto),
+        #[prost(message, tag = "7")]
+        Conic(super::ConicProto),
+        #[prost(string, tag = "8")]
+        Raw(String),
+    }
+}
+
+impl From<&Color> for ColorProto {
+    fn from(color: &Color) -> Self {
+        ColorProto {
+            red: color.red.into(),
+            green: color.green.into(),
+            blue: color.blue.into(),
+        }
+    }
+}
+
+impl From<Color> for ColorProto {
+    fn from(color: Color) -> Self {
+        ColorProto::from(&color)
+    }
+}
+
+impl TryFrom<&ColorProto> for Color {
+    type Error = ProtoError;
+
+    fn try_from(proto: &ColorProto) -> std::result::Result<Self, Self::Error> {
+        let channel = |c: u32| u8::try_from(c).map_err(|_| ProtoError::ChannelOutOfRange(c));
+
+        Ok(Color {
+            red: channel(proto.red)?,
+            green: channel(proto.green)?,
+            blue: channel(proto.blue)?,
+        })
+    }
+}
+
+impl TryFrom<ColorProto> for Color {
+    type Error = ProtoError;
+
+    fn try_from(proto: ColorProto) -> std::result::Result<Self, Self::Error> {
+        Color::try_from(&proto)
+    }
+}
+
+fn stops_to_proto(stops: &[Color]) -> Vec<ColorProto> {
+    stops.iter().map(ColorProto::from).collect()
+}
+
+impl From<&RainbowParams> for RainbowProto {
+    /// Leaves out the fields that have their default values.
+    fn from(params: &RainbowParams) -> Self {
+        let default = RainbowParams::DEFAULT;
+        let field = |value: f32, default: f32| Some(value).filter(|v| v.to_bits() != default.to_bits());
+
+        RainbowProto {
+            saturation: field(params.saturation, default.saturation),
+            lightness: field(params.lightness, default.lightness),
+            period: field(params.period, default.period),
+            phase: field(params.phase, default.phase),
+        }
+    }
+}
+
+impl From<&RainbowProto> for RainbowParams {
+    fn from(proto: &RainbowProto) -> Self {
+        let default = RainbowParams::DEFAULT;
+
+        RainbowParams {
+            saturation: proto.saturation.unwrap_or(default.saturation),
+            lightness: proto.lightness.unwrap_or(default.lightness),
+            period: proto.period.unwrap_or(default.period),
+            phase: proto.phase.unwrap_or(default.phase),
+        }
+    }
+}
+
+fn stops_from_proto(stops: &[ColorProto]) -> std::result::Result<Vec<Color>, ProtoError> {
+    stops.iter().map(Color::try_from).collect()
+}
+
+impl From<&Fill> for FillProto {
+    fn from(fill: &Fill) -> Self {
+        let kind = match fill {
+            Fill::Rainbow(params) => fill_proto::Kind::Rainbow(params.into()),
+            Fill::None => fill_proto::Kind::None(NoneProto {}),
+            Fill::Transparent => fill_proto::Kind::Transparent(TransparentProto {}),
+            Fill::Color(color) => fill_proto::Kind::Color(color.into()),
+            Fill::Gradient(colors) => fill_proto::Kind::Gradient(GradientProto {
+                colors: stops_to_proto(colors),
+            }),
+            Fill::Radial { center: (x, y), stops } => fill_proto::Kind::Radial(RadialProto {
+                center_x: *x,
+                center_y: *y,
+                stops: stops_to_proto(stops),
+            }),
+            Fill::Conic { angle, stops } => fill_proto::Kind::Conic(ConicProto {
+                angle: *angle,
+                stops: stops_to_proto(stops),
+            }),
+            Fill::Raw(raw) => fill_proto::Kind::Raw(raw.clone()),
+        };
+
+        FillProto { kind: Some(kind) }
+    }
+}
+
+impl From<Fill> for FillProto {
+    fn from(fill: Fill) -> Self {
+        FillProto::from(&fill)
+    }
+}
+
+impl TryFrom<&FillProto> for Fill {
+    type Error = ProtoError;
+
+    fn try_from(proto: &FillProto) -> std::result::Result<Self, Self::Error> {
+        let fill = match &proto.kind {
+            None => return Err(ProtoError::MissingKind),
+            Some(fill_proto::Kind::Rainbow(rainbow)) => Fill::Rainbow(rainbow.into()),
+            Some(fill_proto::Kind::None(_)) => Fill::None,
+            Some(fill_proto::Kind::Transparent(_)) => Fill::Transparent,
+            Some(fill_proto::Kind::Color(color)) => Fill::Color(Color::try_from(color)?),
+            Some(fill_proto::Kind::Gradient(gradient)) => Fill::Gradient(stops_from_proto(&gradient.colors)?),
+            Some(fill_proto::Kind::Radial(radial)) => Fill::Radial {
+                center: (radial.center_x, radial.center_y),
+                stops: stops_from_proto(&radial.stops)?,
+            },
+            Some(fill_proto::Kind::Conic(conic)) => Fill::Conic {
+                angle: conic.angle,
+                stops: stops_from_proto(&conic.stops)?,
+            },
+            Some(fill_proto::Kind::Raw(raw)) => Fill::Raw(raw.clone()),
+        };
+
+        fill::check(&fill)?;
+
+        Ok(fill)
+    }
+}
+
+impl TryFrom<FillProto> for Fill {
+    type Error = ProtoError;
+
+    fn try_from(proto: FillProto) -> std::result::Result<Self, Self::Error> {
+        Fill::try_from(&proto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    use crate::error::{GradientError, RainbowError};
+
+    #[test]
+    fn color_round_trip() {
+        let proto = ColorProto::from(Color { red: 255, green: 0, blue: 15 });
+
+        assert_eq!(proto, ColorProto { red: 255, green: 0, blue: 15 });
+        assert_eq!(Color::try_from(proto), Ok(Color { red: 255, green: 0, blue: 15 }));
+    }
+
+    #[test]
+    fn color_out_of_range() {
+        let proto = ColorProto { red: 256, green: 0, blue: 0 };
+
+        assert_eq!(Color::try_from(proto), Err(ProtoError::ChannelOutOfRange(256)));
+    }
+
+    #[test]
+    fn fill_round_trip_through_bytes() {
+        let fills = vec![
+            Fill::rainbow(),
+            Fill::Rainbow(RainbowParams { period: 4.0, phase: 0.5, ..RainbowParams::DEFAULT }),
+            Fill::None,
+            Fill::Transparent,
+            Fill::Color(Color { red: 1, green: 2, blue: 3 }),
+            Fill::Gradient(vec![
+                Color { red: 255, green: 255, blue: 255 },
+                Color { red: 0, green: 0, blue: 0 },
+            ]),
+            Fill::Radial { center: (0.25, 0.75), stops: vec![Color { red: 9, green: 8, blue: 7 }] },
+            Fill::Conic { angle: 90.0, stops: vec![Color { red: 1, green: 1, blue: 1 }] },
+        ];
+
+        for fill in fills {
+            let bytes = FillProto::from(&fill).encode_to_vec();
+            let decoded = FillProto::decode(bytes.as_slice()).unwrap();
+
+            assert_eq!(Fill::try_from(decoded), Ok(fill));
+        }
+    }
+
+    #[test]
+    fn fill_missing_kind() {
+        assert_eq!(Fill::try_from(FillProto { kind: None }), Err(ProtoError::MissingKind));
+    }
+
+    #[test]
+    fn fill_has_to_be_valid() {
+        let white = ColorProto { red: 255, green: 255, blue: 255 };
+        let decode = |kind| Fill::try_from(FillProto { kind: Some(kind) });
+
+        assert_eq!(
+            decode(fill_proto::Kind::Gradient(GradientProto { colors: vec![] })),
+            Err(ProtoError::Invalid(PolicyError::Gradient(GradientError::Empty)))
+        );
+        assert_eq!(
+            decode(fill_proto::Kind::Radial(RadialProto { center_x: 5.0, center_y: 0.5, stops: vec![white.clone()] })),
+            Err(ProtoError::Invalid(PolicyError::Gradient(GradientError::CenterOutOfRange { x: 5.0, y: 0.5 })))
+        );
+        assert!(matches!(
+            decode(fill_proto::Kind::Conic(ConicProto { angle: f32::NAN, stops: vec![white] })),
+            Err(ProtoError::Invalid(PolicyError::Gradient(GradientError::InvalidAngle(_))))
+        ));
+        assert_eq!(
+            decode(fill_proto::Kind::Rainbow(RainbowProto { period: Some(0.0), ..RainbowProto::default() })),
+            Err(ProtoError::Invalid(PolicyError::Rainbow(RainbowError::InvalidPeriod(0.0))))
+        );
+    }
+}
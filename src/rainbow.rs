@@ -0,0 +1,242 @@
+//! The parameters of `Fill::Rainbow`: a walk around the hue wheel that repeats every `period`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::RainbowError;
+
+#[cfg(feature = "std")]
+use crate::color::Color;
+
+#[cfg(feature = "std")]
+use crate::fill::Fill;
+
+/// How `Fill::Rainbow` cycles through the hues. Deserializes from `{ "rainbow": { ... } }`, with
+/// every field optional; the bare `"rainbow"` string is `RainbowParams::DEFAULT`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RainbowParams {
+    /// `0.0..=1.0`, `1.0` by default.
+    pub saturation: f32,
+
+    /// `0.0..=1.0`, `0.5` by default.
+    pub lightness: f32,
+
+    /// How far `t` goes for one trip around the hue wheel, `1.0` by default. Negative periods run
+    /// the wheel backwards.
+    pub period: f32,
+
+    /// Where on the wheel `t = 0.0` starts, as a fraction of a trip, `0.0` (red) by default.
+    pub phase: f32,
+}
+
+impl RainbowParams {
+    /// Fully saturated hues, one trip around the wheel from red as `t` goes from `0.0` to `1.0`.
+    pub const DEFAULT: RainbowParams = RainbowParams { saturation: 1.0, lightness: 0.5, period: 1.0, phase: 0.0 };
+
+    /// Checks that saturation and lightness are in `0.0..=1.0`, the period is a non-zero number
+    /// and the phase is a number.
+    pub fn validate(&self) -> core::result::Result<(), RainbowError> {
+        for (component, value) in [("saturation", self.saturation), ("lightness", self.lightness)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(RainbowError::OutOfRange { component, value })
+            }
+        }
+
+        if !self.period.is_finite() || self.period == 0.0 {
+            return Err(RainbowError::InvalidPeriod(self.period))
+        }
+
+        if !self.phase.is_finite() {
+            return Err(RainbowError::InvalidPhase(self.phase))
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RainbowParams {
+    fn default() -> Self {
+        RainbowParams::DEFAULT
+    }
+}
+
+#[cfg(feature = "std")]
+impl RainbowParams {
+    /// The color at `t`. The cycle repeats every `period`, for any `t`, so an animation can
+    /// sample at its elapsed time in seconds.
+    ///
+    /// ```
+    /// use led_json::{Color, RainbowParams};
+    ///
+    /// let slow = RainbowParams { period: 10.0, ..RainbowParams::DEFAULT };
+    ///
+    /// assert_eq!(slow.sample(0.0), Color::new(255, 0, 0));
+    /// assert_eq!(slow.sample(5.0), Color::new(0, 255, 255));
+    /// assert_eq!(slow.sample(25.0), slow.sample(5.0));
+    /// ```
+    pub fn sample(&self, t: f32) -> Color {
+        Color::from_hsl(self.turn(t) * 360.0, self.saturation, self.lightness)
+    }
+
+    /// The colors at `t = 0.0`, `step`, `2.0 * step` and so on, without end: one per frame of an
+    /// animation that advances `step` each frame.
+    ///
+    /// ```
+    /// use led_json::{Color, RainbowParams};
+    ///
+    /// let frames: Vec<Color> = RainbowParams::DEFAULT.frames(0.25).take(5).collect();
+    ///
+    /// assert_eq!(frames[0], frames[4]);
+    /// assert_eq!(frames[2], Color::new(0, 255, 255));
+    /// ```
+    pub fn frames(self, step: f32) -> RainbowFrames {
+        RainbowFrames { params: self, step, frame: 0 }
+    }
+
+    /// The hue at `t`, in `0.0..1.0` of a trip around the wheel. Unlike `Fill::sample`, `t`
+    /// isn't clamped, so a clock can be fed in directly.
+    pub(crate) fn turn(&self, t: f32) -> f32 {
+        let turn = (t / self.period + self.phase).rem_euclid(1.0);
+
+        // `rem_euclid` can round up to exactly 1.0 for tiny negative values
+        if turn < 1.0 { turn } else { 0.0 }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Fill {
+    /// The static fill this one shows at time `t`, for renderers that don't animate. A `Rainbow`
+    /// is the solid color it has cycled to by then, `RainbowParams::sample(t)`; every other fill
+    /// is the same at all times and is returned as it is.
+    ///
+    /// ```
+    /// use led_json::{Color, Fill, RainbowParams};
+    ///
+    /// let slow = Fill::Rainbow(RainbowParams { period: 10.0, ..RainbowParams::DEFAULT });
+    ///
+    /// assert_eq!(slow.at_time(5.0), Fill::Color(Color::new(0, 255, 255)));
+    /// assert_eq!(Fill::Gradient(vec![Color::new(255, 0, 0)]).at_time(5.0), Fill::Gradient(vec![Color::new(255, 0, 0)]));
+    /// ```
+    pub fn at_time(&self, t: f32) -> Fill {
+        match self {
+            Fill::Rainbow(params) => Fill::Color(params.sample(t)),
+            fill => fill.clone(),
+        }
+    }
+}
+
+/// See `RainbowParams::frames`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct RainbowFrames {
+    params: RainbowParams,
+    step: f32,
+    frame: u64,
+}
+
+#[cfg(feature = "std")]
+impl Iterator for RainbowFrames {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        // multiplying rather than adding up steps, so rounding errors don't build up
+        let color = self.params.sample(self.frame as f32 * self.step);
+        self.frame += 1;
+
+        Some(color)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        assert_eq!(RainbowParams::DEFAULT.validate(), Ok(()));
+        assert_eq!(RainbowParams { period: -2.0, phase: 3.5, ..RainbowParams::DEFAULT }.validate(), Ok(()));
+
+        assert_eq!(
+            RainbowParams { saturation: 1.5, ..RainbowParams::DEFAULT }.validate(),
+            Err(RainbowError::OutOfRange { component: "saturation", value: 1.5 })
+        );
+        assert_eq!(
+            RainbowParams { period: 0.0, ..RainbowParams::DEFAULT }.validate(),
+            Err(RainbowError::InvalidPeriod(0.0))
+        );
+        assert!(RainbowParams { phase: f32::NAN, ..RainbowParams::DEFAULT }.validate().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    mod sample {
+        use super::*;
+
+        #[test]
+        fn turn() {
+            let params = RainbowParams { period: 2.0, phase: 0.25, ..RainbowParams::DEFAULT };
+
+            assert_eq!(params.turn(0.0), 0.25);
+            assert_eq!(params.turn(1.0), 0.75);
+            assert_eq!(params.turn(1.5), 0.0);
+            assert_eq!(params.turn(-1.0), 0.75);
+            assert_eq!(RainbowParams { period: -1.0, ..RainbowParams::DEFAULT }.turn(0.25), 0.75);
+        }
+
+        #[test]
+        fn cycle() {
+            let params = RainbowParams::DEFAULT;
+
+            assert_eq!(params.sample(0.0), Color::new(255, 0, 0));
+            assert_eq!(params.sample(1.0 / 3.0), Color::new(0, 255, 0));
+            assert_eq!(params.sample(1.0), Color::new(255, 0, 0));
+            assert_eq!(params.sample(-2.0 / 3.0), Color::new(0, 255, 0));
+        }
+
+        #[test]
+        fn saturation_and_lightness() {
+            let pastel = RainbowParams { saturation: 0.5, lightness: 0.75, ..RainbowParams::DEFAULT };
+
+            assert_eq!(pastel.sample(0.0), Color::from_hsl(0.0, 0.5, 0.75));
+            assert_eq!(RainbowParams { saturation: 0.0, ..RainbowParams::DEFAULT }.sample(0.4), Color::new(128, 128, 128));
+        }
+
+        #[test]
+        fn phase() {
+            let shifted = RainbowParams { phase: 0.5, ..RainbowParams::DEFAULT };
+
+            assert_eq!(shifted.sample(0.0), Color::new(0, 255, 255));
+            assert_eq!(shifted.sample(0.5), Color::new(255, 0, 0));
+        }
+
+        #[test]
+        fn frames() {
+            let params = RainbowParams { period: 3.0, ..RainbowParams::DEFAULT };
+            let frames: Vec<Color> = params.frames(1.0).take(4).collect();
+
+            assert_eq!(frames, vec![
+                Color::new(255, 0, 0),
+                Color::new(0, 255, 0),
+                Color::new(0, 0, 255),
+                Color::new(255, 0, 0),
+            ]);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn at_time() {
+        let params = RainbowParams { period: 3.0, ..RainbowParams::DEFAULT };
+
+        assert_eq!(Fill::Rainbow(params).at_time(1.0), Fill::Color(Color::new(0, 255, 0)));
+        assert_eq!(Fill::Rainbow(params).at_time(-1.0), Fill::Color(Color::new(0, 0, 255)));
+        assert_eq!(Fill::Rainbow(params).at_time(4.0), Fill::Rainbow(params).at_time(1.0));
+
+        for fill in [Fill::None, Fill::Transparent, Fill::Color(Color::new(1, 2, 3)), Fill::Conic { angle: 90.0, stops: vec![] }] {
+            assert_eq!(fill.at_time(12.5), fill);
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Application-defined color names, like design tokens, in place of hex colors.
+
+use serde::de::{DeserializeSeed, Deserializer};
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use crate::color::{self, Color, ParseOptions};
+use crate::error::ParseColorError;
+use crate::extension::{Extensions, InputExtensions};
+use crate::fill::{self, Fill};
+use crate::warning::Warnings;
+
+/// Looks up colors by name. Implemented for maps from names to colors and for closures.
+///
+/// ```
+/// use led_json::{Color, ColorResolver};
+///
+/// let tokens = |name: &str| match name {
+///     "brand-primary" => Some(Color::new(0x1a, 0x73, 0xe8)),
+///     _ => None,
+/// };
+///
+/// assert_eq!(tokens.resolve("brand-primary"), Some(Color::new(0x1a, 0x73, 0xe8)));
+/// assert_eq!(tokens.resolve("brand-secondary"), None);
+/// ```
+pub trait ColorResolver {
+    /// The color called `name`, if there is one.
+    fn resolve(&self, name: &str) -> Option<Color>;
+}
+
+impl<F> ColorResolver for F
+where
+    F: Fn(&str) -> Option<Color>,
+{
+    fn resolve(&self, name: &str) -> Option<Color> {
+        self(name)
+    }
+}
+
+impl ColorResolver for BTreeMap<String, Color> {
+    fn resolve(&self, name: &str) -> Option<Color> {
+        self.get(name).copied()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: std::hash::BuildHasher> ColorResolver for std::collections::HashMap<String, Color, S> {
+    fn resolve(&self, name: &str) -> Option<Color> {
+        self.get(name).copied()
+    }
+}
+
+/// What the parsing functions take: no resolver at all, or one to try before parsing.
+pub(crate) type Resolver<'a> = Option<&'a dyn ColorResolver>;
+
+/// Parses fills like `Fill::from_str`, with colors also given by name. Names are looked up
+/// before anything else is tried, except for the keywords like `"rainbow"`, which always
+/// win. Given `InputExtensions`, it also reads the string forms they add.
+///
+/// It's also a `DeserializeSeed`, for reading fills with names in them out of config files:
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use led_json::{Color, Fill, FillParser};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut tokens = BTreeMap::new();
+/// tokens.insert("brand-primary".to_string(), Color::new(0x1a, 0x73, 0xe8));
+///
+/// let parser = FillParser::with_resolver(&tokens);
+///
+/// assert_eq!(parser.parse("brand-primary"), Ok(Fill::Color(Color::new(0x1a, 0x73, 0xe8))));
+///
+/// let mut json = serde_json::Deserializer::from_str(r##"["brand-primary", "#fff"]"##);
+/// let fill: Fill = parser.deserialize(&mut json).unwrap();
+///
+/// assert_eq!(fill, Fill::Gradient(vec![Color::new(0x1a, 0x73, 0xe8), Color::new(255, 255, 255)]));
+/// ```
+#[derive(Clone, Copy)]
+pub struct FillParser<'a> {
+    options: ParseOptions,
+    resolver: Resolver<'a>,
+    extensions: Extensions<'a>,
+}
+
+impl<'a> FillParser<'a> {
+    /// Parses with `options` and no names, the same as `Fill::parse_with`.
+    pub fn new(options: ParseOptions) -> Self {
+        FillParser { options, resolver: None, extensions: None }
+    }
+
+    /// Parses with the default, strict `ParseOptions`, looking names up in `resolver`.
+    pub fn with_resolver(resolver: &'a dyn ColorResolver) -> Self {
+        FillParser { options: ParseOptions::default(), resolver: Some(resolver), extensions: None }
+    }
+
+    /// The same parser, with colors that aren't names read according to `options`.
+    pub fn options(self, options: ParseOptions) -> Self {
+        FillParser { options, ..self }
+    }
+
+    /// The same parser, handing strings that aren't keywords, names or colors to `extensions`.
+    pub fn extensions(self, extensions: &'a InputExtensions) -> Self {
+        FillParser { extensions: Some(extensions), ..self }
+    }
+
+    /// Parses any of `Fill::from_str`'s forms.
+    pub fn parse(&self, s: &str) -> core::result::Result<Fill, ParseColorError> {
+        fill::parse(s, self.options, self.resolver, self.extensions, &mut Warnings::new())
+    }
+
+    /// Parses a single color: a name, or a hex color read according to the options.
+    pub fn parse_color(&self, s: &str) -> core::result::Result<Color, ParseColorError> {
+        match self.resolver.and_then(|r| r.resolve(s)) {
+            Some(color) => Ok(color),
+            None => color::parse(s, self.options, &mut Warnings::new()),
+        }
+    }
+}
+
+/// Deserializes a `Fill` with its colors, including the stops of a tagged gradient object like
+/// `{ "type": "radial", "stops": [...] }`, parsed by `parse_color`.
+impl<'de> DeserializeSeed<'de> for FillParser<'_> {
+    type Value = Fill;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Fill, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fill::deserialize_with(deserializer, &|s| self.parse_color(s), None, self.extensions)
+    }
+}
+
+impl core::fmt::Debug for FillParser<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FillParser")
+            .field("options", &self.options)
+            .field("resolver", &self.resolver.map(|_| ".."))
+            .field("extensions", &self.extensions)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    const BRAND: Color = Color { red: 0x1a, green: 0x73, blue: 0xe8 };
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+    fn tokens() -> BTreeMap<String, Color> {
+        let mut tokens = BTreeMap::new();
+        tokens.insert("brand-primary".into(), BRAND);
+        tokens.insert("rainbow".into(), WHITE);
+
+        tokens
+    }
+
+    fn deserialize(parser: FillParser<'_>, json: &str) -> serde_json::Result<Fill> {
+        parser.deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn names() {
+            let tokens = tokens();
+            let parser = FillParser::with_resolver(&tokens);
+
+            assert_eq!(parser.parse("brand-primary"), Ok(Fill::Color(BRAND)));
+            assert_eq!(parser.parse("#fff"), Ok(Fill::Color(WHITE)));
+            assert_eq!(parser.parse("brand-primary, #fff"), Ok(Fill::Gradient(vec![BRAND, WHITE])));
+            assert_eq!(
+                parser.parse("radial-gradient(at 0.5 0.5, brand-primary)"),
+                Ok(Fill::Radial { center: (0.5, 0.5), stops: vec![BRAND] })
+            );
+            assert_eq!(parser.parse_color("brand-primary"), Ok(BRAND));
+        }
+
+        #[test]
+        fn keywords_win() {
+            let tokens = tokens();
+
+            assert_eq!(FillParser::with_resolver(&tokens).parse("rainbow"), Ok(Fill::rainbow()));
+        }
+
+        #[test]
+        fn unknown_names() {
+            let tokens = tokens();
+            let parser = FillParser::with_resolver(&tokens);
+
+            assert_eq!(parser.parse("brand-secondary"), Err(ParseColorError::UnknownKeyword("brand-secondary".into())));
+            assert!(parser.parse("brand-secondary, #fff").is_err());
+        }
+
+        #[test]
+        fn options() {
+            let lookup = |name: &str| Some(BRAND).filter(|_| name == "brand");
+            let parser = FillParser::with_resolver(&lookup).options(ParseOptions::lenient());
+
+            assert_eq!(parser.parse("brand, fff"), Ok(Fill::Gradient(vec![BRAND, WHITE])));
+            assert_eq!(FillParser::new(ParseOptions::default()).parse("brand"), Err(ParseColorError::UnknownKeyword("brand".into())));
+        }
+    }
+
+    mod seed {
+        use super::*;
+
+        #[test]
+        fn names() {
+            let tokens = tokens();
+            let parser = FillParser::with_resolver(&tokens);
+
+            assert_eq!(deserialize(parser, r#""brand-primary""#).unwrap(), Fill::Color(BRAND));
+            assert_eq!(deserialize(parser, r##"["brand-primary", "#fff"]"##).unwrap(), Fill::Gradient(vec![BRAND, WHITE]));
+            assert_eq!(deserialize(parser, r#""rainbow""#).unwrap(), Fill::rainbow());
+        }
+
+        #[test]
+        fn tagged_stops() {
+            let tokens = tokens();
+            let parser = FillParser::with_resolver(&tokens).options(ParseOptions::lenient());
+
+            assert_eq!(
+                deserialize(parser, r#"{ "type": "radial", "stops": ["brand-primary", "fff"] }"#).unwrap(),
+                Fill::Radial { center: (0.5, 0.5), stops: vec![BRAND, WHITE] }
+            );
+            assert_eq!(
+                deserialize(parser, r#"{ "type": "conic", "stops": ["brand-primary", [255, 255, 255]] }"#).unwrap(),
+                Fill::Conic { angle: 0.0, stops: vec![BRAND, WHITE] }
+            );
+
+            let json = r#"{ "type": "linear", "stops": ["brand-secondary"] }"#;
+
+            assert!(deserialize(FillParser::with_resolver(&tokens), json).is_err());
+            assert!(serde_json::from_str::<Fill>(r#"{ "type": "radial", "stops": ["brand-primary"] }"#).is_err());
+        }
+
+        #[test]
+        fn unknown_names() {
+            let tokens = tokens();
+            let err = deserialize(FillParser::with_resolver(&tokens), r#""brand-secondary""#).unwrap_err();
+
+            assert!(err.to_string().contains("brand-secondary"), "{}", err);
+            assert!(serde_json::from_str::<Fill>(r#""brand-primary""#).is_err());
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! JSON Schema descriptions of `Color` and `Fill`, matching everything their `Deserialize` impls
+//! accept.
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    ArrayValidation, InstanceType, Metadata, NumberValidation, ObjectValidation, Schema, SchemaObject,
+    StringValidation, SubschemaValidation,
+};
+use schemars::JsonSchema;
+
+use crate::color::Color;
+use crate::fill::Fill;
+
+const HEX_PATTERN: &str = "^#([0-9a-fA-F]{3}|[0-9a-fA-F]{6})$";
+
+fn described(mut schema: SchemaObject, description: &str) -> Schema {
+    schema.metadata = Some(Box::new(Metadata {
+        description: Some(description.to_string()),
+        ..Default::default()
+    }));
+
+    Schema::Object(schema)
+}
+
+fn any_of(schemas: Vec<Schema>) -> SchemaObject {
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation { any_of: Some(schemas), ..Default::default() })),
+        ..Default::default()
+    }
+}
+
+fn integer(max: u32) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::Integer.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(0.0),
+            maximum: Some(f64::from(max)),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+fn hex_string() -> Schema {
+    let schema = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation { pattern: Some(HEX_PATTERN.to_string()), ..Default::default() })),
+        ..Default::default()
+    };
+
+    described(schema, "`#rgb` or `#rrggbb`")
+}
+
+fn channels_object(names: [&str; 3]) -> Schema {
+    let mut object = ObjectValidation {
+        additional_properties: Some(Box::new(Schema::Bool(false))),
+        ..Default::default()
+    };
+
+    for name in names.iter() {
+        object.properties.insert(name.to_string(), Schema::Object(integer(255)));
+        object.required.insert(name.to_string());
+    }
+
+    let schema = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    };
+
+    described(schema, "channels from 0 to 255")
+}
+
+fn channel_array() -> Schema {
+    let schema = SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(Schema::Object(integer(255)).into()),
+            min_items: Some(3),
+            max_items: Some(4),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+
+    described(schema, "`[red, green, blue]` or `[red, green, blue, alpha]`; the alpha is ignored")
+}
+
+fn packed_integer() -> Schema {
+    described(integer(0xff_ff_ff), "a packed `0xRRGGBB` integer")
+}
+
+/// The forms a color can take inside a gradient: everything but a packed integer, since numbers
+/// in a gradient are channel values.
+fn gradient_stop() -> Schema {
+    Schema::Object(any_of(vec![
+        hex_string(),
+        channels_object(["r", "g", "b"]),
+        channels_object(["red", "green", "blue"]),
+        channel_array(),
+    ]))
+}
+
+fn number() -> Schema {
+    Schema::Object(SchemaObject { instance_type: Some(InstanceType::Number.into()), ..Default::default() })
+}
+
+fn fraction() -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Number.into()),
+        number: Some(Box::new(NumberValidation { minimum: Some(0.0), maximum: Some(1.0), ..Default::default() })),
+        ..Default::default()
+    })
+}
+
+fn center() -> Schema {
+    let schema = SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(fraction().into()),
+            min_items: Some(2),
+            max_items: Some(2),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+
+    described(schema, "`[x, y]` as fractions of the surface")
+}
+
+/// `{ "rainbow": { ... } }`, with every parameter optional. A zero period can't be expressed.
+fn rainbow() -> SchemaObject {
+    let closed = || ObjectValidation {
+        additional_properties: Some(Box::new(Schema::Bool(false))),
+        ..Default::default()
+    };
+
+    let mut params = closed();
+    params.properties.insert("saturation".to_string(), fraction());
+    params.properties.insert("lightness".to_string(), fraction());
+    params.properties.insert("period".to_string(), number());
+    params.properties.insert("phase".to_string(), number());
+
+    let params = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(params)),
+        ..Default::default()
+    };
+
+    let mut object = closed();
+    object.properties.insert("rainbow".to_string(), Schema::Object(params));
+    object.required.insert("rainbow".to_string());
+
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    }
+}
+
+/// `{ "type": kind, "stops": [...] }`, plus the one extra property the kind allows.
+fn tagged(kind: &str, extra: Option<(&str, Schema)>, stops: SchemaObject) -> SchemaObject {
+    let mut object = ObjectValidation {
+        additional_properties: Some(Box::new(Schema::Bool(false))),
+        ..Default::default()
+    };
+
+    let tag = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(vec![kind.into()]),
+        ..Default::default()
+    };
+
+    object.properties.insert("type".to_string(), Schema::Object(tag));
+    object.properties.insert("stops".to_string(), Schema::Object(stops));
+    object.required.insert("type".to_string());
+    object.required.insert("stops".to_string());
+
+    if let Some((name, schema)) = extra {
+        object.properties.insert(name.to_string(), schema);
+    }
+
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    }
+}
+
+impl JsonSchema for Color {
+    fn schema_name() -> String {
+        "Color".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        Schema::Object(any_of(vec![
+            hex_string(),
+            channels_object(["r", "g", "b"]),
+            channels_object(["red", "green", "blue"]),
+            packed_integer(),
+            channel_array(),
+        ]))
+    }
+}
+
+impl JsonSchema for Fill {
+    fn schema_name() -> String {
+        "Fill".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let keywords = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(vec!["rainbow".into(), "none".into(), "transparent".into()]),
+            ..Default::default()
+        };
+
+        let gradient = SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(gradient_stop().into()),
+                min_items: Some(1),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        Schema::Object(any_of(vec![
+            Schema::Object(keywords),
+            gen.subschema_for::<Color>(),
+            described(gradient.clone(), "gradient stops, evenly spaced"),
+            described(tagged("linear", None, gradient.clone()), "a linear gradient"),
+            described(tagged("radial", Some(("center", center())), gradient.clone()), "a radial gradient"),
+            described(tagged("conic", Some(("angle", number())), gradient), "a conic gradient"),
+            described(rainbow(), "a rainbow with other than the default hue cycle"),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn schema<T: JsonSchema>() -> Value {
+        serde_json::to_value(schemars::schema_for!(T)).unwrap()
+    }
+
+    #[test]
+    fn color() {
+        let schema = schema::<Color>();
+        let forms = schema["anyOf"].as_array().unwrap();
+
+        assert_eq!(schema["title"], "Color");
+        assert_eq!(forms.len(), 5);
+        assert_eq!(forms[0]["pattern"], HEX_PATTERN);
+        assert_eq!(forms[1]["required"], json!(["b", "g", "r"]));
+        assert_eq!(forms[1]["additionalProperties"], false);
+        assert_eq!(forms[2]["properties"]["green"]["maximum"], 255.0);
+        assert_eq!(forms[3]["maximum"], 16777215.0);
+        assert_eq!(forms[4]["minItems"], 3);
+        assert_eq!(forms[4]["maxItems"], 4);
+    }
+
+    #[test]
+    fn fill() {
+        let schema = schema::<Fill>();
+        let forms = schema["anyOf"].as_array().unwrap();
+
+        assert_eq!(schema["title"], "Fill");
+        assert_eq!(forms[0]["enum"], json!(["rainbow", "none", "transparent"]));
+        assert_eq!(forms[1]["$ref"], "#/definitions/Color");
+        assert_eq!(forms[2]["type"], "array");
+        assert_eq!(forms[2]["items"]["anyOf"].as_array().unwrap().len(), 4);
+        assert_eq!(forms[3]["properties"]["type"]["enum"], json!(["linear"]));
+        assert_eq!(forms[4]["required"], json!(["stops", "type"]));
+        assert_eq!(forms[2]["minItems"], 1);
+        assert_eq!(forms[4]["properties"]["center"]["maxItems"], 2);
+        assert_eq!(forms[4]["properties"]["center"]["items"]["maximum"], 1.0);
+        assert_eq!(forms[5]["properties"]["angle"]["type"], "number");
+        assert_eq!(forms[5]["additionalProperties"], false);
+        assert_eq!(forms[6]["required"], json!(["rainbow"]));
+        assert_eq!(forms[6]["properties"]["rainbow"]["properties"]["lightness"]["maximum"], 1.0);
+        assert_eq!(forms[6]["properties"]["rainbow"]["additionalProperties"], false);
+        assert!(schema["definitions"]["Color"].is_object());
+    }
+
+    #[test]
+    fn as_a_field() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Config {
+            fill: Fill,
+        }
+
+        let schema = schema::<Config>();
+
+        assert_eq!(schema["properties"]["fill"]["$ref"], "#/definitions/Fill");
+        assert!(schema["definitions"]["Color"].is_object());
+    }
+}
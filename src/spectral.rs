@@ -0,0 +1,241 @@
+//! Experimental: approximate reflectance spectra for colors.
+//!
+//! `Color::to_spectrum` uses Smits' method ("An RGB to Spectrum Conversion for Reflectances",
+//! 1999), building a smooth spectrum out of white, cyan, magenta, yellow, red, green and blue basis
+//! spectra. Going back integrates the spectrum against Wyman, Sloan and Shirley's analytic fit of
+//! the CIE 1931 color matching functions.
+//!
+//! Neither direction is exact, so a round trip can move saturated colors by a few dozen in a
+//! channel. Grays, white and black do round trip. The point is physically plausible mixing:
+//! blending spectra behaves like mixing paints, so blue and yellow give a green rather than gray.
+
+use crate::color::Color;
+use crate::consts::{WHITE_D65 as WHITE, XYZ_TO_SRGB};
+use crate::lab::{linear_to_srgb, multiply, srgb_to_linear};
+
+/// Number of wavelength bins in a `Spectrum`.
+pub const BINS: usize = 10;
+
+/// Shortest wavelength covered, in nanometers. Bin `i` covers
+/// `START + i * BIN_WIDTH .. START + (i + 1) * BIN_WIDTH`.
+pub const START: f32 = 380.0;
+
+/// Width of each bin in nanometers, so the last bin ends at 720nm.
+pub const BIN_WIDTH: f32 = 34.0;
+
+type Basis = [f64; BINS];
+
+// Smits' basis spectra
+const WHITE_SPECTRUM: Basis = [1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000];
+const CYAN: Basis = [0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000];
+const MAGENTA: Basis = [1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959];
+const YELLOW: Basis = [0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840];
+const RED: Basis = [0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149];
+const GREEN: Basis = [0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025];
+const BLUE: Basis = [1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496];
+
+/// Reflectance, roughly `0.0..=1.0`, for each of the `BINS` wavelength bins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spectrum {
+    pub reflectance: [f32; BINS],
+}
+
+/// Piecewise gaussian used by the color matching function fit.
+fn lobe(wavelength: f64, mean: f64, below: f64, above: f64) -> f64 {
+    let sigma = if wavelength < mean { below } else { above };
+    let t = (wavelength - mean) / sigma;
+
+    (-0.5 * t * t).exp()
+}
+
+fn color_matching(wavelength: f64) -> [f64; 3] {
+    let w = wavelength;
+
+    [
+        1.056 * lobe(w, 599.8, 37.9, 31.0) + 0.362 * lobe(w, 442.0, 16.0, 26.7) - 0.065 * lobe(w, 501.1, 20.4, 26.2),
+        0.821 * lobe(w, 568.8, 46.9, 40.5) + 0.286 * lobe(w, 530.9, 16.3, 31.1),
+        1.217 * lobe(w, 437.0, 11.8, 36.0) + 0.681 * lobe(w, 459.0, 26.0, 13.8),
+    ]
+}
+
+/// XYZ contributed by a reflectance of 1.0 in each bin, integrated in 1nm steps.
+fn bin_weights() -> [[f64; 3]; BINS] {
+    let mut weights = [[0.0; 3]; BINS];
+    let steps = BIN_WIDTH as usize;
+
+    for (i, weight) in weights.iter_mut().enumerate() {
+        for step in 0..steps {
+            let wavelength = f64::from(START) + (i * steps + step) as f64 + 0.5;
+            let cmf = color_matching(wavelength);
+
+            for (w, c) in weight.iter_mut().zip(cmf.iter()) {
+                *w += c;
+            }
+        }
+    }
+
+    weights
+}
+
+impl Spectrum {
+    pub fn from_color(color: &Color) -> Spectrum {
+        let (r, g, b) = (srgb_to_linear(color.red), srgb_to_linear(color.green), srgb_to_linear(color.blue));
+
+        let mut spectrum = [0.0; BINS];
+        let mut add = |amount: f64, basis: &Basis| {
+            for (s, b) in spectrum.iter_mut().zip(basis.iter()) {
+                *s += amount * b;
+            }
+        };
+
+        // the smallest channel is white, the next a secondary and the rest a primary
+        if r <= g && r <= b {
+            add(r, &WHITE_SPECTRUM);
+
+            if g <= b {
+                add(g - r, &CYAN);
+                add(b - g, &BLUE);
+            } else {
+                add(b - r, &CYAN);
+                add(g - b, &GREEN);
+            }
+        } else if g <= r && g <= b {
+            add(g, &WHITE_SPECTRUM);
+
+            if r <= b {
+                add(r - g, &MAGENTA);
+                add(b - r, &BLUE);
+            } else {
+                add(b - g, &MAGENTA);
+                add(r - b, &RED);
+            }
+        } else {
+            add(b, &WHITE_SPECTRUM);
+
+            if r <= g {
+                add(r - b, &YELLOW);
+                add(g - r, &GREEN);
+            } else {
+                add(g - b, &YELLOW);
+                add(r - g, &RED);
+            }
+        }
+
+        let mut reflectance = [0.0; BINS];
+
+        for (r, s) in reflectance.iter_mut().zip(spectrum.iter()) {
+            *r = *s as f32;
+        }
+
+        Spectrum { reflectance }
+    }
+
+    /// The sRGB color of the spectrum under D65. A flat reflectance of 1.0 is white.
+    pub fn to_color(&self) -> Color {
+        let weights = bin_weights();
+
+        let mut xyz = [0.0; 3];
+        let mut flat = [0.0; 3];
+
+        for (reflectance, weight) in self.reflectance.iter().zip(weights.iter()) {
+            for k in 0..3 {
+                xyz[k] += f64::from(*reflectance) * weight[k];
+                flat[k] += weight[k];
+            }
+        }
+
+        // scale so that a perfect reflector comes out as the D65 white point
+        let xyz = [xyz[0] * WHITE[0] / flat[0], xyz[1] * WHITE[1] / flat[1], xyz[2] * WHITE[2] / flat[2]];
+        let [r, g, b] = multiply(&XYZ_TO_SRGB, xyz);
+
+        Color {
+            red: linear_to_srgb(r),
+            green: linear_to_srgb(g),
+            blue: linear_to_srgb(b),
+        }
+    }
+
+    /// Subtractive mix, like mixing paints: a weighted geometric mean of the two reflectances, bin
+    /// by bin. `t` is clamped to `0.0..=1.0`, where `0.0` is `self` and `1.0` is `other`.
+    pub fn mix(&self, other: &Spectrum, t: f32) -> Spectrum {
+        let t = t.clamp(0.0, 1.0);
+        let mut reflectance = [0.0; BINS];
+
+        for (i, r) in reflectance.iter_mut().enumerate() {
+            *r = self.reflectance[i].max(0.0).powf(1.0 - t) * other.reflectance[i].max(0.0).powf(t);
+        }
+
+        Spectrum { reflectance }
+    }
+}
+
+impl Color {
+    pub fn to_spectrum(&self) -> Spectrum {
+        Spectrum::from_color(self)
+    }
+
+    /// Mixes the colors as if they were paints, by way of their approximate spectra. See
+    /// `Spectrum::mix`.
+    pub fn mix_spectral(&self, other: &Color, t: f32) -> Color {
+        self.to_spectrum().mix(&other.to_spectrum(), t).to_color()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    #[test]
+    fn neutrals_round_trip() {
+        for c in (0..=255).step_by(15) {
+            assert_eq!(rgb(c, c, c).to_spectrum().to_color(), rgb(c, c, c));
+        }
+    }
+
+    #[test]
+    fn white_is_flat() {
+        for r in rgb(255, 255, 255).to_spectrum().reflectance.iter() {
+            assert!((r - 1.0).abs() < 0.001, "{}", r);
+        }
+    }
+
+    #[test]
+    fn primaries_keep_their_hue() {
+        let red = rgb(255, 0, 0).to_spectrum().to_color();
+        let green = rgb(0, 255, 0).to_spectrum().to_color();
+        let blue = rgb(0, 0, 255).to_spectrum().to_color();
+
+        assert!(red.red == 255 && red.green < 64 && red.blue < 64, "{:?}", red);
+        assert!(green.green > 240 && green.red < 64 && green.blue < 64, "{:?}", green);
+        assert!(blue.blue > 240 && blue.red < 64 && blue.green < 64, "{:?}", blue);
+    }
+
+    #[test]
+    fn red_reflects_long_wavelengths() {
+        let red = rgb(255, 0, 0).to_spectrum();
+
+        assert!(red.reflectance[BINS - 1] > 0.9);
+        assert!(red.reflectance[4] < 0.01);
+    }
+
+    #[test]
+    fn mix_endpoints() {
+        let a = rgb(200, 100, 50).to_spectrum();
+        let b = rgb(10, 20, 200).to_spectrum();
+
+        assert_eq!(a.mix(&b, 0.0), a);
+        assert_eq!(a.mix(&b, 1.0), b);
+    }
+
+    #[test]
+    fn blue_and_yellow_make_green() {
+        let mixed = rgb(0, 0, 255).mix_spectral(&rgb(255, 255, 0), 0.5);
+
+        // mixing channel by channel would give a gray
+        assert!(mixed.green > 64 && mixed.red < 32 && mixed.green > mixed.blue / 2, "{:?}", mixed);
+    }
+}
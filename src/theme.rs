@@ -0,0 +1,195 @@
+use indexmap::IndexMap;
+use serde::{Serialize, Serializer, Deserialize};
+
+use crate::color::Color;
+
+pub use generate::Style;
+pub use semantic::{Adjustment, ContrastConstraint, Role, SemanticTheme, Solution};
+pub use unresolved::{SlotValue, UnresolvedTheme};
+
+mod base16;
+mod generate;
+mod semantic;
+mod similarity;
+mod terminal;
+mod unresolved;
+mod vscode;
+
+/// A set of named color slots, like `background`, `foreground` or `color0` through `color15` for
+/// terminal themes. Deserializing resolves `$slot` references to other slots; see
+/// `UnresolvedTheme`.
+///
+/// Slots keep the order they were read or `set` in, and are written in that order; see
+/// `Theme::ordered` to sort them instead.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(try_from = "UnresolvedTheme")]
+pub struct Theme {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    pub colors: IndexMap<String, Color>,
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Theme::default()
+    }
+
+    pub fn get(&self, slot: &str) -> Option<&Color> {
+        self.colors.get(slot)
+    }
+
+    /// Changing the color of a slot keeps it where it was; a new slot goes last.
+    pub fn set(&mut self, slot: impl Into<String>, color: Color) {
+        self.colors.insert(slot.into(), color);
+    }
+
+    /// Wraps the theme so that it serializes its slots in `order`.
+    ///
+    /// ```
+    /// use led_json::{Color, NameOrder, Theme};
+    ///
+    /// let mut theme = Theme::new();
+    /// theme.set("foreground", Color::new(255, 255, 255));
+    /// theme.set("background", Color::new(0, 0, 0));
+    ///
+    /// let json = serde_json::to_string(&theme.ordered(NameOrder::Alphabetical)).unwrap();
+    ///
+    /// assert_eq!(json, r##"{"colors":{"background":"#000000","foreground":"#ffffff"}}"##);
+    /// ```
+    pub fn ordered(&self, order: NameOrder) -> Ordered<'_, Theme> {
+        Ordered { value: self, order }
+    }
+}
+
+/// The order slots of a `Theme` or swatches of a `Palette` are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameOrder {
+    /// The order they were read or added in.
+    #[default]
+    Insertion,
+
+    /// Sorted by name, so files written by different tools come out the same.
+    Alphabetical,
+}
+
+/// Wraps a `Theme` or `UnresolvedTheme` so that it serializes its slots in the given `NameOrder`.
+#[derive(Debug, Clone, Copy)]
+pub struct Ordered<'a, T> {
+    pub value: &'a T,
+    pub order: NameOrder,
+}
+
+/// A theme with its slots in the order they're written in.
+#[derive(Serialize)]
+#[serde(bound = "V: Serialize")]
+struct Written<'a, V> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: &'a Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: &'a Option<String>,
+
+    #[serde(serialize_with = "serialize_slots")]
+    colors: Vec<(&'a String, &'a V)>,
+}
+
+fn serialize_slots<V, S>(slots: &[(&String, &V)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(slots.iter().copied())
+}
+
+impl<'a, V: Serialize> Written<'a, V> {
+    fn new(name: &'a Option<String>, author: &'a Option<String>, colors: &'a IndexMap<String, V>, order: NameOrder) -> Self {
+        let mut colors: Vec<_> = colors.iter().collect();
+
+        if order == NameOrder::Alphabetical {
+            colors.sort_by_key(|(slot, _)| *slot);
+        }
+
+        Written { name, author, colors }
+    }
+}
+
+impl Serialize for Ordered<'_, Theme> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let theme = self.value;
+
+        Written::new(&theme.name, &theme.author, &theme.colors, self.order).serialize(serializer)
+    }
+}
+
+impl Serialize for Ordered<'_, UnresolvedTheme> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let theme = self.value;
+
+        Written::new(&theme.name, &theme.author, &theme.colors, self.order).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let mut theme = Theme::new();
+        theme.set("foreground", Color { red: 255, green: 255, blue: 255 });
+        theme.set("background", Color { red: 0, green: 0, blue: 0 });
+
+        let json = serde_json::to_string(&theme).unwrap();
+
+        assert_eq!(json, r##"{"colors":{"foreground":"#ffffff","background":"#000000"}}"##);
+        assert_eq!(serde_json::from_str::<Theme>(&json).unwrap(), theme);
+    }
+
+    #[test]
+    fn keeps_the_order_it_was_read_in() {
+        let json = r##"{"name":"Night","colors":{"zero":"#000000","one":"#111111","$":"#222222","a":"#333333"}}"##;
+        let theme: Theme = serde_json::from_str(json).unwrap();
+
+        assert_eq!(serde_json::to_string(&theme).unwrap(), json);
+        assert_eq!(serde_json::to_string(&theme.ordered(NameOrder::Insertion)).unwrap(), json);
+
+        let unresolved: UnresolvedTheme = serde_json::from_str(json).unwrap();
+
+        assert_eq!(serde_json::to_string(&unresolved).unwrap(), json);
+    }
+
+    #[test]
+    fn alphabetical() {
+        let mut theme = Theme::new();
+        theme.author = Some("Someone".into());
+        theme.set("b", Color { red: 0, green: 0, blue: 0 });
+        theme.set("a", Color { red: 255, green: 0, blue: 0 });
+        theme.set("b", Color { red: 255, green: 255, blue: 255 });
+
+        assert_eq!(
+            serde_json::to_string(&theme.ordered(NameOrder::Alphabetical)).unwrap(),
+            r##"{"author":"Someone","colors":{"a":"#ff0000","b":"#ffffff"}}"##
+        );
+
+        let unresolved = UnresolvedTheme::from(theme.clone());
+        let json = serde_json::to_string(&Ordered { value: &unresolved, order: NameOrder::Alphabetical }).unwrap();
+
+        assert_eq!(json, r##"{"author":"Someone","colors":{"a":"#ff0000","b":"#ffffff"}}"##);
+
+        // the order doesn't matter when comparing
+        let sorted: Theme = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(sorted, theme);
+        assert_eq!(serde_json::to_string(&theme).unwrap(), r##"{"author":"Someone","colors":{"b":"#ffffff","a":"#ff0000"}}"##);
+    }
+}
@@ -0,0 +1,241 @@
+//! [Base16](https://github.com/chriskempson/base16) and Base24 scheme files.
+//!
+//! Schemes are imported onto slots named `base00` through `base0F` (plus `base10` through `base17`
+//! for Base24). Both the classic flat layout and the newer one with the slots nested under
+//! `palette:` are read; the classic layout is written since every builder understands it.
+
+use std::fmt::Write;
+
+use crate::color::{parse_hex, Color};
+use crate::error::ThemeError;
+use crate::theme::Theme;
+
+fn slot_name(index: usize) -> String {
+    format!("base{:02X}", index)
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(['"', '\''])
+}
+
+/// `value` as a YAML double-quoted scalar. Anything printable, non-ASCII included, is written as
+/// it is; YAML's escapes stand in for quotes, backslashes and control characters.
+fn quoted(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            // YAML reads the line and paragraph separators as line breaks
+            '\u{2028}' => out.push_str("\\L"),
+            '\u{2029}' => out.push_str("\\P"),
+            c if c.is_control() && u32::from(c) <= 0xff => { let _ = write!(out, "\\x{:02x}", u32::from(c)); },
+            c if c.is_control() => { let _ = write!(out, "\\u{:04x}", u32::from(c)); },
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// `base0f` and `base0F` are both used in the wild; slots are stored uppercase.
+fn normalize_slot(key: &str) -> Option<String> {
+    let digits = key.strip_prefix("base")?;
+
+    if digits.len() != 2 {
+        return None
+    }
+
+    let index = usize::from_str_radix(digits, 16).ok()?;
+
+    if index < 24 { Some(slot_name(index)) } else { None }
+}
+
+impl Theme {
+    /// Reads a Base16 or Base24 YAML scheme.
+    pub fn from_base16(input: &str) -> std::result::Result<Theme, ThemeError> {
+        let mut theme = Theme::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), unquote(value)),
+                None => continue,
+            };
+
+            match key {
+                "scheme" | "name" => theme.name = Some(value.to_string()),
+                "author" => theme.author = Some(value.to_string()),
+                key => if let Some(slot) = normalize_slot(key) {
+                    let hex = value.strip_prefix('#').unwrap_or(value);
+
+                    let color = parse_hex(&format!("#{}", hex))
+                        .ok()
+                        .filter(|_| hex.len() == 6)
+                        .ok_or_else(|| ThemeError::InvalidColor { line: i + 1, value: value.to_string() })?;
+
+                    theme.set(slot, color);
+                },
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Fails if any of `base00` through `base0F` is missing.
+    pub fn to_base16(&self) -> std::result::Result<String, ThemeError> {
+        self.write_base(16)
+    }
+
+    /// Fails if any of `base00` through `base17` is missing.
+    pub fn to_base24(&self) -> std::result::Result<String, ThemeError> {
+        self.write_base(24)
+    }
+
+    fn write_base(&self, slots: usize) -> std::result::Result<String, ThemeError> {
+        let colors = (0..slots)
+            .map(|i| {
+                let slot = slot_name(i);
+
+                match self.get(&slot) {
+                    Some(color) => Ok((slot, color)),
+                    None => Err(ThemeError::MissingSlot(slot)),
+                }
+            })
+            .collect::<std::result::Result<Vec<(String, &Color)>, ThemeError>>()?;
+
+        let mut out = String::new();
+
+        // writing to a String can't fail
+        let _ = writeln!(out, "scheme: {}", quoted(self.name.as_deref().unwrap_or("")));
+        let _ = writeln!(out, "author: {}", quoted(self.author.as_deref().unwrap_or("")));
+
+        for (slot, c) in colors {
+            let _ = writeln!(out, "{}: \"{:02x}{:02x}{:02x}\"", slot, c.red, c.green, c.blue);
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOMORROW_NIGHT: &str = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+
+    #[test]
+    fn import_classic() {
+        let theme = Theme::from_base16(TOMORROW_NIGHT).unwrap();
+
+        assert_eq!(theme.name.as_deref(), Some("Tomorrow Night"));
+        assert_eq!(theme.author.as_deref(), Some("Chris Kempson (http://chriskempson.com)"));
+        assert_eq!(theme.colors.len(), 16);
+        assert_eq!(theme.get("base00"), Some(&Color { red: 0x1d, green: 0x1f, blue: 0x21 }));
+        assert_eq!(theme.get("base0F"), Some(&Color { red: 0xa3, green: 0x68, blue: 0x5a }));
+    }
+
+    #[test]
+    fn import_palette_layout() {
+        let theme = Theme::from_base16(r##"
+system: "base24"
+name: "Example"
+author: "Someone"
+variant: "dark"
+palette:
+  base00: "#000000"
+  base0f: "#ff0000"
+  base17: "#00ff00"
+"##).unwrap();
+
+        assert_eq!(theme.name.as_deref(), Some("Example"));
+        assert_eq!(theme.get("base0F"), Some(&Color { red: 255, green: 0, blue: 0 }));
+        assert_eq!(theme.get("base17"), Some(&Color { red: 0, green: 255, blue: 0 }));
+    }
+
+    #[test]
+    fn invalid_color() {
+        let result = Theme::from_base16("base00: \"fff\"\n");
+
+        assert_eq!(result, Err(ThemeError::InvalidColor { line: 1, value: "fff".to_string() }));
+    }
+
+    #[test]
+    fn round_trip() {
+        let theme = Theme::from_base16(TOMORROW_NIGHT).unwrap();
+        let exported = theme.to_base16().unwrap();
+
+        assert_eq!(exported.trim(), TOMORROW_NIGHT.trim());
+        assert_eq!(Theme::from_base16(&exported).unwrap(), theme);
+    }
+
+    #[test]
+    fn yaml_quoting() {
+        let mut theme = Theme::from_base16(TOMORROW_NIGHT).unwrap();
+        theme.name = Some("Café \"Noir\" \u{1f319}".to_string());
+        theme.author = Some("a\\b\tc\u{7}\u{85}".to_string());
+
+        let exported = theme.to_base16().unwrap();
+
+        assert!(exported.starts_with("scheme: \"Café \\\"Noir\\\" \u{1f319}\"\nauthor: \"a\\\\b\\tc\\x07\\x85\"\n"), "{}", exported);
+        assert_eq!(Theme::from_base16(&exported).unwrap().name.as_deref(), Some("Café \\\"Noir\\\" \u{1f319}"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_quoting_reads_back() {
+        let mut theme = Theme::from_base16(TOMORROW_NIGHT).unwrap();
+        theme.name = Some("Café \"Noir\" \u{1f319}".to_string());
+        theme.author = Some("a\\b\tc\u{7}\u{85}\u{2028}".to_string());
+
+        let document: serde_yaml::Value = serde_yaml::from_str(&theme.to_base16().unwrap()).unwrap();
+
+        assert_eq!(document["scheme"].as_str(), theme.name.as_deref());
+        assert_eq!(document["author"].as_str(), theme.author.as_deref());
+    }
+
+    #[test]
+    fn base24_requires_extra_slots() {
+        let theme = Theme::from_base16(TOMORROW_NIGHT).unwrap();
+
+        assert_eq!(theme.to_base24(), Err(ThemeError::MissingSlot("base10".to_string())));
+    }
+
+    #[test]
+    fn missing_slot() {
+        let mut theme = Theme::new();
+        theme.set("base00", Color { red: 0, green: 0, blue: 0 });
+
+        assert_eq!(theme.to_base16(), Err(ThemeError::MissingSlot("base01".to_string())));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::color::Color;
+use crate::contrast::ensure_contrast;
+use crate::rng::Rng;
+use crate::theme::Theme;
+
+/// The overall feel of a generated theme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    /// Light, soft accents.
+    Pastel,
+
+    /// Fully saturated accents.
+    Vivid,
+
+    /// Grayish, low-saturation accents.
+    Muted,
+}
+
+/// Minimum contrast ratio of `foreground` against `background` and `surface`.
+const TEXT_CONTRAST: f32 = 7.0;
+
+/// Minimum contrast ratio of every accent against `background`.
+const ACCENT_CONTRAST: f32 = 4.5;
+
+impl Style {
+    /// (saturation, lightness) ranges for accents.
+    fn accent(self) -> ((f32, f32), (f32, f32)) {
+        match self {
+            Style::Pastel => ((0.55, 0.75), (0.72, 0.82)),
+            Style::Vivid => ((0.85, 1.0), (0.48, 0.56)),
+            Style::Muted => ((0.18, 0.35), (0.45, 0.6)),
+        }
+    }
+}
+
+impl Theme {
+    /// Generates a complete theme from `seed`. The same seed and style always produce the same
+    /// theme.
+    ///
+    /// The slots are `background`, `surface`, `foreground`, `primary`, `secondary`, `success`,
+    /// `warning`, `danger` and `info`. `foreground` has a contrast ratio of at least 7 against
+    /// both `background` and `surface`, and every accent at least 4.5 against `background`.
+    pub fn generate(seed: u64, style: Style) -> Theme {
+        let mut rng = Rng(seed);
+
+        let hue = rng.range(0.0, 360.0);
+        let dark = rng.next_u64() & 1 == 0;
+        let ((s_low, s_high), (l_low, l_high)) = style.accent();
+
+        let tint = rng.range(0.08, 0.2);
+        let (background, surface) = if dark {
+            (Color::from_hsl(hue, tint, rng.range(0.07, 0.12)), Color::from_hsl(hue, tint, rng.range(0.15, 0.2)))
+        } else {
+            (Color::from_hsl(hue, tint, rng.range(0.96, 0.99)), Color::from_hsl(hue, tint, rng.range(0.88, 0.92)))
+        };
+
+        let foreground = Color::from_hsl(hue, tint, if dark { 0.92 } else { 0.1 });
+        let foreground = ensure_contrast(foreground, &background, TEXT_CONTRAST);
+        let foreground = ensure_contrast(foreground, &surface, TEXT_CONTRAST);
+
+        let mut accent = |hue: f32| {
+            let color = Color::from_hsl(hue, rng.range(s_low, s_high), rng.range(l_low, l_high));
+
+            ensure_contrast(color, &background, ACCENT_CONTRAST)
+        };
+
+        let primary = accent(hue);
+        let secondary = accent(hue + 150.0);
+        let success = accent(130.0);
+        let warning = accent(40.0);
+        let danger = accent(0.0);
+        let info = accent(210.0);
+
+        let mut theme = Theme::new();
+        theme.name = Some(format!("generated-{:?}-{}", style, seed).to_lowercase());
+
+        theme.set("background", background);
+        theme.set("surface", surface);
+        theme.set("foreground", foreground);
+        theme.set("primary", primary);
+        theme.set("secondary", secondary);
+        theme.set("success", success);
+        theme.set("warning", warning);
+        theme.set("danger", danger);
+        theme.set("info", info);
+
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLES: [Style; 3] = [Style::Pastel, Style::Vivid, Style::Muted];
+    const ACCENTS: [&str; 6] = ["primary", "secondary", "success", "warning", "danger", "info"];
+
+    #[test]
+    fn deterministic() {
+        for style in STYLES.iter() {
+            assert_eq!(Theme::generate(42, *style), Theme::generate(42, *style));
+        }
+
+        assert_ne!(Theme::generate(1, Style::Vivid), Theme::generate(2, Style::Vivid));
+    }
+
+    #[test]
+    fn complete() {
+        let theme = Theme::generate(7, Style::Pastel);
+
+        for slot in ["background", "surface", "foreground"].iter().chain(ACCENTS.iter()) {
+            assert!(theme.get(slot).is_some(), "missing {}", slot);
+        }
+    }
+
+    #[test]
+    fn contrast() {
+        for seed in 0..200 {
+            for style in STYLES.iter() {
+                let theme = Theme::generate(seed, *style);
+                let background = theme.get("background").unwrap();
+                let surface = theme.get("surface").unwrap();
+                let foreground = theme.get("foreground").unwrap();
+
+                assert!(foreground.contrast_ratio(background) >= TEXT_CONTRAST);
+                assert!(foreground.contrast_ratio(surface) >= TEXT_CONTRAST);
+
+                for slot in ACCENTS.iter() {
+                    let ratio = theme.get(slot).unwrap().contrast_ratio(background);
+
+                    assert!(ratio >= ACCENT_CONTRAST, "seed {} {:?} {}: {}", seed, style, slot, ratio);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn styles_differ_in_saturation() {
+        let saturation = |style| Theme::generate(3, style).get("primary").unwrap().to_hsl().saturation;
+
+        assert!(saturation(Style::Vivid) > saturation(Style::Muted));
+    }
+}
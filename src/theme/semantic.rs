@@ -0,0 +1,229 @@
+use serde::{Serialize, Deserialize};
+
+use crate::color::Color;
+use crate::contrast::ensure_contrast;
+
+/// A slot in a `SemanticTheme`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Primary,
+    Success,
+    Warning,
+    Danger,
+    Surface,
+    OnSurface,
+}
+
+/// Colors by what they're used for rather than by name.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SemanticTheme {
+    pub primary: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub danger: Color,
+    pub surface: Color,
+    pub on_surface: Color,
+}
+
+/// `foreground` must have a contrast ratio of at least `minimum` against `background`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct ContrastConstraint {
+    pub foreground: Role,
+    pub background: Role,
+    pub minimum: f32,
+}
+
+/// A role whose color the solver had to change to satisfy `constraint`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjustment {
+    pub constraint: ContrastConstraint,
+    pub role: Role,
+    pub from: Color,
+    pub to: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub theme: SemanticTheme,
+
+    /// In the order they were made. A role can show up more than once if constraints pulled it in
+    /// different directions.
+    pub adjustments: Vec<Adjustment>,
+
+    /// Constraints that still don't hold, because they conflict or ask for more contrast than
+    /// is possible.
+    pub unsatisfied: Vec<ContrastConstraint>,
+}
+
+/// Constraints get re-checked after every pass since fixing one can break another. This bounds
+/// how long conflicting constraints can go back and forth.
+const MAX_PASSES: usize = 8;
+
+impl ContrastConstraint {
+    pub fn new(foreground: Role, background: Role, minimum: f32) -> Self {
+        ContrastConstraint { foreground, background, minimum }
+    }
+
+    pub fn is_satisfied(&self, theme: &SemanticTheme) -> bool {
+        theme.get(self.foreground).contrast_ratio(theme.get(self.background)) >= self.minimum
+    }
+}
+
+impl SemanticTheme {
+    pub fn get(&self, role: Role) -> &Color {
+        match role {
+            Role::Primary => &self.primary,
+            Role::Success => &self.success,
+            Role::Warning => &self.warning,
+            Role::Danger => &self.danger,
+            Role::Surface => &self.surface,
+            Role::OnSurface => &self.on_surface,
+        }
+    }
+
+    pub fn get_mut(&mut self, role: Role) -> &mut Color {
+        match role {
+            Role::Primary => &mut self.primary,
+            Role::Success => &mut self.success,
+            Role::Warning => &mut self.warning,
+            Role::Danger => &mut self.danger,
+            Role::Surface => &mut self.surface,
+            Role::OnSurface => &mut self.on_surface,
+        }
+    }
+
+    /// WCAG AA: 4.5 for text on the surface, 3.0 for every accent against the surface.
+    pub fn default_constraints() -> Vec<ContrastConstraint> {
+        vec![
+            ContrastConstraint::new(Role::OnSurface, Role::Surface, 4.5),
+            ContrastConstraint::new(Role::Primary, Role::Surface, 3.0),
+            ContrastConstraint::new(Role::Success, Role::Surface, 3.0),
+            ContrastConstraint::new(Role::Warning, Role::Surface, 3.0),
+            ContrastConstraint::new(Role::Danger, Role::Surface, 3.0),
+        ]
+    }
+
+    /// Adjusts the lightness of each constraint's foreground role, keeping its hue and
+    /// saturation, until every constraint holds or no more progress can be made. Background roles
+    /// are only changed when they are also the foreground of another constraint.
+    pub fn solve(&self, constraints: &[ContrastConstraint]) -> Solution {
+        let mut theme = self.clone();
+        let mut adjustments = vec![];
+
+        for _ in 0..MAX_PASSES {
+            let mut changed = false;
+
+            for constraint in constraints {
+                if constraint.is_satisfied(&theme) {
+                    continue
+                }
+
+                let from = *theme.get(constraint.foreground);
+                let to = ensure_contrast(from, theme.get(constraint.background), constraint.minimum);
+
+                if to != from {
+                    *theme.get_mut(constraint.foreground) = to;
+                    adjustments.push(Adjustment { constraint: *constraint, role: constraint.foreground, from, to });
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break
+            }
+        }
+
+        let unsatisfied = constraints.iter()
+            .filter(|c| !c.is_satisfied(&theme))
+            .copied()
+            .collect();
+
+        Solution { theme, adjustments, unsatisfied }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme() -> SemanticTheme {
+        SemanticTheme {
+            primary: Color { red: 0x1a, green: 0x73, blue: 0xe8 },
+            success: Color { red: 0x1e, green: 0x8e, blue: 0x3e },
+            warning: Color { red: 0xf9, green: 0xab, blue: 0x00 },
+            danger: Color { red: 0xd9, green: 0x30, blue: 0x25 },
+            surface: Color { red: 0xff, green: 0xff, blue: 0xff },
+            on_surface: Color { red: 0x20, green: 0x21, blue: 0x24 },
+        }
+    }
+
+    #[test]
+    fn reports_forced_adjustments() {
+        let solution = theme().solve(&SemanticTheme::default_constraints());
+
+        // yellow on white is the only thing that fails AA here
+        assert_eq!(solution.adjustments.len(), 1);
+        assert_eq!(solution.adjustments[0].role, Role::Warning);
+        assert_eq!(solution.adjustments[0].from, theme().warning);
+        assert!(solution.unsatisfied.is_empty());
+
+        let hsl = solution.theme.warning.to_hsl();
+
+        assert!(solution.theme.warning.contrast_ratio(&solution.theme.surface) >= 3.0);
+        assert!((hsl.hue - theme().warning.to_hsl().hue).abs() < 2.0);
+        assert_eq!(solution.theme.primary, theme().primary);
+    }
+
+    #[test]
+    fn already_satisfied() {
+        let constraints = [ContrastConstraint::new(Role::OnSurface, Role::Surface, 4.5)];
+        let solution = theme().solve(&constraints);
+
+        assert!(solution.adjustments.is_empty());
+        assert_eq!(solution.theme, theme());
+    }
+
+    #[test]
+    fn chained_constraints() {
+        let mut start = theme();
+        start.surface = Color { red: 0x80, green: 0x80, blue: 0x80 };
+        start.on_surface = Color { red: 0x90, green: 0x90, blue: 0x90 };
+        start.primary = Color { red: 0x10, green: 0x10, blue: 0x30 };
+
+        // primary is fine against on-surface until on-surface gets darkened to fix its own
+        // constraint
+        let constraints = [
+            ContrastConstraint::new(Role::OnSurface, Role::Surface, 4.5),
+            ContrastConstraint::new(Role::Primary, Role::OnSurface, 3.0),
+        ];
+        let solution = start.solve(&constraints);
+
+        assert!(solution.unsatisfied.is_empty(), "{:?}", solution.unsatisfied);
+        assert_eq!(solution.adjustments.iter().map(|a| a.role).collect::<Vec<_>>(), vec![Role::OnSurface, Role::Primary]);
+    }
+
+    #[test]
+    fn impossible_constraint() {
+        let constraints = [ContrastConstraint::new(Role::OnSurface, Role::Surface, 22.0)];
+        let solution = theme().solve(&constraints);
+
+        assert_eq!(solution.unsatisfied, constraints.to_vec());
+        assert_eq!(solution.theme.on_surface, Color { red: 0, green: 0, blue: 0 });
+    }
+
+    #[test]
+    fn serde() {
+        let json = serde_json::to_value(theme()).unwrap();
+
+        assert_eq!(json["on-surface"], "#202124");
+        assert_eq!(serde_json::from_value::<SemanticTheme>(json).unwrap(), theme());
+
+        let constraint: ContrastConstraint = serde_json::from_str(
+            r#"{ "foreground": "on-surface", "background": "surface", "minimum": 7.0 }"#
+        ).unwrap();
+
+        assert_eq!(constraint, ContrastConstraint::new(Role::OnSurface, Role::Surface, 7.0));
+    }
+}
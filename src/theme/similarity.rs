@@ -0,0 +1,122 @@
+use crate::color::Color;
+use crate::theme::Theme;
+
+const HUE_BINS: usize = 12;
+
+/// Histogram bin for every color that is too desaturated, dark or light to have a meaningful hue.
+const GRAY_BIN: usize = HUE_BINS;
+
+/// Average slot differences at or beyond this are treated as completely different.
+const MAX_DELTA_E: f32 = 100.0;
+
+/// How much the per-slot comparison counts when the themes share slots. The rest comes from the
+/// hue distribution.
+const SLOT_WEIGHT: f32 = 0.7;
+
+fn hue_histogram<'a>(colors: impl Iterator<Item = &'a Color>) -> [f32; HUE_BINS + 1] {
+    let mut bins = [0.0; HUE_BINS + 1];
+    let mut total = 0.0;
+
+    for color in colors {
+        let hsl = color.to_hsl();
+
+        let bin = if hsl.saturation < 0.15 || hsl.lightness < 0.1 || hsl.lightness > 0.95 {
+            GRAY_BIN
+        } else {
+            ((hsl.hue / 360.0 * HUE_BINS as f32) as usize).min(HUE_BINS - 1)
+        };
+
+        bins[bin] += 1.0;
+        total += 1.0;
+    }
+
+    if total > 0.0 {
+        bins.iter_mut().for_each(|b| *b /= total);
+    }
+
+    bins
+}
+
+impl Theme {
+    /// How alike two themes are, from `0.0` (nothing in common) to `1.0` (the same colors).
+    ///
+    /// Slots present in both themes are compared with CIEDE2000 and averaged. That's combined with
+    /// the overlap of the two themes' hue histograms, so themes with different slot names can
+    /// still be compared by their overall palette. The result is symmetric.
+    pub fn similarity(&self, other: &Theme) -> f32 {
+        let distances = self.colors.iter()
+            .filter_map(|(slot, color)| other.get(slot).map(|o| color.delta_e(o)))
+            .collect::<Vec<f32>>();
+
+        let ours = hue_histogram(self.colors.values());
+        let theirs = hue_histogram(other.colors.values());
+        let hue_score = ours.iter().zip(theirs.iter()).map(|(a, b)| a.min(*b)).sum::<f32>();
+
+        if distances.is_empty() {
+            return hue_score.clamp(0.0, 1.0)
+        }
+
+        let mean = distances.iter().sum::<f32>() / distances.len() as f32;
+        let slot_score = 1.0 - (mean / MAX_DELTA_E).min(1.0);
+
+        (SLOT_WEIGHT * slot_score + (1.0 - SLOT_WEIGHT) * hue_score).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(colors: &[(&str, u8, u8, u8)]) -> Theme {
+        let mut theme = Theme::new();
+
+        for (slot, red, green, blue) in colors {
+            theme.set(*slot, Color { red: *red, green: *green, blue: *blue });
+        }
+
+        theme
+    }
+
+    fn dark() -> Theme {
+        theme(&[("background", 0x1d, 0x1f, 0x21), ("foreground", 0xc5, 0xc8, 0xc6), ("accent", 0x81, 0xa2, 0xbe)])
+    }
+
+    #[test]
+    fn identical() {
+        assert!((dark().similarity(&dark()) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn similar_beats_different() {
+        let close = theme(&[("background", 0x20, 0x20, 0x24), ("foreground", 0xc0, 0xc0, 0xc0), ("accent", 0x7a, 0x9e, 0xc0)]);
+        let light = theme(&[("background", 0xff, 0xff, 0xff), ("foreground", 0x10, 0x10, 0x10), ("accent", 0xd0, 0x30, 0x20)]);
+
+        let close_score = dark().similarity(&close);
+        let light_score = dark().similarity(&light);
+
+        assert!(close_score > 0.9, "{}", close_score);
+        assert!(close_score > light_score, "{} <= {}", close_score, light_score);
+    }
+
+    #[test]
+    fn symmetric() {
+        let other = theme(&[("background", 0xff, 0xff, 0xff), ("accent", 0xd0, 0x30, 0x20)]);
+
+        assert_eq!(dark().similarity(&other), other.similarity(&dark()));
+    }
+
+    #[test]
+    fn no_shared_slots_uses_hues() {
+        let blues = theme(&[("a", 0, 0, 200), ("b", 30, 30, 220)]);
+        let more_blues = theme(&[("x", 10, 10, 190)]);
+        let reds = theme(&[("y", 200, 0, 0)]);
+
+        assert_eq!(blues.similarity(&more_blues), 1.0);
+        assert_eq!(blues.similarity(&reds), 0.0);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Theme::new().similarity(&dark()), 0.0);
+    }
+}
@@ -0,0 +1,269 @@
+//! Importers for terminal color schemes.
+//!
+//! Every importer maps onto the same slot names so themes can be converted between them:
+//! `foreground`, `background`, `cursor`, `cursor_text`, `selection_foreground`,
+//! `selection_background` and `color0` through `color15` (kitty also allows up to `color255`).
+//! Settings that aren't colors are skipped; values that look like colors but don't parse are
+//! errors.
+
+use std::collections::HashMap;
+
+use crate::color::{Color, ParseOptions};
+use crate::error::ThemeError;
+use crate::theme::Theme;
+
+const ANSI_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+fn looks_like_color(value: &str) -> bool {
+    value.starts_with('#') || value.starts_with("0x")
+}
+
+/// Accepts `#rgb`, `#rrggbb` and alacritty's `0xrrggbb`.
+fn parse_color(value: &str, line: usize) -> std::result::Result<Color, ThemeError> {
+    let options = ParseOptions { allow_0x: true, ..ParseOptions::default() };
+
+    Color::parse_with(value, options).map_err(|_| ThemeError::InvalidColor { line, value: value.to_string() })
+}
+
+/// Returns the line number (1-based) along with each line that isn't blank or a comment.
+fn content_lines<'a>(input: &'a str, comment: &'a str) -> impl Iterator<Item = (usize, &'a str)> {
+    input.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(move |(_, line)| !line.is_empty() && !line.starts_with(comment))
+}
+
+impl Theme {
+    /// Reads `.Xresources` color definitions like `*.color0: #1d1f21` or `URxvt*background: #000`.
+    /// `#define` macros are expanded, as used by most published Xresources themes.
+    pub fn from_xresources(input: &str) -> std::result::Result<Theme, ThemeError> {
+        let mut theme = Theme::new();
+        let mut defines: HashMap<&str, &str> = HashMap::new();
+
+        for (line, content) in content_lines(input, "!") {
+            if let Some(define) = content.strip_prefix("#define") {
+                let mut parts = define.split_whitespace();
+
+                if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                    defines.insert(name, value);
+                }
+
+                continue
+            }
+
+            let (key, value) = match content.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            let value = defines.get(value).copied().unwrap_or(value);
+
+            if !looks_like_color(value) {
+                continue
+            }
+
+            // `URxvt*color0`, `*.color0` and `color0` all name the same slot
+            let slot = key.rsplit(['.', '*']).next().unwrap_or(key);
+
+            let slot = match slot {
+                "cursorColor" => "cursor",
+                slot => slot,
+            };
+
+            theme.set(slot, parse_color(value, line)?);
+        }
+
+        Ok(theme)
+    }
+
+    /// Reads a kitty theme (`kitty.conf` syntax): `foreground #dddddd`.
+    pub fn from_kitty(input: &str) -> std::result::Result<Theme, ThemeError> {
+        let mut theme = Theme::new();
+
+        for (line, content) in content_lines(input, "#") {
+            let mut parts = content.split_whitespace();
+
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key, value),
+                _ => continue,
+            };
+
+            if !looks_like_color(value) {
+                continue
+            }
+
+            theme.set(key, parse_color(value, line)?);
+        }
+
+        Ok(theme)
+    }
+
+    /// Reads the `[colors.*]` tables of an alacritty TOML config. Only the flat
+    /// `key = "value"` form that alacritty themes are published in is understood.
+    pub fn from_alacritty(input: &str) -> std::result::Result<Theme, ThemeError> {
+        let mut theme = Theme::new();
+        let mut section = String::new();
+
+        for (line, content) in content_lines(input, "#") {
+            if content.starts_with('[') {
+                section = content.trim_matches(['[', ']']).trim().to_string();
+                continue
+            }
+
+            let (key, value) = match content.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim().trim_matches(['"', '\''])),
+                None => continue,
+            };
+
+            if !looks_like_color(value) {
+                continue
+            }
+
+            let slot = match (section.as_str(), key) {
+                ("colors.primary", "foreground") => "foreground".to_string(),
+                ("colors.primary", "background") => "background".to_string(),
+                ("colors.cursor", "cursor") => "cursor".to_string(),
+                ("colors.cursor", "text") => "cursor_text".to_string(),
+                ("colors.selection", "background") => "selection_background".to_string(),
+                ("colors.selection", "text") => "selection_foreground".to_string(),
+                ("colors.normal", name) | ("colors.bright", name) => {
+                    let offset = if section == "colors.bright" { 8 } else { 0 };
+
+                    match ANSI_NAMES.iter().position(|n| *n == name) {
+                        Some(i) => format!("color{}", i + offset),
+                        None => continue,
+                    }
+                },
+                _ => continue,
+            };
+
+            theme.set(slot, parse_color(value, line)?);
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    mod xresources {
+        use super::*;
+
+        #[test]
+        fn basic() {
+            let theme = Theme::from_xresources("
+                ! comment
+                *.foreground: #c5c8c6
+                *background:  #1d1f21
+                URxvt*color1: #cc6666
+                *.cursorColor: #fff
+                *.font: xft:Monospace:size=10
+            ").unwrap();
+
+            assert_eq!(theme.get("foreground"), Some(&rgb(0xc5, 0xc8, 0xc6)));
+            assert_eq!(theme.get("background"), Some(&rgb(0x1d, 0x1f, 0x21)));
+            assert_eq!(theme.get("color1"), Some(&rgb(0xcc, 0x66, 0x66)));
+            assert_eq!(theme.get("cursor"), Some(&rgb(255, 255, 255)));
+            assert_eq!(theme.colors.len(), 4);
+        }
+
+        #[test]
+        fn defines() {
+            let theme = Theme::from_xresources("
+                #define base00 #1d1f21
+                *.background: base00
+                *.color0:     base00
+            ").unwrap();
+
+            assert_eq!(theme.get("background"), Some(&rgb(0x1d, 0x1f, 0x21)));
+            assert_eq!(theme.get("color0"), Some(&rgb(0x1d, 0x1f, 0x21)));
+        }
+
+        #[test]
+        fn invalid_color() {
+            let result = Theme::from_xresources("*.color0: #zzzzzz\n");
+
+            assert_eq!(result, Err(ThemeError::InvalidColor { line: 1, value: "#zzzzzz".to_string() }));
+        }
+    }
+
+    mod kitty {
+        use super::*;
+
+        #[test]
+        fn basic() {
+            let theme = Theme::from_kitty("
+                # vim:ft=kitty
+                foreground           #dddddd
+                background           #000000
+                selection_background #fffacd
+                color12              #0000ff
+                cursor_shape         beam
+            ").unwrap();
+
+            assert_eq!(theme.get("foreground"), Some(&rgb(0xdd, 0xdd, 0xdd)));
+            assert_eq!(theme.get("selection_background"), Some(&rgb(0xff, 0xfa, 0xcd)));
+            assert_eq!(theme.get("color12"), Some(&rgb(0, 0, 255)));
+            assert_eq!(theme.colors.len(), 4);
+        }
+
+        #[test]
+        fn invalid_color() {
+            let result = Theme::from_kitty("\ncolor0 #12345\n");
+
+            assert_eq!(result, Err(ThemeError::InvalidColor { line: 2, value: "#12345".to_string() }));
+        }
+    }
+
+    mod alacritty {
+        use super::*;
+
+        #[test]
+        fn basic() {
+            let theme = Theme::from_alacritty(r##"
+                [colors.primary]
+                background = '#1d1f21'
+                foreground = "0xc5c8c6"
+
+                [colors.cursor]
+                text = '#1d1f21'
+                cursor = '#ffffff'
+
+                [colors.normal]
+                black = '#000000'
+                red   = '#cc6666'
+
+                [colors.bright]
+                black = '#666666'
+                white = '#ffffff'
+
+                [font]
+                size = 12
+            "##).unwrap();
+
+            assert_eq!(theme.get("background"), Some(&rgb(0x1d, 0x1f, 0x21)));
+            assert_eq!(theme.get("foreground"), Some(&rgb(0xc5, 0xc8, 0xc6)));
+            assert_eq!(theme.get("cursor_text"), Some(&rgb(0x1d, 0x1f, 0x21)));
+            assert_eq!(theme.get("cursor"), Some(&rgb(255, 255, 255)));
+            assert_eq!(theme.get("color0"), Some(&rgb(0, 0, 0)));
+            assert_eq!(theme.get("color1"), Some(&rgb(0xcc, 0x66, 0x66)));
+            assert_eq!(theme.get("color8"), Some(&rgb(0x66, 0x66, 0x66)));
+            assert_eq!(theme.get("color15"), Some(&rgb(255, 255, 255)));
+            assert_eq!(theme.colors.len(), 8);
+        }
+
+        #[test]
+        fn converts_to_kitty_slots() {
+            let alacritty = Theme::from_alacritty("[colors.normal]\nblue = '#0000ff'\n").unwrap();
+            let kitty = Theme::from_kitty("color4 #0000ff\n").unwrap();
+
+            assert_eq!(alacritty, kitty);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Themes whose slots can refer to other slots, like `"border": "$foreground"`, kept as they are
+//! until something asks for the colors.
+
+use indexmap::IndexMap;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::color::{parse_hex, Color};
+use crate::error::ThemeError;
+use crate::theme::Theme;
+
+/// What a slot of an `UnresolvedTheme` holds: a color, or `$name` for the color of slot `name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotValue {
+    Color(Color),
+
+    /// The name of the slot, without the `$`.
+    Reference(String),
+}
+
+/// A `Theme` as it's written, with its `$references` left in place, for tools that edit theme
+/// files and have to write them back without resolving them. Colors are written back in
+/// canonical form, in the order they were read in.
+///
+/// `Theme` deserializes through it, resolving the references.
+///
+/// ```
+/// use led_json::{Color, SlotValue, UnresolvedTheme};
+///
+/// let json = r##"{"colors":{"border":"$foreground","foreground":"#ffffff"}}"##;
+/// let mut theme: UnresolvedTheme = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(theme.colors["border"], SlotValue::Reference("foreground".into()));
+/// assert_eq!(serde_json::to_string(&theme).unwrap(), json);
+///
+/// theme.colors.insert("foreground".into(), SlotValue::Color(Color::new(0, 0, 0)));
+///
+/// assert_eq!(theme.resolve_slot("border"), Ok(Color::new(0, 0, 0)));
+/// assert_eq!(theme.resolve().unwrap().get("border"), Some(&Color::new(0, 0, 0)));
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct UnresolvedTheme {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    pub colors: IndexMap<String, SlotValue>,
+}
+
+impl UnresolvedTheme {
+    pub fn new() -> Self {
+        UnresolvedTheme::default()
+    }
+
+    /// The color of `slot`, following references through as many slots as it takes.
+    pub fn resolve_slot(&self, slot: &str) -> Result<Color, ThemeError> {
+        let mut seen = BTreeSet::new();
+        let mut current = slot;
+
+        loop {
+            if !seen.insert(current) {
+                return Err(ThemeError::ReferenceCycle(slot.into()))
+            }
+
+            match self.colors.get(current) {
+                Some(SlotValue::Color(color)) => return Ok(*color),
+                Some(SlotValue::Reference(reference)) if self.colors.contains_key(reference) => current = reference,
+                Some(SlotValue::Reference(reference)) => {
+                    return Err(ThemeError::UnknownReference { slot: current.into(), reference: reference.clone() })
+                },
+                None => return Err(ThemeError::MissingSlot(slot.into())),
+            }
+        }
+    }
+
+    /// The theme with every reference replaced by the color it refers to.
+    pub fn resolve(&self) -> Result<Theme, ThemeError> {
+        let colors = self.colors.keys()
+            .map(|slot| Ok((slot.clone(), self.resolve_slot(slot)?)))
+            .collect::<Result<_, ThemeError>>()?;
+
+        Ok(Theme { name: self.name.clone(), author: self.author.clone(), colors })
+    }
+}
+
+impl From<Theme> for UnresolvedTheme {
+    fn from(theme: Theme) -> Self {
+        UnresolvedTheme {
+            name: theme.name,
+            author: theme.author,
+            colors: theme.colors.into_iter().map(|(slot, color)| (slot, SlotValue::Color(color))).collect(),
+        }
+    }
+}
+
+impl TryFrom<UnresolvedTheme> for Theme {
+    type Error = ThemeError;
+
+    fn try_from(theme: UnresolvedTheme) -> Result<Self, ThemeError> {
+        theme.resolve()
+    }
+}
+
+impl Serialize for SlotValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            SlotValue::Color(color) => color.serialize(serializer),
+            SlotValue::Reference(_) if !serializer.is_human_readable() => {
+                Err(ser::Error::custom("references can only be written in human-readable formats"))
+            },
+            SlotValue::Reference(slot) => serializer.collect_str(&format_args!("${}", slot)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SlotValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return Color::deserialize(deserializer).map(SlotValue::Color)
+        }
+
+        struct ColorOrReference;
+
+        // everything but `$` strings is handed on to `Color`
+        impl<'de> Visitor<'de> for ColorOrReference {
+            type Value = SlotValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a color or a `$slot` reference")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<SlotValue, E>
+            where
+                E: de::Error,
+            {
+                match value.strip_prefix('$') {
+                    Some(slot) => Ok(SlotValue::Reference(slot.into())),
+                    None => parse_hex(value).map(SlotValue::Color).map_err(E::custom),
+                }
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<SlotValue, E>
+            where
+                E: de::Error,
+            {
+                Color::deserialize(de::value::U64Deserializer::new(value)).map(SlotValue::Color)
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<SlotValue, E>
+            where
+                E: de::Error,
+            {
+                Color::deserialize(de::value::I64Deserializer::new(value)).map(SlotValue::Color)
+            }
+
+            fn visit_seq<S>(self, seq: S) -> Result<SlotValue, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                Color::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(SlotValue::Color)
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<SlotValue, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                Color::deserialize(de::value::MapAccessDeserializer::new(map)).map(SlotValue::Color)
+            }
+        }
+
+        deserializer.deserialize_any(ColorOrReference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+    fn theme(json: &str) -> UnresolvedTheme {
+        serde_json::from_str(json).unwrap()
+    }
+
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn round_trips_references() {
+            let json = r##"{"name":"Night","colors":{"a":"$b","b":"$c","c":"#ffffff","d":"$missing"}}"##;
+
+            assert_eq!(serde_json::to_string(&theme(json)).unwrap(), json);
+        }
+
+        #[test]
+        fn every_color_form() {
+            let theme = theme(r##"{"colors":{"a":"#FFF","b":16777215,"c":[255,255,255],"d":{"r":255,"g":255,"b":255}}}"##);
+
+            assert!(theme.colors.values().all(|value| *value == SlotValue::Color(WHITE)));
+        }
+
+        #[test]
+        fn invalid_colors() {
+            assert!(serde_json::from_str::<UnresolvedTheme>(r#"{"colors":{"a":"fff"}}"#).is_err());
+            assert!(serde_json::from_str::<UnresolvedTheme>(r#"{"colors":{"a":true}}"#).is_err());
+        }
+
+        #[test]
+        fn theme_resolves_when_deserialized() {
+            let theme: Theme = serde_json::from_str(r##"{"colors":{"border":"$fg","fg":"#fff"}}"##).unwrap();
+
+            assert_eq!(theme.get("border"), Some(&WHITE));
+
+            let err = serde_json::from_str::<Theme>(r#"{"colors":{"border":"$fg"}}"#).unwrap_err();
+
+            assert!(err.to_string().contains("refers to $fg"), "{}", err);
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        #[test]
+        fn chains() {
+            let theme = theme(r##"{"colors":{"a":"$b","b":"$c","c":"#fff"}}"##);
+
+            assert_eq!(theme.resolve_slot("a"), Ok(WHITE));
+            assert_eq!(theme.resolve().unwrap().colors.len(), 3);
+        }
+
+        #[test]
+        fn errors() {
+            let theme = theme(r##"{"colors":{"a":"$b","b":"$nowhere","c":"$d","d":"$c","e":"$e"}}"##);
+
+            assert_eq!(
+                theme.resolve_slot("a"),
+                Err(ThemeError::UnknownReference { slot: "b".into(), reference: "nowhere".into() })
+            );
+            assert_eq!(theme.resolve_slot("c"), Err(ThemeError::ReferenceCycle("c".into())));
+            assert_eq!(theme.resolve_slot("e"), Err(ThemeError::ReferenceCycle("e".into())));
+            assert_eq!(theme.resolve_slot("f"), Err(ThemeError::MissingSlot("f".into())));
+            assert!(theme.resolve().is_err());
+        }
+
+        #[test]
+        fn from_theme() {
+            let mut resolved = Theme::new();
+            resolved.set("background", WHITE);
+
+            assert_eq!(UnresolvedTheme::from(resolved.clone()).resolve(), Ok(resolved));
+        }
+    }
+}
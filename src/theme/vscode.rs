@@ -0,0 +1,255 @@
+//! VS Code color themes (`*-color-theme.json`).
+//!
+//! Workbench colors keep their VS Code names (`editor.background`, `statusBar.foreground`, ...).
+//! Each `tokenColors` scope becomes `token.<scope>.foreground` / `token.<scope>.background`.
+//! Colors with an alpha channel (`#rrggbbaa`, `#rgba`) are imported without it.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use serde_json::Value;
+
+use crate::color::{parse_hex, Color};
+use crate::error::ThemeError;
+use crate::theme::Theme;
+
+/// Theme files are JSON with comments and trailing commas, which serde_json doesn't accept. This
+/// drops comments first, then the trailing commas, so a comma followed by a comment and a `}` is
+/// caught too. Strings are left alone.
+fn strip_jsonc(input: &str) -> String {
+    let uncommented = outside_strings(input, |c, chars, out| match (c, chars.peek()) {
+        ('/', Some('/')) => {
+            while let Some(&next) = chars.peek() {
+                if next == '\n' { break }
+                chars.next();
+            }
+        },
+        ('/', Some('*')) => {
+            chars.next();
+
+            let mut last = ' ';
+            for next in chars.by_ref() {
+                if last == '*' && next == '/' { break }
+                last = next;
+            }
+        },
+        _ => out.push(c),
+    });
+
+    outside_strings(&uncommented, |c, chars, out| {
+        if c == ',' {
+            let rest = chars.clone().find(|c| !c.is_whitespace());
+
+            if rest == Some('}') || rest == Some(']') {
+                return
+            }
+        }
+
+        out.push(c)
+    })
+}
+
+/// Copies `input`, handing every character outside a string to `f` along with the rest of the
+/// input, which it can skip ahead in.
+fn outside_strings<F>(input: &str, mut f: F) -> String
+where
+    F: FnMut(char, &mut Peekable<Chars<'_>>, &mut String),
+{
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+
+            match c {
+                '\\' => if let Some(escaped) = chars.next() { out.push(escaped) },
+                '"' => in_string = false,
+                _ => {},
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else {
+            f(c, &mut chars, &mut out);
+        }
+    }
+
+    out
+}
+
+fn parse_color(slot: &str, value: &str) -> std::result::Result<Color, ThemeError> {
+    let invalid = || ThemeError::InvalidSlot { slot: slot.to_string(), value: value.to_string() };
+
+    // `get` rather than indexing: the cut can land inside a multi-byte character
+    let opaque = match value.len() {
+        5 | 9 => value.get(..value.len() - value.len() / 4).ok_or_else(invalid)?,
+        _ => value,
+    };
+
+    parse_hex(opaque).map_err(|_| invalid())
+}
+
+fn scopes(scope: Option<&Value>) -> Vec<String> {
+    match scope {
+        Some(Value::String(s)) => s.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+        Some(Value::Array(items)) => items.iter().filter_map(|s| s.as_str()).map(|s| s.trim().to_string()).collect(),
+        _ => vec![],
+    }
+}
+
+impl Theme {
+    pub fn from_vscode(input: &str) -> std::result::Result<Theme, ThemeError> {
+        let document: Value = serde_json::from_str(&strip_jsonc(input))
+            .map_err(|e| ThemeError::InvalidJson(e.to_string()))?;
+
+        let mut theme = Theme::new();
+
+        theme.name = document.get("name").and_then(|n| n.as_str()).map(String::from);
+
+        if let Some(colors) = document.get("colors").and_then(|c| c.as_object()) {
+            for (slot, value) in colors {
+                if let Some(value) = value.as_str() {
+                    theme.set(slot.as_str(), parse_color(slot, value)?);
+                }
+            }
+        }
+
+        let tokens = document.get("tokenColors").and_then(|t| t.as_array()).map(Vec::as_slice).unwrap_or(&[]);
+
+        for token in tokens {
+            let settings = match token.get("settings") {
+                Some(settings) => settings,
+                None => continue,
+            };
+
+            let scopes = scopes(token.get("scope"));
+
+            for property in ["foreground", "background"].iter() {
+                let value = match settings.get(*property).and_then(|v| v.as_str()) {
+                    Some(value) => value,
+                    None => continue,
+                };
+
+                // old themes put the editor defaults in a scope-less entry
+                if scopes.is_empty() {
+                    let slot = format!("editor.{}", property);
+
+                    if theme.get(&slot).is_none() {
+                        let color = parse_color(&slot, value)?;
+                        theme.set(slot, color);
+                    }
+                }
+
+                for scope in scopes.iter() {
+                    let slot = format!("token.{}.{}", scope, property);
+                    let color = parse_color(&slot, value)?;
+
+                    theme.set(slot, color);
+                }
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = r##"{
+        // comments are allowed
+        "name": "Example Dark",
+        "type": "dark",
+        "colors": {
+            "editor.background": "#1e1e1e",
+            "editor.foreground": "#d4d4d4",
+            /* translucent */
+            "editor.selectionBackground": "#264f7880",
+        },
+        "tokenColors": [
+            {
+                "name": "Comment",
+                "scope": ["comment", "punctuation.definition.comment"],
+                "settings": { "foreground": "#6A9955", "fontStyle": "italic" }
+            },
+            {
+                "scope": "string, string.quoted",
+                "settings": { "foreground": "#ce9178", "background": "#000" }
+            },
+        ]
+    }"##;
+
+    fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    #[test]
+    fn colors() {
+        let theme = Theme::from_vscode(THEME).unwrap();
+
+        assert_eq!(theme.name.as_deref(), Some("Example Dark"));
+        assert_eq!(theme.get("editor.background"), Some(&rgb(0x1e, 0x1e, 0x1e)));
+        assert_eq!(theme.get("editor.selectionBackground"), Some(&rgb(0x26, 0x4f, 0x78)));
+    }
+
+    #[test]
+    fn token_colors() {
+        let theme = Theme::from_vscode(THEME).unwrap();
+
+        assert_eq!(theme.get("token.comment.foreground"), Some(&rgb(0x6a, 0x99, 0x55)));
+        assert_eq!(theme.get("token.punctuation.definition.comment.foreground"), Some(&rgb(0x6a, 0x99, 0x55)));
+        assert_eq!(theme.get("token.string.quoted.foreground"), Some(&rgb(0xce, 0x91, 0x78)));
+        assert_eq!(theme.get("token.string.background"), Some(&rgb(0, 0, 0)));
+        assert_eq!(theme.colors.len(), 9);
+    }
+
+    #[test]
+    fn global_token_settings() {
+        let theme = Theme::from_vscode(r##"{
+            "tokenColors": [{ "settings": { "background": "#272822", "foreground": "#f8f8f2" } }]
+        }"##).unwrap();
+
+        assert_eq!(theme.get("editor.background"), Some(&rgb(0x27, 0x28, 0x22)));
+        assert_eq!(theme.get("editor.foreground"), Some(&rgb(0xf8, 0xf8, 0xf2)));
+    }
+
+    #[test]
+    fn comment_markers_inside_strings() {
+        let theme = Theme::from_vscode(r##"{ "name": "a // b /* c */,]", "colors": {} }"##).unwrap();
+
+        assert_eq!(theme.name.as_deref(), Some("a // b /* c */,]"));
+    }
+
+    #[test]
+    fn comments_after_trailing_commas() {
+        let line = Theme::from_vscode("{ \"colors\": { \"x\": \"#1e1e1e\", // dark\n } }").unwrap();
+        let block = Theme::from_vscode(r##"{ "colors": { "x": "#1e1e1e", /* dark */ }, /* end */ }"##).unwrap();
+
+        assert_eq!(line.get("x"), Some(&rgb(0x1e, 0x1e, 0x1e)));
+        assert_eq!(block.get("x"), Some(&rgb(0x1e, 0x1e, 0x1e)));
+    }
+
+    #[test]
+    fn invalid_color() {
+        let result = Theme::from_vscode(r##"{ "colors": { "editor.background": "red" } }"##);
+
+        assert_eq!(result, Err(ThemeError::InvalidSlot { slot: "editor.background".to_string(), value: "red".to_string() }));
+    }
+
+    #[test]
+    fn non_ascii_color() {
+        for value in ["ab\u{20ac}", "#abcd\u{20ac}e"] {
+            let result = Theme::from_vscode(&format!(r#"{{ "colors": {{ "x": "{}" }} }}"#, value));
+
+            assert_eq!(result, Err(ThemeError::InvalidSlot { slot: "x".to_string(), value: value.to_string() }));
+        }
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(matches!(Theme::from_vscode("{"), Err(ThemeError::InvalidJson(_))));
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::vec::Vec;
+
+use core::fmt;
+
+/// A mistake that lenient parsing (`ParseOptions::lenient`) repaired instead of rejecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// Whitespace around the input was ignored.
+    TrimmedWhitespace,
+
+    /// The leading `#` was missing.
+    MissingHash,
+
+    /// Five hex digits were read as six with a dropped leading zero, like `#ff00a` for `#0ff00a`.
+    PaddedLeadingZero,
+
+    /// Upper and lower case were mixed, like `#Ff00aA`, or a keyword wasn't lowercase, like
+    /// `Rainbow`. Hex digits are accepted in any case even in strict mode; this is only reported.
+    MixedCase,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Warning::TrimmedWhitespace => "ignored surrounding whitespace",
+            Warning::MissingHash => "added a missing '#'",
+            Warning::PaddedLeadingZero => "added a dropped leading zero",
+            Warning::MixedCase => "mixed upper and lower case",
+        })
+    }
+}
+
+/// Everything lenient parsing repaired, in the order it was found. Empty when the input was
+/// already valid in strict mode.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    pub fn new() -> Self {
+        Warnings::default()
+    }
+
+    pub(crate) fn push(&mut self, warning: Warning) {
+        self.0.push(warning);
+    }
+
+    pub(crate) fn extend(&mut self, other: Warnings) {
+        self.0.extend(other.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.0.iter()
+    }
+
+    pub fn contains(&self, warning: Warning) -> bool {
+        self.0.contains(&warning)
+    }
+}
+
+impl IntoIterator for Warnings {
+    type Item = Warning;
+    type IntoIter = alloc::vec::IntoIter<Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
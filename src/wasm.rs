@@ -0,0 +1,40 @@
+//! `wasm-bindgen` exports, so web front-ends check colors and fills with the same parser the
+//! Rust side reads them with. See the README for building the module.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::fill::Fill;
+
+#[derive(Serialize)]
+struct Channels {
+    red: u8,
+    green: u8,
+    blue: u8,
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    // plain objects rather than `Map`s, so the result looks like the JSON it came from
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// Parses `#rgb` or `#rrggbb` into `{ red, green, blue }`. Throws if `s` isn't a hex color.
+#[wasm_bindgen]
+pub fn parse_color(s: &str) -> Result<JsValue, JsError> {
+    let color = Color::from_str(s)?;
+
+    to_js(&Channels { red: color.red, green: color.green, blue: color.blue })
+}
+
+/// Parses a fill from its JSON form, like `"rainbow"` or `["#fff", "#000"]`, and returns it
+/// the way it serializes: colors as `#rrggbb` strings and gradients as arrays or tagged objects.
+/// Throws with the deserializer's message if it's invalid.
+#[wasm_bindgen]
+pub fn parse_fill(json: &str) -> Result<JsValue, JsError> {
+    let fill: Fill = serde_json::from_str(json)?;
+
+    to_js(&fill)
+}
@@ -0,0 +1,199 @@
+//! Hot-reloading a theme file while it's being edited.
+
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::{mpsc, watch};
+
+use std::path::{Path, PathBuf};
+
+use crate::error::LoadError;
+use crate::load::parse_theme;
+use crate::theme::Theme;
+
+type Validator = Box<dyn Fn(&Theme) -> std::result::Result<(), String> + Send>;
+
+/// Watches a theme file and re-reads it whenever it changes, in any format `Theme::load`
+/// understands.
+///
+/// Every reload that parses and passes the validator is published to the `subscribe` handles;
+/// ones that don't are sent to `next_error` and the last good theme stays current. Saving the
+/// same theme again doesn't publish anything. The directory holding the file is watched rather
+/// than the file itself, so editors that save by replacing the file are followed too.
+///
+/// Watching stops when the `ThemeWatcher` is dropped.
+pub struct ThemeWatcher {
+    _watcher: notify::RecommendedWatcher,
+    themes: watch::Receiver<Theme>,
+    errors: mpsc::UnboundedReceiver<LoadError>,
+}
+
+fn read(path: &Path, validator: &Validator) -> std::result::Result<Theme, LoadError> {
+    let theme = parse_theme(path, &std::fs::read_to_string(path)?)?;
+    validator(&theme).map_err(LoadError::Rejected)?;
+
+    Ok(theme)
+}
+
+impl ThemeWatcher {
+    /// Loads the theme at `path` and starts watching it. Fails if the first load does.
+    pub fn new(path: impl AsRef<Path>) -> std::result::Result<ThemeWatcher, LoadError> {
+        ThemeWatcher::with_validator(path, |_| Ok(()))
+    }
+
+    /// Like `new`, but every version of the theme, including the first, also has to pass
+    /// `validator`, e.g. to check that the slots an application needs are all there.
+    pub fn with_validator<F>(path: impl AsRef<Path>, validator: F) -> std::result::Result<ThemeWatcher, LoadError>
+    where
+        F: Fn(&Theme) -> std::result::Result<(), String> + Send + 'static,
+    {
+        let path = std::path::absolute(path.as_ref())?;
+        let validator: Validator = Box::new(validator);
+
+        let (theme_tx, themes) = watch::channel(read(&path, &validator)?);
+        let (error_tx, errors) = mpsc::unbounded_channel();
+
+        let file: PathBuf = path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    let _ = error_tx.send(e.into());
+                    return
+                },
+            };
+
+            let written = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+            );
+            let relevant = written && event.paths.iter().any(|p| p == &file);
+
+            if !relevant {
+                return
+            }
+
+            match read(&file, &validator) {
+                Ok(theme) => {
+                    theme_tx.send_if_modified(|current| {
+                        let changed = *current != theme;
+                        *current = theme;
+
+                        changed
+                    });
+                },
+                // the file can briefly be missing while an editor swaps it out
+                Err(LoadError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {},
+                Err(e) => {
+                    let _ = error_tx.send(e);
+                },
+            }
+        })?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new("/"));
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+
+        Ok(ThemeWatcher { _watcher: watcher, themes, errors })
+    }
+
+    /// The latest theme that loaded successfully.
+    pub fn current(&self) -> Theme {
+        self.themes.borrow().clone()
+    }
+
+    /// A handle that's notified of every new theme. `changed().await` waits for the next one.
+    pub fn subscribe(&self) -> watch::Receiver<Theme> {
+        self.themes.clone()
+    }
+
+    /// Waits for the next reload that failed.
+    pub async fn next_error(&mut self) -> Option<LoadError> {
+        self.errors.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::color::Color;
+
+    fn directory(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("led-json-watch-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn theme_json(background: &str) -> String {
+        format!(r#"{{ "colors": {{ "background": "{}" }} }}"#, background)
+    }
+
+    fn background(theme: &Theme) -> Option<Color> {
+        theme.get("background").cloned()
+    }
+
+    #[tokio::test]
+    async fn publishes_changes() {
+        let dir = directory("changes");
+        let path = dir.join("theme.json");
+        std::fs::write(&path, theme_json("#000")).unwrap();
+
+        let watcher = ThemeWatcher::new(&path).unwrap();
+        let mut themes = watcher.subscribe();
+
+        assert_eq!(background(&watcher.current()), Some(Color { red: 0, green: 0, blue: 0 }));
+
+        std::fs::write(&path, theme_json("#fff")).unwrap();
+        tokio::time::timeout(Duration::from_secs(10), themes.changed()).await.unwrap().unwrap();
+
+        assert_eq!(background(&themes.borrow()), Some(Color { red: 255, green: 255, blue: 255 }));
+        assert_eq!(background(&watcher.current()), Some(Color { red: 255, green: 255, blue: 255 }));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_last_good_theme() {
+        let dir = directory("invalid");
+        let path = dir.join("theme.json");
+        std::fs::write(&path, theme_json("#000")).unwrap();
+
+        let mut watcher = ThemeWatcher::with_validator(&path, |theme| match theme.get("background") {
+            Some(_) => Ok(()),
+            None => Err("no background".to_string()),
+        }).unwrap();
+
+        std::fs::write(&path, r#"{ "colors": {} }"#).unwrap();
+
+        // the write can also be seen half done, as invalid JSON
+        let rejected = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Some(LoadError::Rejected(message)) = watcher.next_error().await {
+                    return message
+                }
+            }
+        });
+
+        assert_eq!(rejected.await.unwrap(), "no background");
+        assert_eq!(background(&watcher.current()), Some(Color { red: 0, green: 0, blue: 0 }));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn first_load_must_succeed() {
+        let dir = directory("first");
+        let path = dir.join("theme.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(ThemeWatcher::new(&path), Err(LoadError::Invalid { .. })));
+
+        std::fs::write(&path, theme_json("#000")).unwrap();
+
+        assert!(matches!(ThemeWatcher::with_validator(&path, |_| Err("never".to_string())), Err(LoadError::Rejected(_))));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use led_json::{fill_keywords, Color, CustomFill, Fill, RainbowParams};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Message {
+    pub id: u32,
+    pub fill: Fill,
+}
+
+fn fills() -> Vec<Fill> {
+    let stops = vec![Color::new(255, 0, 0), Color::new(0, 0, 255)];
+
+    vec![
+        Fill::rainbow(),
+        Fill::Rainbow(RainbowParams { period: 4.0, phase: 0.5, ..RainbowParams::DEFAULT }),
+        Fill::None,
+        Fill::Transparent,
+        Fill::Color(Color::new(255, 0, 255)),
+        Fill::Gradient(stops.clone()),
+        Fill::Radial { center: (0.25, 0.75), stops: stops.clone() },
+        Fill::Conic { angle: 90.0, stops },
+        Fill::Raw("var(--accent)".into()),
+    ]
+}
+
+#[test]
+fn bincode_round_trip() {
+    for fill in fills() {
+        let message = Message { id: 7, fill };
+        let bytes = bincode::serialize(&message).unwrap();
+
+        assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), message);
+    }
+}
+
+#[test]
+fn postcard_round_trip() {
+    for fill in fills() {
+        let message = Message { id: 7, fill };
+        let bytes = postcard::to_allocvec(&message).unwrap();
+
+        assert_eq!(postcard::from_bytes::<Message>(&bytes).unwrap(), message);
+    }
+}
+
+#[test]
+fn compact() {
+    assert_eq!(postcard::to_allocvec(&Color::new(1, 2, 3)).unwrap(), [1, 2, 3]);
+    assert_eq!(postcard::to_allocvec(&Fill::None).unwrap(), [1]);
+    assert_eq!(postcard::to_allocvec(&Fill::Color(Color::new(1, 2, 3))).unwrap(), [3, 1, 2, 3]);
+    // the tag, the stop count and the stops
+    assert_eq!(postcard::to_allocvec(&Fill::Gradient(vec![Color::new(1, 2, 3), Color::new(4, 5, 6)])).unwrap(), [4, 2, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn json_is_unchanged() {
+    assert_eq!(serde_json::to_string(&Fill::Color(Color::new(255, 0, 255))).unwrap(), r##""#ff00ff""##);
+}
+
+#[test]
+fn invalid() {
+    assert!(postcard::from_bytes::<Fill>(&[7]).is_err());
+    assert!(postcard::from_bytes::<Fill>(&[3, 1, 2]).is_err());
+    // gradients still need a stop
+    assert!(postcard::from_bytes::<Fill>(&[4, 0]).is_err());
+    // and rainbows a non-zero period
+    assert!(postcard::from_bytes::<Fill>(&[0, 0, 0, 128, 63, 0, 0, 0, 63, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+}
+
+fill_keywords! {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Effect {
+        Pulse = "pulse",
+    }
+}
+
+#[test]
+fn custom_fills() {
+    let fills = vec![
+        CustomFill::Keyword(Effect::Pulse),
+        CustomFill::Color(Color::new(255, 0, 255)),
+        CustomFill::Gradient(vec![Color::new(255, 0, 0), Color::new(0, 0, 255)]),
+    ];
+
+    for fill in fills {
+        let bytes = bincode::serialize(&fill).unwrap();
+
+        assert_eq!(bincode::deserialize::<CustomFill<Effect>>(&bytes).unwrap(), fill);
+        assert_eq!(postcard::from_bytes::<CustomFill<Effect>>(&postcard::to_allocvec(&fill).unwrap()).unwrap(), fill);
+    }
+
+    // the same form as `Fill`, with keywords as `Raw`
+    let bytes = postcard::to_allocvec(&CustomFill::<Effect>::Color(Color::new(1, 2, 3))).unwrap();
+
+    assert_eq!(bytes, [3, 1, 2, 3]);
+    assert_eq!(postcard::from_bytes::<Fill>(&bytes).unwrap(), Fill::Color(Color::new(1, 2, 3)));
+    assert!(postcard::from_bytes::<CustomFill<Effect>>(&postcard::to_allocvec(&Fill::None).unwrap()).is_err());
+    assert!(postcard::from_bytes::<CustomFill<Effect>>(&postcard::to_allocvec(&Fill::Raw("chase".into())).unwrap()).is_err());
+}
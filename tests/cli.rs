@@ -0,0 +1,63 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn color_tool(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_color-tool")).args(args).output().unwrap()
+}
+
+/// Writes `contents` to a file of that name in a directory of its own.
+fn config(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("color-tool-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+
+    path
+}
+
+#[test]
+fn validate() {
+    let good = config("good.json", r##"{ "accent": "#ff00aa", "idle": "rainbow" }"##);
+    let bad = config("bad.yaml", "theme:\n  accent: ['#ff0000', 'nope']\n");
+
+    let output = color_tool(&["validate", good.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let output = color_tool(&["validate", good.to_str().unwrap(), bad.to_str().unwrap()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stdout.starts_with(&format!("{}: /theme/accent/1: ", bad.display())), "{}", stdout);
+    assert_eq!(stdout.lines().count(), 1);
+}
+
+#[test]
+fn normalize() {
+    let file = config("normalize.json", r##"{ "accent": "#FF00AA", "bg": [0, 0, 0] }"##);
+    let output = color_tool(&["normalize", "--format", "short-hex", file.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "{\n  \"accent\": \"#f0a\",\n  \"bg\": \"#000\"\n}\n");
+}
+
+#[test]
+fn convert() {
+    let file = config("convert.toml", "accent = \"#FF00AA\"\n");
+    let output = color_tool(&["convert", "--to", "yaml", file.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "accent: '#ff00aa'\n");
+
+    let bad = config("convert.json", r##"{ "accent": "#ff00zz" }"##);
+    let output = color_tool(&["convert", "--to", "toml", bad.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("/accent: "));
+}
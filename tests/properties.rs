@@ -0,0 +1,125 @@
+//! Property tests: arbitrary colors and fills survive every round trip, and no input makes the
+//! parsers panic. `PROPTEST_CASES=10000 cargo test --test properties` for a longer run.
+
+#![cfg(feature = "std")]
+
+use proptest::prelude::*;
+use serde::de::DeserializeSeed;
+
+use std::str::FromStr;
+
+use led_json::{Color, Fill, ParseOptions, ParserCache, RainbowParams};
+
+fn color() -> impl Strategy<Value = Color> {
+    any::<(u8, u8, u8)>().prop_map(|(red, green, blue)| Color { red, green, blue })
+}
+
+fn stops() -> impl Strategy<Value = Vec<Color>> {
+    prop::collection::vec(color(), 1..8)
+}
+
+fn rainbow() -> impl Strategy<Value = RainbowParams> {
+    let period = prop_oneof![-100.0f32..-0.01, 0.01f32..100.0];
+
+    (0.0f32..=1.0, 0.0f32..=1.0, period, -10.0f32..10.0)
+        .prop_map(|(saturation, lightness, period, phase)| RainbowParams { saturation, lightness, period, phase })
+}
+
+/// Every fill that passes the default validation, so it can be deserialized again.
+fn fill() -> impl Strategy<Value = Fill> {
+    prop_oneof![
+        Just(Fill::rainbow()),
+        rainbow().prop_map(Fill::Rainbow),
+        Just(Fill::None),
+        Just(Fill::Transparent),
+        color().prop_map(Fill::Color),
+        stops().prop_map(Fill::Gradient),
+        (0.0f32..=1.0, 0.0f32..=1.0, stops()).prop_map(|(x, y, stops)| Fill::Radial { center: (x, y), stops }),
+        (-720.0f32..720.0, stops()).prop_map(|(angle, stops)| Fill::Conic { angle, stops }),
+    ]
+}
+
+/// Strings that are close to a color or fill: the right characters, in roughly the right shape.
+fn near_valid() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[ #]?(0x)?[0-9a-fA-FgG]{0,8}[ é]?",
+        "(ansi\\( ?[0-9]{0,4}\\)?|(bright[-_])?(red|blue|whit))",
+        "(rainbow|Rainbow|none|NONE|transparent)[ ]?",
+        "rainbow\\(((period|phase|saturation|speed) ?-?[0-9.]{0,4},? ?){0,3}\\)?",
+        "(linear|radial|conic)-gradient\\((at|from)? ?[0-9.deg -]{0,8},? ?(#[0-9a-f]{3},? ?){0,3}\\)?",
+        "(#[0-9a-f]{3,6}, ?){0,4}#?[0-9a-f]{0,6}",
+    ]
+}
+
+/// JSON-shaped input, balanced or not.
+fn near_json() -> impl Strategy<Value = String> {
+    "[\\[\\]{}\",:# 0-9a-fr.-]{0,40}|\\{ ?\"(type|r|stops|center|angle|rainbow)\": ?[\\[\"#0-9a-z, .\\]]{0,30}\\}"
+}
+
+proptest! {
+    #[test]
+    fn color_json_round_trips(color in color()) {
+        let json = serde_json::to_string(&color).unwrap();
+
+        prop_assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+    }
+
+    #[test]
+    fn color_string_round_trips(color in color()) {
+        prop_assert_eq!(Color::from_str(&color.to_string()).unwrap(), color);
+    }
+
+    #[test]
+    fn fill_json_round_trips(fill in fill()) {
+        let json = serde_json::to_string(&fill).unwrap();
+
+        prop_assert_eq!(serde_json::from_str::<Fill>(&json).unwrap(), fill);
+    }
+
+    #[test]
+    fn fill_string_round_trips(fill in fill()) {
+        prop_assert_eq!(Fill::from_str(&fill.to_string()).unwrap(), fill);
+    }
+
+    #[test]
+    fn cached_parsing_matches(s in near_valid()) {
+        let cache = ParserCache::new(4);
+
+        prop_assert_eq!(cache.parse(&s), Color::from_str(&s));
+        prop_assert_eq!(cache.parse(&s), Color::from_str(&s));
+    }
+
+    #[test]
+    fn lenient_accepts_what_strict_does(s in near_valid()) {
+        if let Ok(color) = Color::from_str(&s) {
+            prop_assert_eq!(Color::parse_with(&s, ParseOptions::lenient()), Ok(color));
+        }
+    }
+
+    #[test]
+    fn parsers_never_panic(s in any::<String>()) {
+        let _ = Color::from_str(&s);
+        let _ = Color::parse_with_warnings(&s, ParseOptions::lenient());
+        let _ = Fill::from_str(&s);
+        let _ = Fill::parse_with_warnings(&s, ParseOptions::lenient());
+        let _ = serde_json::from_str::<Fill>(&serde_json::to_string(&s).unwrap());
+    }
+
+    #[test]
+    fn near_valid_strings_never_panic(s in near_valid()) {
+        let _ = Color::parse_with_warnings(&s, ParseOptions::lenient());
+        let _ = Color::parse_with(&s, ParseOptions { require_hash: false, allow_0x: true, lenient: false, allow_ansi: true });
+        let _ = Fill::parse_with_warnings(&s, ParseOptions::lenient());
+
+        if let Ok(fill) = Fill::from_str(&s) {
+            prop_assert_eq!(Fill::from_str(&fill.to_string()).unwrap(), fill);
+        }
+    }
+
+    #[test]
+    fn deserializing_never_panics(json in near_json()) {
+        let _ = serde_json::from_str::<Color>(&json);
+        let _ = serde_json::from_str::<Fill>(&json);
+        let _ = ParserCache::default().fill().deserialize(&mut serde_json::Deserializer::from_str(&json));
+    }
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "toml")]
+
+use serde::{Serialize, Deserialize};
+
+use led_json::{Color, Fill, RainbowParams, Theme};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct MyData {
+    pub color: Fill,
+}
+
+fn round_trip(data: &str, expected: Fill) {
+    let v: MyData = toml::from_str(data).unwrap();
+
+    assert_eq!(v.color, expected);
+
+    let serialized = toml::to_string(&v).unwrap();
+
+    assert_eq!(toml::from_str::<MyData>(&serialized).unwrap(), v);
+}
+
+#[test]
+fn rainbow() {
+    round_trip(r#"color = "rainbow""#, Fill::rainbow());
+}
+
+#[test]
+fn rainbow_params() {
+    round_trip(r#"color = { rainbow = { period = 4.0, phase = 0.5 } }"#, Fill::Rainbow(RainbowParams { period: 4.0, phase: 0.5, ..RainbowParams::DEFAULT }));
+}
+
+#[test]
+fn none() {
+    round_trip(r#"color = "none""#, Fill::None);
+}
+
+#[test]
+fn color() {
+    round_trip(r##"color = "#f0f""##, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+}
+
+#[test]
+fn literal_string() {
+    round_trip(r##"color = '#ff00ff'"##, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+}
+
+#[test]
+fn gradient() {
+    round_trip(r##"color = ["#fff", "#00ff00", '#00f']"##, Fill::Gradient(vec![
+        Color { red: 255, green: 255, blue: 255 },
+        Color { red: 0, green: 255, blue: 0 },
+        Color { red: 0, green: 0, blue: 255 },
+    ]));
+}
+
+#[test]
+fn serialized_form() {
+    let v = MyData { color: Fill::Gradient(vec![Color { red: 255, green: 0, blue: 0 }]) };
+
+    assert_eq!(toml::to_string(&v).unwrap().trim(), r##"color = ["#ff0000"]"##);
+}
+
+#[test]
+fn invalid() {
+    assert!(toml::from_str::<MyData>(r#"color = "hello""#).is_err());
+    assert!(toml::from_str::<MyData>(r#"color = ["rainbow"]"#).is_err());
+    assert!(toml::from_str::<MyData>(r#"color = true"#).is_err());
+    assert!(toml::from_str::<MyData>(r#"color = -12"#).is_err());
+}
+
+#[test]
+fn integer_forms() {
+    let magenta = Fill::Color(Color { red: 255, green: 0, blue: 255 });
+
+    assert_eq!(toml::from_str::<MyData>("color = 0xff00ff").unwrap().color, magenta);
+    assert_eq!(toml::from_str::<MyData>("color = [255, 0, 255]").unwrap().color, magenta);
+}
+
+#[test]
+fn theme() {
+    let data = r##"
+        name = "Example"
+
+        [colors]
+        background = "#000000"
+        foreground = "#ffffff"
+    "##;
+
+    let theme: Theme = toml::from_str(data).unwrap();
+
+    assert_eq!(theme.name.as_deref(), Some("Example"));
+    assert_eq!(theme.get("foreground"), Some(&Color { red: 255, green: 255, blue: 255 }));
+    assert_eq!(toml::from_str::<Theme>(&toml::to_string(&theme).unwrap()).unwrap(), theme);
+}
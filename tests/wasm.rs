@@ -0,0 +1,54 @@
+//! Runs under node with `wasm-bindgen-test-runner`, see the README.
+
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use serde_json::{json, Value};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use led_json::wasm::{parse_color, parse_fill};
+
+fn value(js: JsValue) -> Value {
+    serde_wasm_bindgen::from_value(js).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn short_hex() {
+    assert_eq!(value(parse_color("#f0a").unwrap()), json!({ "red": 255, "green": 0, "blue": 170 }));
+}
+
+#[wasm_bindgen_test]
+fn long_hex() {
+    assert_eq!(value(parse_color("#0f1e2d").unwrap()), json!({ "red": 15, "green": 30, "blue": 45 }));
+}
+
+#[wasm_bindgen_test]
+fn invalid_hex() {
+    assert!(parse_color("f0a").is_err());
+    assert!(parse_color("#ff00g0").is_err());
+}
+
+#[wasm_bindgen_test]
+fn keyword() {
+    assert_eq!(value(parse_fill(r#""rainbow""#).unwrap()), json!("rainbow"));
+}
+
+#[wasm_bindgen_test]
+fn color_fill() {
+    assert_eq!(value(parse_fill(r#"{ "r": 255, "g": 0, "b": 255 }"#).unwrap()), json!("#ff00ff"));
+}
+
+#[wasm_bindgen_test]
+fn gradient() {
+    assert_eq!(value(parse_fill(r##"["#fff", [0, 0, 0]]"##).unwrap()), json!(["#ffffff", "#000000"]));
+
+    let radial = r##"{ "type": "radial", "center": [0.25, 0.5], "stops": ["#f00"] }"##;
+
+    assert_eq!(value(parse_fill(radial).unwrap()), json!({ "type": "radial", "center": [0.25, 0.5], "stops": ["#ff0000"] }));
+}
+
+#[wasm_bindgen_test]
+fn invalid_fill() {
+    assert!(parse_fill("[]").is_err());
+    assert!(parse_fill(r#""black""#).is_err());
+}
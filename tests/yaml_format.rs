@@ -0,0 +1,95 @@
+#![cfg(feature = "yaml")]
+
+use serde::{Serialize, Deserialize};
+
+use led_json::{Color, Fill, RainbowParams, Theme};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct MyData {
+    pub color: Fill,
+}
+
+fn round_trip(data: &str, expected: Fill) {
+    let v: MyData = serde_yaml::from_str(data).unwrap();
+
+    assert_eq!(v.color, expected);
+
+    let serialized = serde_yaml::to_string(&v).unwrap();
+
+    assert_eq!(serde_yaml::from_str::<MyData>(&serialized).unwrap(), v);
+}
+
+#[test]
+fn rainbow() {
+    round_trip("color: rainbow", Fill::rainbow());
+}
+
+#[test]
+fn rainbow_params() {
+    round_trip("color:\n  rainbow:\n    period: 4.0\n    phase: 0.5", Fill::Rainbow(RainbowParams { period: 4.0, phase: 0.5, ..RainbowParams::DEFAULT }));
+}
+
+#[test]
+fn transparent() {
+    round_trip("color: transparent", Fill::Transparent);
+}
+
+#[test]
+fn color() {
+    // `#` starts a comment in YAML, so colors have to be quoted
+    round_trip(r##"color: "#f0f""##, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+    round_trip(r##"color: '#ff00ff'"##, Fill::Color(Color { red: 255, green: 0, blue: 255 }));
+}
+
+#[test]
+fn flow_gradient() {
+    round_trip(r##"color: ["#fff", "#00ff00"]"##, Fill::Gradient(vec![
+        Color { red: 255, green: 255, blue: 255 },
+        Color { red: 0, green: 255, blue: 0 },
+    ]));
+}
+
+#[test]
+fn block_gradient() {
+    let data = r##"
+color:
+  - "#fff"
+  - '#00ff00'
+"##;
+
+    round_trip(data, Fill::Gradient(vec![
+        Color { red: 255, green: 255, blue: 255 },
+        Color { red: 0, green: 255, blue: 0 },
+    ]));
+}
+
+#[test]
+fn invalid() {
+    assert!(serde_yaml::from_str::<MyData>("color: hello").is_err());
+    assert!(serde_yaml::from_str::<MyData>("color: [rainbow]").is_err());
+    assert!(serde_yaml::from_str::<MyData>("color: true").is_err());
+    assert!(serde_yaml::from_str::<MyData>("color: #fff").is_err());
+}
+
+#[test]
+fn integer_forms() {
+    let magenta = Fill::Color(Color { red: 255, green: 0, blue: 255 });
+
+    assert_eq!(serde_yaml::from_str::<MyData>("color: 16711935").unwrap().color, magenta);
+    assert_eq!(serde_yaml::from_str::<MyData>("color: [255, 0, 255]").unwrap().color, magenta);
+}
+
+#[test]
+fn theme() {
+    let data = r##"
+name: Example
+colors:
+  background: "#000"
+  foreground: "#ffffff"
+"##;
+
+    let theme: Theme = serde_yaml::from_str(data).unwrap();
+
+    assert_eq!(theme.get("background"), Some(&Color { red: 0, green: 0, blue: 0 }));
+    assert_eq!(serde_yaml::from_str::<Theme>(&serde_yaml::to_string(&theme).unwrap()).unwrap(), theme);
+}